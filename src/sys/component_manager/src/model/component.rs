@@ -1725,12 +1725,12 @@ impl ResolvedInstanceState {
                 | fdecl::Offer::EventStream(fdecl::OfferEventStream { target, .. }) => {
                     if target.is_some() {
                         return Err(ModelError::dynamic_offer_invalid(
-                            cm_fidl_validator::error::ErrorList {
-                                errs: vec![cm_fidl_validator::error::Error::extraneous_field(
+                            cm_fidl_validator::error::ErrorList::from(
+                                cm_fidl_validator::error::Error::extraneous_field(
                                     "OfferDecl",
                                     "target",
-                                )],
-                            },
+                                ),
+                            ),
                         ));
                     }
                 }
@@ -2,10 +2,20 @@
 // Use of this source code is governed by a BSD-style license that can be
 // found in the LICENSE file.
 
-use {std::fmt, thiserror::Error};
+use {
+    std::collections::{BTreeMap, HashMap},
+    std::fmt,
+    std::io,
+    thiserror::Error,
+};
 
 /// Enum type that can represent any error encountered during validation.
-#[derive(Debug, Error, PartialEq, Clone)]
+///
+/// New variants are added as validation grows, so the enum is `#[non_exhaustive]`: downstream
+/// crates must include a wildcard arm when matching, or branch on [`Error::code`] /
+/// [`Error::category`] instead of the variants themselves.
+#[derive(Debug, Error, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[non_exhaustive]
 pub enum Error {
     #[error("Field `{}` is missing for {}.", .0.field, .0.decl)]
     MissingField(DeclField),
@@ -19,6 +29,9 @@ pub enum Error {
     #[error("\"{1}\" is duplicated for field `{}` in {}.", .0.field, .0.decl)]
     DuplicateField(DeclField, String),
 
+    #[error("\"{1}\" is duplicated for field `{}` in {}: first declared at index {2}, duplicated at index {3}.", .0.field, .0.decl)]
+    DuplicateFieldAtIndex(DeclField, String, usize, usize),
+
     #[error("Field `{}` for {} is invalid.",  .0.field, .0.decl)]
     InvalidField(DeclField),
 
@@ -28,9 +41,18 @@ pub enum Error {
     #[error("Field `{}` for {} is too long. The field must be up to {1} characters.", .0.field, .0.decl)]
     FieldTooLong(DeclField, usize),
 
+    #[error("Field `{}` for {} has too many path segments. The path must have at most {1} segments.", .0.field, .0.decl)]
+    FieldTooDeep(DeclField, usize),
+
     #[error("\"{0}\" cannot declare a capability of type `{1}`.")]
     InvalidCapabilityType(DeclField, String),
 
+    #[error("\"{0}\" declares a capability of type `{1}`, which is deprecated at the targeted API level.")]
+    DeprecatedCapabilityType(DeclField, String),
+
+    #[error("{0} contains an unknown FIDL variant (ordinal {1}).")]
+    UnknownVariant(DeclField, String),
+
     #[error("\"{0}\" target \"{1}\" is same as source.")]
     OfferTargetEqualsSource(String, String),
 
@@ -49,6 +71,9 @@ pub enum Error {
     #[error("\"{1}\" is referenced in {0} but it does not appear in capabilities.")]
     InvalidCapability(DeclField, String),
 
+    #[error("\"{1}\" is referenced in {0} but it is not a capability of type `{2}`.")]
+    CapabilityTypeMismatch(DeclField, String, String),
+
     #[error("\"{1}\" is referenced in {0} but it does not appear in runners.")]
     InvalidRunner(DeclField, String),
 
@@ -72,21 +97,232 @@ pub enum Error {
 
     #[error("The `availability` field in {0} for {1} must be set to \"optional\" because the source is \"void\".")]
     AvailabilityMustBeOptional(DeclField, String),
+
+    #[error("\"{1}\" is not allowed to set `{}` to \"reboot\" in {}.", .0.field, .0.decl)]
+    OnTerminateNotAllowed(DeclField, String),
+
+    #[error("Field `{}` for {} requests rights that the source directory does not declare.", .0.field, .0.decl)]
+    RightsEscalation(DeclField),
+
+    #[error("Field `{}` for {} names a backing directory whose rights include execute, which storage may not have.", .0.field, .0.decl)]
+    InvalidStorageBackingRights(DeclField),
+
+    #[error("{} extends \"none\" but registers no runners, resolvers, or debug capabilities; children placed in it cannot be resolved or run.", .0.decl)]
+    EmptyEnvironment(DeclField),
+
     #[error("Invalid aggregate offer: {0}")]
     InvalidAggregateOffer(String),
+
+    #[error("Declaration holds {0} entries across its lists, exceeding the configured maximum.")]
+    TooManyDeclarations(usize),
+}
+
+/// Coarse-grained groups of related [`Error`] variants, for bucketing diagnostics in
+/// summary reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// A field is missing, empty, duplicated, or shouldn't be present.
+    Structure,
+    /// A field references a child, collection, capability, etc. that isn't declared.
+    Reference,
+    /// A name, path, URL, or other field value is malformed.
+    Naming,
+    /// The declarations are individually valid but interact badly, e.g. dependency
+    /// cycles, overlapping paths, or an offer that targets its own source.
+    Topology,
 }
 
 impl Error {
+    /// Returns the [`ErrorCategory`] this error belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::MissingField(_)
+            | Error::EmptyField(_)
+            | Error::ExtraneousField(_)
+            | Error::DuplicateField(_, _)
+            | Error::DuplicateFieldAtIndex(_, _, _, _)
+            | Error::InvalidCapabilityType(_, _)
+            | Error::DeprecatedCapabilityType(_, _)
+            | Error::UnknownVariant(_, _)
+            | Error::ExtraneousSourcePath(_, _)
+            | Error::NestedVector
+            | Error::EmptyEnvironment(_)
+            | Error::TooManyDeclarations(_) => ErrorCategory::Structure,
+            Error::InvalidChild(_, _)
+            | Error::InvalidCollection(_, _)
+            | Error::InvalidStorage(_, _)
+            | Error::InvalidEnvironment(_, _)
+            | Error::InvalidCapability(_, _)
+            | Error::CapabilityTypeMismatch(_, _, _)
+            | Error::InvalidRunner(_, _)
+            | Error::EventStreamEventNotFound(_, _) => ErrorCategory::Reference,
+            Error::InvalidField(_)
+            | Error::InvalidUrl(_, _)
+            | Error::FieldTooLong(_, _)
+            | Error::FieldTooDeep(_, _)
+            | Error::AvailabilityMustBeOptional(_, _)
+            | Error::OnTerminateNotAllowed(_, _) => ErrorCategory::Naming,
+            Error::DependencyCycle(_)
+            | Error::InvalidPathOverlap { .. }
+            | Error::PkgPathOverlap { .. }
+            | Error::OfferTargetEqualsSource(_, _)
+            | Error::RightsEscalation(_)
+            | Error::InvalidStorageBackingRights(_)
+            | Error::InvalidAggregateOffer(_) => ErrorCategory::Topology,
+        }
+    }
+
+    /// Returns a short stable identifier for this error's variant (e.g. `"missing_field"`),
+    /// suitable for grouping and counting diagnostics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::MissingField(_) => "missing_field",
+            Error::EmptyField(_) => "empty_field",
+            Error::ExtraneousField(_) => "extraneous_field",
+            Error::DuplicateField(_, _) => "duplicate_field",
+            Error::DuplicateFieldAtIndex(_, _, _, _) => "duplicate_field_at_index",
+            Error::InvalidField(_) => "invalid_field",
+            Error::InvalidUrl(_, _) => "invalid_url",
+            Error::FieldTooLong(_, _) => "field_too_long",
+            Error::FieldTooDeep(_, _) => "field_too_deep",
+            Error::InvalidCapabilityType(_, _) => "invalid_capability_type",
+            Error::DeprecatedCapabilityType(_, _) => "deprecated_capability_type",
+            Error::UnknownVariant(_, _) => "unknown_variant",
+            Error::OfferTargetEqualsSource(_, _) => "offer_target_equals_source",
+            Error::InvalidChild(_, _) => "invalid_child",
+            Error::InvalidCollection(_, _) => "invalid_collection",
+            Error::InvalidStorage(_, _) => "invalid_storage",
+            Error::InvalidEnvironment(_, _) => "invalid_environment",
+            Error::InvalidCapability(_, _) => "invalid_capability",
+            Error::CapabilityTypeMismatch(_, _, _) => "capability_type_mismatch",
+            Error::InvalidRunner(_, _) => "invalid_runner",
+            Error::EventStreamEventNotFound(_, _) => "event_stream_event_not_found",
+            Error::DependencyCycle(_) => "dependency_cycle",
+            Error::InvalidPathOverlap { .. } => "invalid_path_overlap",
+            Error::PkgPathOverlap { .. } => "pkg_path_overlap",
+            Error::ExtraneousSourcePath(_, _) => "extraneous_source_path",
+            Error::NestedVector => "nested_vector",
+            Error::AvailabilityMustBeOptional(_, _) => "availability_must_be_optional",
+            Error::OnTerminateNotAllowed(_, _) => "on_terminate_not_allowed",
+            Error::RightsEscalation(_) => "rights_escalation",
+            Error::InvalidStorageBackingRights(_) => "invalid_storage_backing_rights",
+            Error::EmptyEnvironment(_) => "empty_environment",
+            Error::InvalidAggregateOffer(_) => "invalid_aggregate_offer",
+            Error::TooManyDeclarations(_) => "too_many_declarations",
+        }
+    }
+
+    /// Returns the [`DeclField`] naming the declaration and field this error is about, if
+    /// the variant carries one. Errors that describe whole-manifest problems (e.g.
+    /// [`Error::DependencyCycle`]) return `None`.
+    pub fn decl_field(&self) -> Option<&DeclField> {
+        match self {
+            Error::MissingField(decl_field)
+            | Error::EmptyField(decl_field)
+            | Error::ExtraneousField(decl_field)
+            | Error::DuplicateField(decl_field, _)
+            | Error::DuplicateFieldAtIndex(decl_field, _, _, _)
+            | Error::InvalidField(decl_field)
+            | Error::InvalidUrl(decl_field, _)
+            | Error::FieldTooLong(decl_field, _)
+            | Error::FieldTooDeep(decl_field, _)
+            | Error::InvalidCapabilityType(decl_field, _)
+            | Error::DeprecatedCapabilityType(decl_field, _)
+            | Error::UnknownVariant(decl_field, _)
+            | Error::InvalidChild(decl_field, _)
+            | Error::InvalidCollection(decl_field, _)
+            | Error::InvalidStorage(decl_field, _)
+            | Error::InvalidEnvironment(decl_field, _)
+            | Error::InvalidCapability(decl_field, _)
+            | Error::CapabilityTypeMismatch(decl_field, _, _)
+            | Error::InvalidRunner(decl_field, _)
+            | Error::EventStreamEventNotFound(decl_field, _)
+            | Error::InvalidPathOverlap { decl: decl_field, .. }
+            | Error::PkgPathOverlap { decl: decl_field, .. }
+            | Error::ExtraneousSourcePath(decl_field, _)
+            | Error::AvailabilityMustBeOptional(decl_field, _)
+            | Error::OnTerminateNotAllowed(decl_field, _)
+            | Error::RightsEscalation(decl_field)
+            | Error::InvalidStorageBackingRights(decl_field)
+            | Error::EmptyEnvironment(decl_field) => Some(decl_field),
+            Error::OfferTargetEqualsSource(_, _)
+            | Error::DependencyCycle(_)
+            | Error::NestedVector
+            | Error::InvalidAggregateOffer(_)
+            | Error::TooManyDeclarations(_) => None,
+        }
+    }
+
+    /// Mutable counterpart of [`Error::decl_field`], used by [`Error::with_index`].
+    fn decl_field_mut(&mut self) -> Option<&mut DeclField> {
+        match self {
+            Error::MissingField(decl_field)
+            | Error::EmptyField(decl_field)
+            | Error::ExtraneousField(decl_field)
+            | Error::DuplicateField(decl_field, _)
+            | Error::DuplicateFieldAtIndex(decl_field, _, _, _)
+            | Error::InvalidField(decl_field)
+            | Error::InvalidUrl(decl_field, _)
+            | Error::FieldTooLong(decl_field, _)
+            | Error::FieldTooDeep(decl_field, _)
+            | Error::InvalidCapabilityType(decl_field, _)
+            | Error::DeprecatedCapabilityType(decl_field, _)
+            | Error::UnknownVariant(decl_field, _)
+            | Error::InvalidChild(decl_field, _)
+            | Error::InvalidCollection(decl_field, _)
+            | Error::InvalidStorage(decl_field, _)
+            | Error::InvalidEnvironment(decl_field, _)
+            | Error::InvalidCapability(decl_field, _)
+            | Error::CapabilityTypeMismatch(decl_field, _, _)
+            | Error::InvalidRunner(decl_field, _)
+            | Error::EventStreamEventNotFound(decl_field, _)
+            | Error::InvalidPathOverlap { decl: decl_field, .. }
+            | Error::PkgPathOverlap { decl: decl_field, .. }
+            | Error::ExtraneousSourcePath(decl_field, _)
+            | Error::AvailabilityMustBeOptional(decl_field, _)
+            | Error::OnTerminateNotAllowed(decl_field, _)
+            | Error::RightsEscalation(decl_field)
+            | Error::InvalidStorageBackingRights(decl_field)
+            | Error::EmptyEnvironment(decl_field) => Some(decl_field),
+            Error::OfferTargetEqualsSource(_, _)
+            | Error::DependencyCycle(_)
+            | Error::NestedVector
+            | Error::InvalidAggregateOffer(_)
+            | Error::TooManyDeclarations(_) => None,
+        }
+    }
+
+    /// Records `index` — the offending declaration's position within its list — on this
+    /// error's [`DeclField`], so that loops iterating over e.g. `uses` can annotate errors
+    /// without a separate constructor per variant. Errors that don't carry a [`DeclField`]
+    /// are returned unchanged.
+    pub fn with_index(mut self, index: usize) -> Self {
+        if let Some(decl_field) = self.decl_field_mut() {
+            decl_field.index = Some(index);
+        }
+        self
+    }
+
+    /// Returns the name of the declaration this error is about, if known. See [`Error::decl_field`].
+    pub fn decl(&self) -> Option<&str> {
+        self.decl_field().map(|decl_field| decl_field.decl.as_str())
+    }
+
+    /// Returns the name of the field this error is about, if known. See [`Error::decl_field`].
+    pub fn field(&self) -> Option<&str> {
+        self.decl_field().map(|decl_field| decl_field.field.as_str())
+    }
+
     pub fn missing_field(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
-        Error::MissingField(DeclField { decl: decl_type.into(), field: keyword.into() })
+        Error::MissingField(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
     }
 
     pub fn empty_field(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
-        Error::EmptyField(DeclField { decl: decl_type.into(), field: keyword.into() })
+        Error::EmptyField(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
     }
 
     pub fn extraneous_field(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
-        Error::ExtraneousField(DeclField { decl: decl_type.into(), field: keyword.into() })
+        Error::ExtraneousField(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
     }
 
     pub fn duplicate_field(
@@ -95,13 +331,28 @@ impl Error {
         value: impl Into<String>,
     ) -> Self {
         Error::DuplicateField(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             value.into(),
         )
     }
 
+    pub fn duplicate_field_at_index(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        value: impl Into<String>,
+        first_index: usize,
+        duplicate_index: usize,
+    ) -> Self {
+        Error::DuplicateFieldAtIndex(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            value.into(),
+            first_index,
+            duplicate_index,
+        )
+    }
+
     pub fn invalid_field(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
-        Error::InvalidField(DeclField { decl: decl_type.into(), field: keyword.into() })
+        Error::InvalidField(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
     }
 
     pub fn invalid_url(
@@ -110,14 +361,14 @@ impl Error {
         message: &str,
     ) -> Self {
         Error::InvalidUrl(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             message.into(),
         )
     }
 
     pub fn field_too_long(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
         Error::FieldTooLong(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             cm_types::MAX_NAME_LENGTH,
         )
     }
@@ -127,7 +378,18 @@ impl Error {
         keyword: impl Into<String>,
         max: usize,
     ) -> Self {
-        Error::FieldTooLong(DeclField { decl: decl_type.into(), field: keyword.into() }, max)
+        Error::FieldTooLong(DeclField { decl: decl_type.into(), field: keyword.into(), index: None }, max)
+    }
+
+    pub fn field_too_deep(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        max_depth: usize,
+    ) -> Self {
+        Error::FieldTooDeep(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            max_depth,
+        )
     }
 
     pub fn invalid_capability_type(
@@ -136,11 +398,33 @@ impl Error {
         type_name: impl Into<String>,
     ) -> Self {
         Error::InvalidCapabilityType(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            type_name.into(),
+        )
+    }
+
+    pub fn deprecated_capability_type(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        type_name: impl Into<String>,
+    ) -> Self {
+        Error::DeprecatedCapabilityType(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             type_name.into(),
         )
     }
 
+    pub fn unknown_variant(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        ordinal: u64,
+    ) -> Self {
+        Error::UnknownVariant(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            ordinal.to_string(),
+        )
+    }
+
     pub fn offer_target_equals_source(decl: impl Into<String>, target: impl Into<String>) -> Self {
         Error::OfferTargetEqualsSource(decl.into(), target.into())
     }
@@ -151,7 +435,7 @@ impl Error {
         child: impl Into<String>,
     ) -> Self {
         Error::InvalidChild(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             child.into(),
         )
     }
@@ -162,7 +446,7 @@ impl Error {
         collection: impl Into<String>,
     ) -> Self {
         Error::InvalidCollection(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             collection.into(),
         )
     }
@@ -173,7 +457,7 @@ impl Error {
         storage: impl Into<String>,
     ) -> Self {
         Error::InvalidStorage(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             storage.into(),
         )
     }
@@ -184,7 +468,7 @@ impl Error {
         environment: impl Into<String>,
     ) -> Self {
         Error::InvalidEnvironment(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             environment.into(),
         )
     }
@@ -196,7 +480,7 @@ impl Error {
         runner: impl Into<String>,
     ) -> Self {
         Error::InvalidRunner(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             runner.into(),
         )
     }
@@ -207,18 +491,31 @@ impl Error {
         capability: impl Into<String>,
     ) -> Self {
         Error::InvalidCapability(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             capability.into(),
         )
     }
 
+    pub fn capability_type_mismatch(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        capability: impl Into<String>,
+        type_name: impl Into<String>,
+    ) -> Self {
+        Error::CapabilityTypeMismatch(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            capability.into(),
+            type_name.into(),
+        )
+    }
+
     pub fn event_stream_event_not_found(
         decl_type: impl Into<String>,
         keyword: impl Into<String>,
         event_name: impl Into<String>,
     ) -> Self {
         Error::EventStreamEventNotFound(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             event_name.into(),
         )
     }
@@ -234,23 +531,23 @@ impl Error {
         other_path: impl Into<String>,
     ) -> Self {
         Error::InvalidPathOverlap {
-            decl: DeclField { decl: decl.into(), field: "target_path".to_string() },
+            decl: DeclField { decl: decl.into(), field: "target_path".to_string(), index: None },
             path: path.into(),
-            other_decl: DeclField { decl: other_decl.into(), field: "target_path".to_string() },
+            other_decl: DeclField { decl: other_decl.into(), field: "target_path".to_string(), index: None },
             other_path: other_path.into(),
         }
     }
 
     pub fn pkg_path_overlap(decl: impl Into<String>, path: impl Into<String>) -> Self {
         Error::PkgPathOverlap {
-            decl: DeclField { decl: decl.into(), field: "target_path".to_string() },
+            decl: DeclField { decl: decl.into(), field: "target_path".to_string(), index: None },
             path: path.into(),
         }
     }
 
     pub fn extraneous_source_path(decl_type: impl Into<String>, path: impl Into<String>) -> Self {
         Error::ExtraneousSourcePath(
-            DeclField { decl: decl_type.into(), field: "source_path".to_string() },
+            DeclField { decl: decl_type.into(), field: "source_path".to_string(), index: None },
             path.into(),
         )
     }
@@ -265,7 +562,7 @@ impl Error {
         source_name: Option<&String>,
     ) -> Self {
         Error::AvailabilityMustBeOptional(
-            DeclField { decl: decl_type.into(), field: keyword.into() },
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
             source_name.cloned().unwrap_or("<unnamed>".to_string()),
         )
     }
@@ -273,34 +570,181 @@ impl Error {
     pub fn invalid_aggregate_offer(info: impl Into<String>) -> Self {
         Error::InvalidAggregateOffer(info.into())
     }
+
+    pub fn too_many_declarations(count: usize) -> Self {
+        Error::TooManyDeclarations(count)
+    }
+
+    pub fn empty_environment(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
+        Error::EmptyEnvironment(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
+    }
+
+    pub fn rights_escalation(decl_type: impl Into<String>, keyword: impl Into<String>) -> Self {
+        Error::RightsEscalation(DeclField { decl: decl_type.into(), field: keyword.into(), index: None })
+    }
+
+    pub fn invalid_storage_backing_rights(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+    ) -> Self {
+        Error::InvalidStorageBackingRights(DeclField {
+            decl: decl_type.into(),
+            field: keyword.into(),
+            index: None,
+        })
+    }
+
+    pub fn on_terminate_not_allowed(
+        decl_type: impl Into<String>,
+        keyword: impl Into<String>,
+        child: impl Into<String>,
+    ) -> Self {
+        Error::OnTerminateNotAllowed(
+            DeclField { decl: decl_type.into(), field: keyword.into(), index: None },
+            child.into(),
+        )
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+/// Ordered by `decl`, then `field`, then `index`, so that sorted error lists group by
+/// declaration.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct DeclField {
     pub decl: String,
     pub field: String,
+    /// The position of the offending declaration within its list (e.g. which `use` in
+    /// `uses`), when the caller recorded one via [`Error::with_index`].
+    pub index: Option<usize>,
 }
 
 impl fmt::Display for DeclField {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}.{}", &self.decl, &self.field)
+        match self.index {
+            Some(index) => write!(f, "{}[{}].{}", &self.decl, index, &self.field),
+            None => write!(f, "{}.{}", &self.decl, &self.field),
+        }
+    }
+}
+
+/// A half-open byte range `[start, end)` in manifest source text. Spans are produced by
+/// whatever front end compiled the manifest; validation only carries them through. See
+/// `validate_with_spans`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Maps declaration fields to their [`Span`]s in the source the decl was compiled from.
+/// Population is entirely up to the caller; fields without an entry simply yield errors
+/// without a span. Spans are keyed by declaration type and field name (matching
+/// [`DeclField`]), so all declarations of one type share a field's span — precise enough
+/// for underlining the offending field in small manifests.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SpanMap {
+    spans: HashMap<(String, String), Span>,
+}
+
+impl SpanMap {
+    pub fn new() -> Self {
+        Self::default()
     }
+
+    /// Records the source span of `field` in declarations of type `decl`.
+    pub fn insert(&mut self, decl: impl Into<String>, field: impl Into<String>, span: Span) {
+        self.spans.insert((decl.into(), field.into()), span);
+    }
+
+    /// Looks up the span recorded for the declaration field an error names, if any.
+    pub fn get(&self, decl_field: &DeclField) -> Option<Span> {
+        self.spans.get(&(decl_field.decl.clone(), decl_field.field.clone())).copied()
+    }
+}
+
+/// The result of `validate_with_spans`: each [`Error`], paired with the source [`Span`] of
+/// the field it names when the caller's [`SpanMap`] had one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedErrorList {
+    pub errs: Vec<(Error, Option<Span>)>,
 }
 
 /// Represents a list of errors encountered during validation.
 #[derive(Debug, Error, PartialEq, Clone)]
 pub struct ErrorList {
     pub errs: Vec<Error>,
+    /// Prepended to the `Display` output when set; see [`ErrorList::prefixed`].
+    prefix: Option<String>,
 }
 
 impl ErrorList {
     pub(crate) fn new(errs: Vec<Error>) -> ErrorList {
-        ErrorList { errs }
+        ErrorList { errs, prefix: None }
+    }
+
+    /// Returns this list with `prefix` (typically the path of the manifest that failed
+    /// validation) prepended to its `Display` output, e.g.
+    /// `meta/foo.cm: Field \`source\` is missing for UseProtocol.`.
+    pub fn prefixed(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Returns whether the list contains exactly `error`. Useful for asserting on one
+    /// specific error without pinning the full list.
+    pub fn contains(&self, error: &Error) -> bool {
+        self.errs.contains(error)
+    }
+
+    /// Returns whether the list contains any error whose [`Error::code`] is `code`. Useful
+    /// for asserting on a class of error without being sensitive to ordering or to
+    /// unrelated errors.
+    pub fn contains_code(&self, code: &str) -> bool {
+        self.errs.iter().any(|error| error.code() == code)
+    }
+
+    /// Returns a histogram of the contained errors, keyed by [`Error::code`].
+    pub fn summary(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for err in &self.errs {
+            *counts.entry(err.code()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Returns one line per contained error, sorted, suitable for printing to a terminal. Unlike
+    /// the `Display` impl, which joins all errors with ", ", each entry stands on its own.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.errs.iter().map(|e| format!("{}", e)).collect();
+        lines.sort();
+        lines
+    }
+
+    /// Writes the output of [`ErrorList::to_lines`] to `w`, one error per line.
+    pub fn print_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        for line in self.to_lines() {
+            writeln!(w, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Error> for ErrorList {
+    fn from(err: Error) -> Self {
+        ErrorList::new(vec![err])
+    }
+}
+
+impl From<Vec<Error>> for ErrorList {
+    fn from(errs: Vec<Error>) -> Self {
+        ErrorList::new(errs)
     }
 }
 
 impl fmt::Display for ErrorList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = &self.prefix {
+            write!(f, "{}: ", prefix)?;
+        }
         let strs: Vec<String> = self.errs.iter().map(|e| format!("{}", e)).collect();
         write!(f, "{}", strs.join(", "))
     }
@@ -310,6 +754,50 @@ impl fmt::Display for ErrorList {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_with_index() {
+        let error = Error::invalid_child("Decl", "source", "child").with_index(3);
+        assert_eq!(
+            error.decl_field(),
+            Some(&DeclField {
+                decl: "Decl".to_string(),
+                field: "source".to_string(),
+                index: Some(3)
+            })
+        );
+        assert_eq!(
+            format!("{}", error),
+            "\"child\" is referenced in Decl[3].source but it does not appear in children."
+        );
+
+        // Errors without a `DeclField` pass through unchanged.
+        let error = Error::dependency_cycle("{{...}}".to_string()).with_index(7);
+        assert_eq!(error.decl_field(), None);
+    }
+
+    #[test]
+    fn test_error_ordering() {
+        // Shuffled: later variants, and within a variant later decls/fields, sort after.
+        let mut errors = vec![
+            Error::invalid_field("Decl", "field"),
+            Error::missing_field("Decl", "z_field"),
+            Error::duplicate_field("Decl", "field", "b"),
+            Error::missing_field("Decl", "a_field"),
+            Error::duplicate_field("Decl", "field", "a"),
+        ];
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                Error::missing_field("Decl", "a_field"),
+                Error::missing_field("Decl", "z_field"),
+                Error::duplicate_field("Decl", "field", "a"),
+                Error::duplicate_field("Decl", "field", "b"),
+                Error::invalid_field("Decl", "field"),
+            ]
+        );
+    }
+
     #[test]
     fn test_errors() {
         assert_eq!(
@@ -332,6 +820,10 @@ mod tests {
             format!("{}", Error::field_too_long_with_max("Decl", "keyword", 100)),
             "Field `keyword` for Decl is too long. The field must be up to 100 characters."
         );
+        assert_eq!(
+            format!("{}", Error::field_too_deep("Decl", "keyword", 4)),
+            "Field `keyword` for Decl has too many path segments. The path must have at most 4 segments."
+        );
         assert_eq!(
             format!("{}", Error::invalid_child("Decl", "source", "child")),
             "\"child\" is referenced in Decl.source but it does not appear in children."
@@ -345,4 +837,135 @@ mod tests {
             "\"name\" is referenced in Decl.source but it does not appear in storage."
         );
     }
+
+    #[test]
+    fn test_decl_field_accessors() {
+        let err = Error::missing_field("Child", "name");
+        assert_eq!(err.decl(), Some("Child"));
+        assert_eq!(err.field(), Some("name"));
+
+        let err = Error::invalid_path_overlap("UseDirectory", "/data", "UseStorage", "/data/sub");
+        assert_eq!(err.decl(), Some("UseDirectory"));
+        assert_eq!(err.field(), Some("target_path"));
+
+        let err = Error::dependency_cycle("{{self -> child a -> self}}".to_string());
+        assert_eq!(err.decl_field(), None);
+        assert_eq!(err.decl(), None);
+        assert_eq!(err.field(), None);
+
+        assert_eq!(Error::offer_target_equals_source("OfferProtocol", "child").decl(), None);
+    }
+
+    #[test]
+    fn test_error_list_contains() {
+        let errors = ErrorList::new(vec![
+            Error::missing_field("Child", "name"),
+            Error::duplicate_field("Collection", "name", "coll"),
+        ]);
+        assert!(errors.contains(&Error::missing_field("Child", "name")));
+        assert!(!errors.contains(&Error::missing_field("Child", "url")));
+        assert!(errors.contains_code("duplicate_field"));
+        assert!(!errors.contains_code("invalid_field"));
+    }
+
+    #[test]
+    fn test_error_list_from() {
+        let err = Error::missing_field("Child", "name");
+        assert_eq!(ErrorList::from(err.clone()), ErrorList::new(vec![err.clone()]));
+
+        let errs = vec![err.clone(), Error::missing_field("Child", "url")];
+        assert_eq!(ErrorList::from(errs.clone()), ErrorList::new(errs));
+    }
+
+    #[test]
+    fn test_error_list_summary() {
+        let errors = ErrorList::new(vec![
+            Error::missing_field("Child", "name"),
+            Error::missing_field("Child", "url"),
+            Error::duplicate_field("Collection", "name", "coll"),
+        ]);
+        let summary = errors.summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary.get("missing_field"), Some(&2));
+        assert_eq!(summary.get("duplicate_field"), Some(&1));
+    }
+
+    #[test]
+    fn test_error_list_to_lines() {
+        let errors = ErrorList::new(vec![
+            Error::missing_field("Decl", "url"),
+            Error::missing_field("Decl", "name"),
+        ]);
+        assert_eq!(
+            errors.to_lines(),
+            vec![
+                "Field `name` is missing for Decl.".to_string(),
+                "Field `url` is missing for Decl.".to_string(),
+            ]
+        );
+        let mut out = Vec::new();
+        errors.print_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Field `name` is missing for Decl.\nField `url` is missing for Decl.\n"
+        );
+    }
+
+    // `Error` is `#[non_exhaustive]`; the constructors are the supported way to build each
+    // variant. This keeps them all buildable (and their codes populated) as variants evolve.
+    #[test]
+    fn test_error_constructors_buildable() {
+        let errors = vec![
+            Error::missing_field("Decl", "keyword"),
+            Error::empty_field("Decl", "keyword"),
+            Error::extraneous_field("Decl", "keyword"),
+            Error::duplicate_field("Decl", "keyword", "foo"),
+            Error::duplicate_field_at_index("Decl", "keyword", "foo", 0, 1),
+            Error::invalid_field("Decl", "keyword"),
+            Error::invalid_url("Decl", "keyword", "bad-url"),
+            Error::field_too_long("Decl", "keyword"),
+            Error::field_too_long_with_max("Decl", "keyword", 100),
+            Error::field_too_deep("Decl", "keyword", 4),
+            Error::invalid_capability_type("Decl", "keyword", "event"),
+            Error::deprecated_capability_type("Decl", "keyword", "event"),
+            Error::unknown_variant("Decl", "keyword", 1),
+            Error::offer_target_equals_source("Decl", "child"),
+            Error::invalid_child("Decl", "keyword", "child"),
+            Error::invalid_collection("Decl", "keyword", "coll"),
+            Error::invalid_storage("Decl", "keyword", "data"),
+            Error::invalid_environment("Decl", "keyword", "env"),
+            Error::invalid_capability("Decl", "keyword", "cap"),
+            Error::capability_type_mismatch("Decl", "keyword", "cap", "protocol"),
+            Error::invalid_runner("Decl", "keyword", "elf"),
+            Error::event_stream_event_not_found("Decl", "keyword", "started"),
+            Error::dependency_cycle("{{self -> self}}".to_string()),
+            Error::invalid_path_overlap("Decl", "/foo", "OtherDecl", "/foo/bar"),
+            Error::pkg_path_overlap("Decl", "/pkg"),
+            Error::extraneous_source_path("Decl", "/path"),
+            Error::nested_vector(),
+            Error::availability_must_be_optional("Decl", "keyword", Some(&"cap".to_string())),
+            Error::invalid_aggregate_offer("info"),
+            Error::empty_environment("Decl", "keyword"),
+            Error::rights_escalation("Decl", "keyword"),
+            Error::invalid_storage_backing_rights("Decl", "keyword"),
+            Error::on_terminate_not_allowed("Decl", "keyword", "child"),
+        ];
+        for error in &errors {
+            assert!(!error.code().is_empty(), "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_error_categories() {
+        assert_eq!(Error::missing_field("Decl", "keyword").category(), ErrorCategory::Structure);
+        assert_eq!(
+            Error::invalid_child("Decl", "source", "child").category(),
+            ErrorCategory::Reference
+        );
+        assert_eq!(Error::invalid_field("Decl", "keyword").category(), ErrorCategory::Naming);
+        assert_eq!(
+            Error::dependency_cycle("{{self -> child a -> self}}".to_string()).category(),
+            ErrorCategory::Topology
+        );
+    }
 }
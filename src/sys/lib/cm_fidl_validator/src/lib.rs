@@ -10,9 +10,10 @@ use {
     crate::{error::*, util::*},
     directed_graph::DirectedGraph,
     fidl_fuchsia_component_config as fconfig, fidl_fuchsia_component_decl as fdecl,
+    fidl_fuchsia_data as fdata, fidl_fuchsia_io as fio,
     itertools::Itertools,
     std::{
-        collections::{HashMap, HashSet},
+        collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
         fmt,
         path::Path,
     },
@@ -120,20 +121,668 @@ pub fn validate_values_data(data: &fconfig::ValuesData) -> Result<(), ErrorList>
 ///   component that exposed them.
 ///
 /// All checks are local to this Component.
+///
+/// Validation is total: for any structurally-decoded Component — including adversarial input —
+/// this returns `Err` rather than panicking.
 pub fn validate(decl: &fdecl::Component) -> Result<(), ErrorList> {
-    let ctx = ValidationContext::default();
+    validate_with_options(decl, ValidationOptions::default())
+}
+
+/// Validates a Component like [`validate`], additionally returning warnings: diagnostics for
+/// declarations that are legal but usually indicate a mistake. Warnings never cause
+/// validation to fail and are returned even when it does.
+pub fn validate_with_warnings(decl: &fdecl::Component) -> (Result<(), ErrorList>, Vec<Error>) {
+    validate_with_options_and_warnings(decl, ValidationOptions::default())
+}
+
+/// Validates a Component like [`validate_with_warnings`], with the given
+/// [`ValidationOptions`]. Some options (e.g. `extraneous_as_warning`) change which
+/// diagnostics land in the warning list.
+pub fn validate_with_options_and_warnings(
+    decl: &fdecl::Component,
+    options: ValidationOptions,
+) -> (Result<(), ErrorList>, Vec<Error>) {
+    let mut ctx = ValidationContext { options, ..ValidationContext::default() };
+    let result = ctx.validate(decl, None).map_err(|errs| ErrorList::new(errs));
+    (result, std::mem::take(&mut ctx.warnings))
+}
+
+/// Validates a Component like [`validate`], additionally invoking `sink` once per error, in
+/// the order the errors were found. Errors reported directly by the validator are delivered
+/// as validation proceeds; errors appended by shared field-checking helpers are delivered at
+/// the next direct report (or at the end of validation), still in order. The full
+/// [`ErrorList`] is returned as usual, so the sink sees exactly the errors the result holds.
+pub fn validate_with_sink(
+    decl: &fdecl::Component,
+    sink: &mut dyn FnMut(&Error),
+) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext { sink: Some(sink), ..ValidationContext::default() };
+    ctx.validate(decl, None).map_err(|errs| ErrorList::new(errs))
+}
+
+/// Validates a Component like [`validate`], prefixing any resulting error list's `Display`
+/// output with `name` — typically the manifest's path — so tools that validate many files
+/// can report which one failed without reformatting the list themselves.
+pub fn validate_named(name: &str, decl: &fdecl::Component) -> Result<(), ErrorList> {
+    validate(decl).map_err(|errors| errors.prefixed(name))
+}
+
+/// Validates a Component like [`validate`], pairing each error with the source [`Span`] of
+/// the field it names, as recorded in `spans` by the front end that compiled the manifest.
+/// Errors whose field has no recorded span (or that name no field at all) carry `None`.
+pub fn validate_with_spans(
+    decl: &fdecl::Component,
+    spans: &SpanMap,
+) -> Result<(), SpannedErrorList> {
+    validate(decl).map_err(|errors| SpannedErrorList {
+        errs: errors
+            .errs
+            .into_iter()
+            .map(|error| {
+                let span = error.decl_field().and_then(|decl_field| spans.get(decl_field));
+                (error, span)
+            })
+            .collect(),
+    })
+}
+
+/// Validates a Component like [`validate`], rendering failures as a multi-section report
+/// suitable for printing to a terminal: errors grouped under their [`ErrorCategory`], one
+/// per line, followed by a histogram of error codes from [`ErrorList::summary`].
+pub fn validate_and_report(decl: &fdecl::Component) -> Result<(), String> {
+    let errors = match validate(decl) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors,
+    };
+    let mut report = format!(
+        "Found {} validation error{}.\n",
+        errors.errs.len(),
+        if errors.errs.len() == 1 { "" } else { "s" }
+    );
+    for category in [
+        ErrorCategory::Structure,
+        ErrorCategory::Reference,
+        ErrorCategory::Naming,
+        ErrorCategory::Topology,
+    ] {
+        let mut lines: Vec<String> = errors
+            .errs
+            .iter()
+            .filter(|error| error.category() == category)
+            .map(|error| error.to_string())
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        lines.sort();
+        report.push_str(&format!("\n{:?}:\n", category));
+        for line in lines {
+            report.push_str(&format!("  {}\n", line));
+        }
+    }
+    report.push_str("\nError counts by code:\n");
+    for (code, count) in errors.summary() {
+        report.push_str(&format!("  {}: {}\n", code, count));
+    }
+    Err(report)
+}
+
+/// Incremental re-validation for callers (e.g. editors) that validate the same declaration
+/// repeatedly as one section changes. [`Validator::with_decl`] builds the cross-section
+/// context — children, collections, capability names, environment names — once; each
+/// `revalidate_*` method then re-runs only its own section against that cached context,
+/// reporting the same errors [`validate`] would report for that section.
+///
+/// The cached context reflects the declaration as it was when the `Validator` was built,
+/// and errors in the shared sections themselves (e.g. a malformed child) are reported by
+/// [`validate`], not here. Rebuild the `Validator` when anything outside the revalidated
+/// section changes.
+pub struct Validator<'a> {
+    decl: &'a fdecl::Component,
+    ctx: ValidationContext<'a>,
+}
+
+impl<'a> Validator<'a> {
+    /// Builds the shared validation context from `decl`'s children, collections,
+    /// capabilities, and environments.
+    pub fn with_decl(decl: &'a fdecl::Component) -> Self {
+        let mut ctx = ValidationContext::default();
+        if let Some(envs) = &decl.environments {
+            ctx.collect_environment_names(&envs);
+        }
+        if let Some(children) = decl.children.as_ref() {
+            for (index, child) in children.iter().enumerate() {
+                ctx.validate_child_decl(&child, index);
+            }
+        }
+        if let Some(collections) = decl.collections.as_ref() {
+            for collection in collections {
+                ctx.validate_collection_decl(&collection);
+            }
+        }
+        if let Some(capabilities) = decl.capabilities.as_ref() {
+            for capability in capabilities {
+                ctx.validate_capability_decl(capability, false);
+            }
+        }
+        // The collection passes double as validators; their errors belong to the full
+        // `validate` run, not to any section revalidated here.
+        ctx.errors.clear();
+        ctx.warnings.clear();
+        ctx.strong_dependencies = Default::default();
+        ctx.dependency_edge_capabilities.clear();
+        ctx.dependency_edges.clear();
+        Validator { decl, ctx }
+    }
+
+    /// Re-validates only `uses`.
+    pub fn revalidate_uses(&mut self) -> Result<(), ErrorList> {
+        let decl = self.decl;
+        self.run_section(|ctx| {
+            if let Some(uses) = decl.uses.as_ref() {
+                ctx.validate_use_decls(uses);
+            }
+        })
+    }
+
+    /// Re-validates only `exposes`.
+    pub fn revalidate_exposes(&mut self) -> Result<(), ErrorList> {
+        let decl = self.decl;
+        self.run_section(|ctx| {
+            if let Some(exposes) = decl.exposes.as_ref() {
+                let mut target_ids = HashMap::new();
+                for expose in exposes.iter() {
+                    ctx.validate_expose_decl(&expose, &mut target_ids);
+                }
+            }
+        })
+    }
+
+    /// Re-validates only `offers`, including cycle detection over the dependency edges
+    /// the offers introduce. Edges contributed by other sections (e.g. environments) are
+    /// not rebuilt here, so a cycle that runs through them is only caught by [`validate`].
+    pub fn revalidate_offers(&mut self) -> Result<(), ErrorList> {
+        let decl = self.decl;
+        self.run_section(|ctx| {
+            if let Some(offers) = decl.offers.as_ref() {
+                for offer in offers.iter() {
+                    ctx.validate_offers_decl(&offer, OfferType::Static);
+                }
+                ctx.validate_offer_group(&offers);
+            }
+            if let Err(e) = ctx.strong_dependencies.topological_sort() {
+                let message = ctx.format_cycles_with_capabilities(&e);
+                ctx.push_error(Error::dependency_cycle(message));
+            }
+        })
+    }
+
+    /// Runs one section against the cached context, then resets the per-run state it may
+    /// have accumulated so the next call starts clean.
+    fn run_section(
+        &mut self,
+        section: impl FnOnce(&mut ValidationContext<'a>),
+    ) -> Result<(), ErrorList> {
+        section(&mut self.ctx);
+        self.ctx.target_ids.clear();
+        // Used events and event streams are collected while validating `uses`, so they are
+        // per-run state here, unlike the declaration-backed sets built by `with_decl`.
+        self.ctx.all_events.clear();
+        self.ctx.all_event_streams.clear();
+        self.ctx.strong_dependencies = Default::default();
+        self.ctx.dependency_edge_capabilities.clear();
+        self.ctx.dependency_edges.clear();
+        let errors = std::mem::take(&mut self.ctx.errors);
+        self.ctx.next_unreported = 0;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ErrorList::new(errors))
+        }
+    }
+}
+
+/// Options that adjust the behavior of [`validate`]. The `Default` value performs exactly the
+/// checks that `validate` does.
+#[derive(Debug, Default, Clone)]
+pub struct ValidationOptions {
+    /// When set, a `Child` may only declare `on_terminate` as
+    /// [`fdecl::OnTerminate::Reboot`] if its name appears in this set. "Reboot-on-terminate"
+    /// is security-sensitive, so policy-aware callers can restrict it to an allowlist; when
+    /// `None` the field is not checked.
+    pub reboot_on_terminate_allowlist: Option<HashSet<String>>,
+    /// When `true`, unknown FIDL union variants (e.g. a `Use` added at a newer API level) are
+    /// reported as [`Error::UnknownVariant`] carrying the unknown ordinal, rather than the
+    /// generic error each list falls back to. Useful when debugging forward-compatibility
+    /// issues; default behavior is unchanged.
+    pub reject_unknown: bool,
+    /// The platform API level to validate against. Some capability types and fields are only
+    /// legal at certain API levels (e.g. event capabilities are deprecated at
+    /// [`EVENT_DEPRECATION_API_LEVEL`]); when `None`, no level-specific checks run.
+    pub api_level: Option<u32>,
+    /// When set, a `UseStorage`'s `source_name` must appear either in this set or among the
+    /// component's own storage declarations. Callers that can enumerate the storage
+    /// capabilities offered to the component (e.g. root realm validation) use this to catch
+    /// uses of storage that nothing provides; when `None` the name is not cross-checked.
+    pub known_storage_names: Option<HashSet<String>>,
+    /// When `true`, `Child.url` is measured against its length limit in Unicode code
+    /// points (`chars().count()`) rather than bytes, so multibyte characters don't consume
+    /// the limit faster than they read. Default remains byte length for compatibility.
+    pub count_chars: bool,
+    /// When `true`, child and collection names that differ only in ASCII case (e.g. `Col`
+    /// and `col`) are reported as duplicates, since they collide on case-insensitive
+    /// filesystems. Names that are exact duplicates are always reported regardless of this
+    /// option.
+    pub case_insensitive_names: bool,
+    /// When `true`, runner `target_name`s must be unique across all of the component's
+    /// environments, not just within each environment. Two environments registering the same
+    /// runner name is legal but often suspicious, since the name routes differently depending
+    /// on which environment a child lands in.
+    pub strict_runner_names: bool,
+    /// When `true`, validation ends as soon as any check fails and the resulting
+    /// [`ErrorList`] holds only the first error. Useful for callers that only need a
+    /// valid/invalid answer (e.g. interactive editing) and don't want to pay for the
+    /// full error list on a badly broken declaration.
+    pub stop_on_first_error: bool,
+    /// When `true`, the `source_path`s of this component's own protocol and directory
+    /// capabilities are checked for overlap against the `target_path`s of its uses, in
+    /// addition to the per-section checks. Both sets of paths land in the program's
+    /// namespace, so e.g. declaring a directory at `/data` while using one at `/data/sub`
+    /// is a collision even though each section is valid on its own.
+    pub strict_path_overlap: bool,
+    /// When set, a declaration whose `uses`, `offers`, `exposes`, `children`, `collections`,
+    /// and `capabilities` lists together hold more than this many entries is rejected up
+    /// front with a single [`Error::TooManyDeclarations`], before any per-entry work runs.
+    /// Guards against resource exhaustion when validating untrusted manifests; when `None`
+    /// no limit applies.
+    pub max_decls: Option<usize>,
+    /// When set, paths and `subdir` values with more than this many `/`-separated segments
+    /// are rejected with [`Error::FieldTooDeep`]. Deeply nested paths are legal but can
+    /// cause problems for consumers downstream (e.g. when joined onto other paths); when
+    /// `None` depth is unlimited.
+    pub max_path_depth: Option<usize>,
+    /// When `true`, [`Error::ExtraneousField`] is recorded as a warning (surfaced by
+    /// `validate_with_warnings`) rather than a fatal error. Useful for staged migrations
+    /// where generated manifests still carry harmless leftover fields (e.g. a `collection`
+    /// on a static child ref); the fields are still flagged, but don't fail validation.
+    pub extraneous_as_warning: bool,
+    /// When `true`, storage capabilities are accepted as built-ins and validated like any
+    /// other storage declaration. Builtin storage isn't currently allowed by default — though
+    /// there's no deep reason for this — so the flag lets experimenting callers opt in
+    /// without relaxing the check for everyone.
+    pub allow_builtin_storage: bool,
+    /// When `true`, a [`Error::DependencyCycle`] error is moved to the front of the
+    /// resulting [`ErrorList`]. Cycle detection runs last, so the cycle error is otherwise
+    /// appended after every structural error and easy to miss; developers debugging routing
+    /// usually want it first.
+    pub cycle_first: bool,
+}
+
+impl ValidationOptions {
+    /// Options for validating an ordinary component declaration: exactly the checks
+    /// [`validate`] performs. Spelled out so call sites can say what they're validating
+    /// rather than reaching for `Default`.
+    pub fn for_component() -> Self {
+        Self::default()
+    }
+
+    /// Options for validating framework builtin declarations (used with
+    /// [`validate_capabilities_with_options`] and `as_builtin = true`). Builtin storage is
+    /// accepted, since the framework itself is the one declaring it.
+    pub fn for_builtin() -> Self {
+        Self { allow_builtin_storage: true, ..Self::default() }
+    }
+
+    /// The strictest validation this crate offers: unknown FIDL variants are reported with
+    /// their ordinal, names that collide case-insensitively are duplicates, runner names
+    /// must be unique across environments, and capability source paths may not overlap use
+    /// target paths. New optional checks are fair game to add here, so a declaration that
+    /// passes `strict()` today may not tomorrow.
+    pub fn strict() -> Self {
+        Self {
+            reject_unknown: true,
+            case_insensitive_names: true,
+            strict_runner_names: true,
+            strict_path_overlap: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Validates a Component with the given [`ValidationOptions`]. See [`validate`].
+pub fn validate_with_options(
+    decl: &fdecl::Component,
+    options: ValidationOptions,
+) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext { options, ..ValidationContext::default() };
     ctx.validate(decl, None).map_err(|errs| ErrorList::new(errs))
 }
 
-/// Validates a list of Capabilities independently.
+/// Validates a set of labeled Components (e.g. every manifest in a package) and reports the
+/// errors for each Component that failed, keyed by the caller-supplied label. Components that
+/// validate successfully don't appear in the result; an empty map means everything passed.
+pub fn validate_all<'a>(
+    decls: impl IntoIterator<Item = (&'a str, &'a fdecl::Component)>,
+) -> BTreeMap<String, ErrorList> {
+    let mut failures = BTreeMap::new();
+    for (label, decl) in decls {
+        if let Err(errs) = validate(decl) {
+            failures.insert(label.to_string(), errs);
+        }
+    }
+    failures
+}
+
+/// Builds the strong-dependency graph that `validate` uses for cycle detection, returning its
+/// edges with nodes stringified via `DependencyNode`'s `Display` impl. This is useful for
+/// tooling that wants to render a component's internal capability-routing graph (e.g. as
+/// Graphviz) rather than only learning whether validation passed.
+///
+/// Returns the errors from `validate` if the Component is invalid.
+pub fn build_dependency_graph(
+    decl: &fdecl::Component,
+) -> Result<Vec<(String, String)>, ErrorList> {
+    let mut ctx = ValidationContext::default();
+    ctx.validate(decl, None).map_err(|errs| ErrorList::new(errs))?;
+    Ok(ctx
+        .dependency_edges
+        .iter()
+        .map(|(source, target)| (source.to_string(), target.to_string()))
+        .collect())
+}
+
+/// The kind of capability a [`Route`] carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapabilityKind {
+    Service,
+    Protocol,
+    Directory,
+    Storage,
+    Runner,
+    Resolver,
+    Event,
+    EventStream,
+}
+
+/// A summary of where one capability comes from and where it goes, extracted from a
+/// component's offers and exposes by [`list_routes`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route<'a> {
+    pub kind: CapabilityKind,
+    /// The capability's `source_name`.
+    pub name: String,
+    /// The routing source. `None` when the source doesn't participate in the dependency
+    /// graph (parent, framework, debug), mirroring `DependencyNode::try_from_ref`.
+    pub source: Option<DependencyNode<'a>>,
+    /// Every graph-participating target the capability is routed to. Targets outside the
+    /// graph (e.g. an expose's parent) are omitted, so an expose typically has none.
+    pub targets: Vec<DependencyNode<'a>>,
+}
+
+/// Lists the capability routes declared by `decl`'s offers and exposes, grouped by kind,
+/// name, and source, in declaration order. This is read-only metadata extraction for
+/// documentation and visualization tools; it does not validate the declaration.
+pub fn list_routes(decl: &fdecl::Component) -> Vec<Route<'_>> {
+    fn add_route<'a>(
+        routes: &mut Vec<Route<'a>>,
+        kind: CapabilityKind,
+        name: Option<&'a String>,
+        source: Option<&'a fdecl::Ref>,
+        target: Option<&'a fdecl::Ref>,
+    ) {
+        let name = match name {
+            Some(name) => name,
+            None => return,
+        };
+        let source = DependencyNode::try_from_ref(source);
+        let target = DependencyNode::try_from_ref(target);
+        match routes
+            .iter_mut()
+            .find(|route| route.kind == kind && route.name == *name && route.source == source)
+        {
+            Some(route) => {
+                if let Some(target) = target {
+                    if !route.targets.contains(&target) {
+                        route.targets.push(target);
+                    }
+                }
+            }
+            None => routes.push(Route {
+                kind,
+                name: name.clone(),
+                source,
+                targets: target.into_iter().collect(),
+            }),
+        }
+    }
+
+    let mut routes = Vec::new();
+    for offer in decl.offers.iter().flatten() {
+        match offer {
+            fdecl::Offer::Service(o) => add_route(
+                &mut routes,
+                CapabilityKind::Service,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Protocol(o) => add_route(
+                &mut routes,
+                CapabilityKind::Protocol,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Directory(o) => add_route(
+                &mut routes,
+                CapabilityKind::Directory,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Storage(o) => add_route(
+                &mut routes,
+                CapabilityKind::Storage,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Runner(o) => add_route(
+                &mut routes,
+                CapabilityKind::Runner,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Resolver(o) => add_route(
+                &mut routes,
+                CapabilityKind::Resolver,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::Event(o) => add_route(
+                &mut routes,
+                CapabilityKind::Event,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            fdecl::Offer::EventStream(o) => add_route(
+                &mut routes,
+                CapabilityKind::EventStream,
+                o.source_name.as_ref(),
+                o.source.as_ref(),
+                o.target.as_ref(),
+            ),
+            _ => {}
+        }
+    }
+    for expose in decl.exposes.iter().flatten() {
+        match expose {
+            fdecl::Expose::Service(e) => add_route(
+                &mut routes,
+                CapabilityKind::Service,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            fdecl::Expose::Protocol(e) => add_route(
+                &mut routes,
+                CapabilityKind::Protocol,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            fdecl::Expose::Directory(e) => add_route(
+                &mut routes,
+                CapabilityKind::Directory,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            fdecl::Expose::Runner(e) => add_route(
+                &mut routes,
+                CapabilityKind::Runner,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            fdecl::Expose::Resolver(e) => add_route(
+                &mut routes,
+                CapabilityKind::Resolver,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            fdecl::Expose::EventStream(e) => add_route(
+                &mut routes,
+                CapabilityKind::EventStream,
+                e.source_name.as_ref(),
+                e.source.as_ref(),
+                e.target.as_ref(),
+            ),
+            _ => {}
+        }
+    }
+    routes
+}
+
+/// Checks only the dependency topology of a ComponentDecl: builds the strong dependency graph
+/// and reports any cycles, discarding every other class of error. Intended for callers that
+/// have already validated the decl structurally and want a quick answer to "does this set of
+/// routes introduce a cycle?"; a structurally invalid decl may be missing edges here, so this
+/// is not a substitute for [`validate`].
+pub fn validate_dependencies(decl: &fdecl::Component) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext::default();
+    match ctx.validate(decl, None) {
+        Ok(()) => Ok(()),
+        Err(errs) => {
+            let cycles: Vec<Error> =
+                errs.into_iter().filter(|e| matches!(e, Error::DependencyCycle(_))).collect();
+            if cycles.is_empty() {
+                Ok(())
+            } else {
+                Err(ErrorList::new(cycles))
+            }
+        }
+    }
+}
+
+/// Canonicalizes `decl` so that equivalent manifests produced by different front ends
+/// compare equal: sorts `uses`, `exposes`, `offers`, `children`, `collections` and
+/// `capabilities` into a stable order and drops entries that are exact duplicates of an
+/// earlier one. Conflicting (non-equal) entries are kept; reporting those is [`validate`]'s
+/// job. Performs no validation of its own.
+pub fn normalize(decl: &mut fdecl::Component) {
+    fn normalize_list<T: fmt::Debug + PartialEq>(list: &mut Option<Vec<T>>) {
+        if let Some(list) = list.as_mut() {
+            list.sort_by_cached_key(|entry| format!("{:?}", entry));
+            list.dedup();
+        }
+    }
+    normalize_list(&mut decl.uses);
+    normalize_list(&mut decl.exposes);
+    normalize_list(&mut decl.offers);
+    normalize_list(&mut decl.children);
+    normalize_list(&mut decl.collections);
+    normalize_list(&mut decl.capabilities);
+}
+
+/// The outcome of [`validate_detailed`]: the errors and warnings from a full validation pass,
+/// plus the capability and child names collected along the way (sorted, so two reports for the
+/// same declaration compare equal and diff cleanly across runs).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ValidationReport {
+    pub errors: Vec<Error>,
+    pub warnings: Vec<Error>,
+    pub capabilities: Vec<String>,
+    pub children: Vec<String>,
+}
+
+/// Validates a ComponentDecl as [`validate`] does, but returns a [`ValidationReport`] capturing
+/// the collected capability and child sets alongside any errors and warnings, rather than just
+/// pass/fail.
+pub fn validate_detailed(decl: &fdecl::Component) -> ValidationReport {
+    let mut ctx = ValidationContext::default();
+    let errors = match ctx.validate(decl, None) {
+        Ok(()) => vec![],
+        Err(errs) => errs,
+    };
+    let mut capabilities: Vec<String> =
+        ctx.all_capability_ids.iter().map(|name| name.to_string()).collect();
+    capabilities.sort_unstable();
+    let mut children: Vec<String> = ctx.all_children.keys().map(|name| name.to_string()).collect();
+    children.sort_unstable();
+    ValidationReport {
+        errors,
+        warnings: std::mem::take(&mut ctx.warnings),
+        capabilities,
+        children,
+    }
+}
+
+/// Validates a single Capability independently. Equivalent to [`validate_capabilities`] with a
+/// one-element list; duplicate-name detection is vacuous for a single declaration.
+pub fn validate_capability(
+    capability: &fdecl::Capability,
+    as_builtin: bool,
+) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext::default();
+    ctx.validate_capability_decl(capability, as_builtin);
+    if ctx.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorList::new(ctx.errors))
+    }
+}
+
+/// Validates a list of Capabilities independently. For non-builtin capabilities this also
+/// checks that `source_path`s don't overlap, the way [`ValidationContext::validate_use_paths`]
+/// checks use target paths: a directory rooted at `/data` and another at `/data/sub` would
+/// collide in the providing component's outgoing directory.
 pub fn validate_capabilities(
     capabilities: &Vec<fdecl::Capability>,
     as_builtin: bool,
 ) -> Result<(), ErrorList> {
-    let mut ctx = ValidationContext::default();
+    validate_capabilities_with_options(capabilities, as_builtin, ValidationOptions::default())
+}
+
+/// Validates a list of Capabilities like [`validate_capabilities`], with the given
+/// [`ValidationOptions`]. Some options (e.g. `allow_builtin_storage`) change which
+/// capability types are accepted as built-ins.
+pub fn validate_capabilities_with_options(
+    capabilities: &Vec<fdecl::Capability>,
+    as_builtin: bool,
+    options: ValidationOptions,
+) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext { options, ..ValidationContext::default() };
     for capability in capabilities {
         ctx.validate_capability_decl(capability, as_builtin);
     }
+    if !as_builtin {
+        check_capability_source_paths(capabilities, &mut ctx.errors);
+    }
     if ctx.errors.is_empty() {
         Ok(())
     } else {
@@ -141,12 +790,165 @@ pub fn validate_capabilities(
     }
 }
 
+/// Checks that the `source_path`s of protocol and directory capabilities don't partially
+/// overlap. Exact duplicates are allowed — two capabilities may alias the same path under
+/// different names — but one path nested inside another would make the shallower entry
+/// shadow the deeper one.
+fn check_capability_source_paths(capabilities: &[fdecl::Capability], errors: &mut Vec<Error>) {
+    let mut source_paths: Vec<(&String, &str, &Path)> = Vec::new();
+    for capability in capabilities {
+        match capability {
+            fdecl::Capability::Protocol(fdecl::Protocol { source_path: Some(path), .. }) => {
+                // Protocols overlap through their parent directory, like use paths do.
+                if let Some(dir) = Path::new(path).parent() {
+                    source_paths.push((path, "Protocol", dir));
+                }
+            }
+            fdecl::Capability::Directory(fdecl::Directory { source_path: Some(path), .. }) => {
+                source_paths.push((path, "Directory", Path::new(path)));
+            }
+            _ => {}
+        }
+    }
+    for (&(path_a, decl_a, dir_a), &(path_b, decl_b, dir_b)) in
+        source_paths.iter().tuple_combinations()
+    {
+        if path_a == path_b {
+            continue;
+        }
+        if match (decl_a, decl_b) {
+            // Two protocols may share a parent directory, but one path may not nest
+            // inside the other.
+            ("Protocol", "Protocol") => {
+                dir_a != dir_b && (dir_a.starts_with(dir_b) || dir_b.starts_with(dir_a))
+            }
+            // Anything involving a directory may not be the same as or nest inside it.
+            _ => dir_a.starts_with(dir_b) || dir_b.starts_with(dir_a),
+        } {
+            errors.push(Error::invalid_path_overlap(decl_a, path_a, decl_b, path_b));
+        }
+    }
+}
+
+/// A fluent builder for [`fdecl::Component`], for tests and tools that construct declarations
+/// programmatically without spelling out every table field. Only the common declaration shapes
+/// have helpers; anything else can be set on the result of [`ComponentDeclBuilder::build`].
+#[derive(Debug)]
+pub struct ComponentDeclBuilder {
+    decl: fdecl::Component,
+}
+
+impl ComponentDeclBuilder {
+    pub fn new() -> Self {
+        Self { decl: fdecl::Component::EMPTY }
+    }
+
+    /// Adds a lazily-started static child.
+    pub fn child(mut self, name: &str, url: &str) -> Self {
+        self.decl.children.get_or_insert_with(Vec::new).push(fdecl::Child {
+            name: Some(name.to_string()),
+            url: Some(url.to_string()),
+            startup: Some(fdecl::StartupMode::Lazy),
+            on_terminate: None,
+            environment: None,
+            ..fdecl::Child::EMPTY
+        });
+        self
+    }
+
+    /// Adds a collection with the given durability.
+    pub fn collection(mut self, name: &str, durability: fdecl::Durability) -> Self {
+        self.decl.collections.get_or_insert_with(Vec::new).push(fdecl::Collection {
+            name: Some(name.to_string()),
+            durability: Some(durability),
+            ..fdecl::Collection::EMPTY
+        });
+        self
+    }
+
+    /// Adds a use-protocol declaration with a strong dependency on `source`.
+    pub fn use_protocol(mut self, source: fdecl::Ref, source_name: &str, target_path: &str) -> Self {
+        self.decl.uses.get_or_insert_with(Vec::new).push(fdecl::Use::Protocol(
+            fdecl::UseProtocol {
+                dependency_type: Some(fdecl::DependencyType::Strong),
+                source: Some(source),
+                source_name: Some(source_name.to_string()),
+                target_path: Some(target_path.to_string()),
+                ..fdecl::UseProtocol::EMPTY
+            },
+        ));
+        self
+    }
+
+    /// Adds an offer-protocol declaration with a strong dependency on `source`.
+    pub fn offer_protocol(
+        mut self,
+        source: fdecl::Ref,
+        source_name: &str,
+        target: fdecl::Ref,
+        target_name: &str,
+    ) -> Self {
+        self.decl.offers.get_or_insert_with(Vec::new).push(fdecl::Offer::Protocol(
+            fdecl::OfferProtocol {
+                source: Some(source),
+                source_name: Some(source_name.to_string()),
+                target: Some(target),
+                target_name: Some(target_name.to_string()),
+                dependency_type: Some(fdecl::DependencyType::Strong),
+                ..fdecl::OfferProtocol::EMPTY
+            },
+        ));
+        self
+    }
+
+    /// Validates the built Component with [`validate`] and returns it.
+    pub fn build(self) -> Result<fdecl::Component, ErrorList> {
+        validate(&self.decl)?;
+        Ok(self.decl)
+    }
+
+    /// Returns the built Component without validating it.
+    pub fn build_unvalidated(self) -> fdecl::Component {
+        self.decl
+    }
+}
+
+impl Default for ComponentDeclBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The platform API level at which event capabilities are deprecated. When
+/// [`ValidationOptions::api_level`] targets this level or above, `use event` declarations are
+/// rejected.
+const EVENT_DEPRECATION_API_LEVEL: u32 = 10;
+
+/// The protocol served by the framework for administering a storage capability. Using it from a
+/// `Ref::Capability` is only meaningful when the referenced capability is a storage declaration.
+const STORAGE_ADMIN_PROTOCOL_NAME: &str = "fuchsia.sys2.StorageAdmin";
+
 /// An interface to call into either `check_dynamic_name()` or `check_name()`, depending on the context
 /// of the caller.
 type CheckChildNameFn = fn(Option<&String>, &str, &str, &mut Vec<Error>) -> bool;
 
+/// Validates a Child being created at runtime in a collection. Performs the same validation
+/// on it as `validate_child`, except that the longer dynamic name limit applies and
+/// `startup: Eager` is rejected: eager startup only applies to static children, since there
+/// is no parent resolution step at which the framework would start a dynamic child.
 pub fn validate_dynamic_child(child: &fdecl::Child) -> Result<(), ErrorList> {
-    validate_child(child, check_dynamic_name)
+    let mut errors = match validate_child(child, check_dynamic_name, false) {
+        Ok(()) => vec![],
+        Err(list) => list.errs,
+    };
+    if child.startup == Some(fdecl::StartupMode::Eager) {
+        errors.push(Error::invalid_field("Child", "startup"));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorList::new(errors))
+    }
 }
 
 /// Validates an independent Child. Performs the same validation on it as `validate`. A
@@ -154,10 +956,24 @@ pub fn validate_dynamic_child(child: &fdecl::Child) -> Result<(), ErrorList> {
 fn validate_child(
     child: &fdecl::Child,
     check_child_name: CheckChildNameFn,
+    count_chars: bool,
 ) -> Result<(), ErrorList> {
     let mut errors = vec![];
     check_child_name(child.name.as_ref(), "Child", "name", &mut errors);
-    check_url(child.url.as_ref(), "Child", "url", &mut errors);
+    let url_ok = if count_chars {
+        check_url_chars(child.url.as_ref(), "Child", "url", &mut errors)
+    } else {
+        check_url(child.url.as_ref(), "Child", "url", &mut errors)
+    };
+    // A relative URL's fragment names a resource within the enclosing package, e.g.
+    // `#meta/child.cm`. An empty fragment or an empty path segment can't name a resource.
+    if url_ok {
+        if let Some(fragment) = child.url.as_ref().and_then(|url| url.strip_prefix('#')) {
+            if fragment.is_empty() || fragment.split('/').any(|segment| segment.is_empty()) {
+                errors.push(Error::invalid_field("Child", "url"));
+            }
+        }
+    }
     if child.startup.is_none() {
         errors.push(Error::missing_field("Child", "startup"));
     }
@@ -168,63 +984,226 @@ fn validate_child(
     if errors.is_empty() {
         Ok(())
     } else {
-        Err(ErrorList { errs: errors })
+        Err(ErrorList::new(errors))
+    }
+}
+
+/// Validates an independent Collection. Performs the same validation on it as `validate`,
+/// except that the environment cross-reference is skipped: a standalone Collection doesn't
+/// carry the set of environments the enclosing component declares, so `environment` is only
+/// checked for being a well-formed name.
+pub fn validate_collection(collection: &fdecl::Collection) -> Result<(), ErrorList> {
+    let mut errors = vec![];
+    check_name(collection.name.as_ref(), "Collection", "name", &mut errors);
+    if collection.durability.is_none() {
+        errors.push(Error::missing_field("Collection", "durability"));
+    }
+    if collection.environment.is_some() {
+        check_name(collection.environment.as_ref(), "Collection", "environment", &mut errors);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorList::new(errors))
     }
 }
 
 /// Validates a collection of dynamic offers. Dynamic offers differ from static
 /// offers, in that
 ///
-/// 1. a dynamic offer's `target` field must be omitted;
-/// 2. a dynamic offer's `source` _may_ be a dynamic child;
-/// 3. since this crate isn't really designed to handle dynamic children, we
+/// 1. a dynamic offer's `source` and `target` _may_ be dynamic children (a
+///    `ChildRef` with `collection` set); `target` is still required, for every
+///    offer kind, just as it is for static offers;
+/// 2. since this crate isn't really designed to handle dynamic children, we
 ///    disable the checks that ensure that the source/target exist, and that the
 ///    offers don't introduce any cycles.
 pub fn validate_dynamic_offers<'a>(
     offers: &'a Vec<fdecl::Offer>,
     decl: &'a fdecl::Component,
 ) -> Result<(), ErrorList> {
-    let ctx = ValidationContext::default();
+    let mut ctx = ValidationContext::default();
     ctx.validate(decl, Some(offers)).map_err(|errs| ErrorList::new(errs))
 }
 
-fn check_offer_name(
-    prop: Option<&String>,
-    decl: &str,
-    keyword: &str,
-    offer_type: OfferType,
-    errors: &mut Vec<Error>,
-) -> bool {
-    if offer_type == OfferType::Dynamic {
-        check_dynamic_name(prop, decl, keyword, errors)
+/// Validates a set of static offers independently of a full component declaration. `children`
+/// and `collections` supply the offer sources and targets that exist in the enclosing realm.
+/// Performs the same per-offer validation as `validate`, including detection of strong
+/// dependency cycles among the offers. Since the component's own capability declarations
+/// aren't available here, offers sourced from `self` are reported as referencing an
+/// undeclared capability.
+pub fn validate_offers(
+    offers: &Vec<fdecl::Offer>,
+    children: &[fdecl::Child],
+    collections: &[fdecl::Collection],
+) -> Result<(), ErrorList> {
+    let mut ctx = ValidationContext::default();
+    for (index, child) in children.iter().enumerate() {
+        if let Some(name) = child.name.as_ref() {
+            ctx.all_children.entry(name.as_str()).or_insert((index, child));
+        }
+    }
+    for collection in collections {
+        if let Some(name) = collection.name.as_ref() {
+            ctx.all_collections.insert(
+                name.as_str(),
+                collection.allowed_offers.unwrap_or(fdecl::AllowedOffers::StaticOnly),
+            );
+        }
+    }
+    for offer in offers.iter() {
+        ctx.validate_offers_decl(offer, OfferType::Static);
+    }
+    ctx.validate_offer_group(offers);
+    if let Err(e) = ctx.strong_dependencies.topological_sort() {
+        let message = ctx.format_cycles_with_capabilities(&e);
+        ctx.errors.push(Error::dependency_cycle(message));
+    }
+    if ctx.errors.is_empty() {
+        Ok(())
     } else {
-        check_name(prop, decl, keyword, errors)
+        Err(ErrorList::new(ctx.errors))
     }
 }
 
-#[derive(Default)]
-struct ValidationContext<'a> {
-    all_children: HashMap<&'a str, &'a fdecl::Child>,
-    all_collections: HashSet<&'a str>,
-    all_capability_ids: HashSet<&'a str>,
-    all_storage_and_sources: HashMap<&'a str, Option<&'a fdecl::Ref>>,
-    all_services: HashSet<&'a str>,
-    all_protocols: HashSet<&'a str>,
-    all_directories: HashSet<&'a str>,
-    all_runners: HashSet<&'a str>,
-    all_resolvers: HashSet<&'a str>,
-    all_environment_names: HashSet<&'a str>,
-    all_events: HashSet<&'a str>,
-    all_event_streams: HashSet<&'a str>,
-    strong_dependencies: DirectedGraph<DependencyNode<'a>>,
+/// Checks that a `facets` dictionary is structurally well-formed: keys must be non-empty and
+/// unique. Facet values are component-defined and are not interpreted here.
+fn validate_facets(facets: Option<&fdata::Dictionary>, errors: &mut Vec<Error>) {
+    if let Some(facets) = facets {
+        if let Some(entries) = facets.entries.as_ref() {
+            let mut seen_keys = HashSet::new();
+            for entry in entries {
+                if entry.key.is_empty() {
+                    errors.push(Error::empty_field("ComponentDecl", "facets.key"));
+                } else if !seen_keys.insert(entry.key.as_str()) {
+                    errors.push(Error::duplicate_field("ComponentDecl", "facets.key", &entry.key));
+                }
+            }
+        }
+    }
+}
+
+/// Checks that an event `filter` dictionary is structurally well-formed: keys must be non-empty
+/// and unique, and values (when present) must be string or string-vector variants. Does not
+/// validate the semantics of any particular filter key; that's left to the event system.
+fn check_event_filter(filter: Option<&fdata::Dictionary>, decl: &str, errors: &mut Vec<Error>) {
+    if let Some(filter) = filter {
+        if let Some(entries) = filter.entries.as_ref() {
+            let mut seen_keys = HashSet::new();
+            for entry in entries {
+                if entry.key.is_empty() {
+                    errors.push(Error::empty_field(decl, "filter.key"));
+                } else if !seen_keys.insert(entry.key.as_str()) {
+                    errors.push(Error::duplicate_field(decl, "filter.key", &entry.key));
+                }
+                match entry.value.as_deref() {
+                    Some(fdata::DictionaryValue::Str(_))
+                    | Some(fdata::DictionaryValue::StrVec(_))
+                    | None => {}
+                    Some(_) => {
+                        errors.push(Error::invalid_field(decl, "filter"));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks that a `subdir` value is a valid relative path that stays inside the directory being
+/// routed: `.` and `..` segments are rejected in addition to `check_relative_path`'s rules.
+fn check_subdir(
+    prop: Option<&String>,
+    decl: &str,
+    max_depth: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> bool {
+    if !check_relative_path_with_depth(prop, decl, "subdir", max_depth, errors) {
+        return false;
+    }
+    if let Some(subdir) = prop {
+        if subdir.split('/').any(|segment| segment == "." || segment == "..") {
+            errors.push(Error::invalid_field(decl, "subdir"));
+            return false;
+        }
+    }
+    true
+}
+
+/// Checks that a directory `rights` value, when present, has at least one operation bit set.
+/// An empty rights set is meaningless for a directory and almost certainly a mistake.
+fn check_rights(rights: Option<&fio::Operations>, decl: &str, errors: &mut Vec<Error>) {
+    if let Some(rights) = rights {
+        if rights.is_empty() {
+            errors.push(Error::invalid_field(decl, "rights"));
+        }
+    }
+}
+
+fn check_offer_name(
+    prop: Option<&String>,
+    decl: &str,
+    keyword: &str,
+    offer_type: OfferType,
+    errors: &mut Vec<Error>,
+) -> bool {
+    if offer_type == OfferType::Dynamic {
+        check_dynamic_name(prop, decl, keyword, errors)
+    } else {
+        check_name(prop, decl, keyword, errors)
+    }
+}
+
+#[derive(Default)]
+struct ValidationContext<'a> {
+    /// Children by name, each with the index in `children` at which the name first appeared,
+    /// so duplicates can report both colliding positions.
+    all_children: HashMap<&'a str, (usize, &'a fdecl::Child)>,
+    /// Collections by name, each with its effective `allowed_offers` policy. Static offers
+    /// into a collection are always legal regardless of policy — `StaticOnly` restricts what
+    /// may be *added dynamically* — but recording the policy here gives target validation a
+    /// single place to consult when a dynamic-offer check needs it.
+    all_collections: HashMap<&'a str, fdecl::AllowedOffers>,
+    all_capability_ids: HashSet<&'a str>,
+    all_storage_and_sources: HashMap<&'a str, Option<&'a fdecl::Ref>>,
+    all_services: HashSet<&'a str>,
+    all_protocols: HashSet<&'a str>,
+    /// Directory declarations by name, with their declared rights, so routes sourced from
+    /// `self` can be checked against what the directory actually provides.
+    all_directories: HashMap<&'a str, Option<fio::Operations>>,
+    all_runners: HashSet<&'a str>,
+    all_resolvers: HashSet<&'a str>,
+    all_environment_names: HashSet<&'a str>,
+    all_events: HashSet<&'a str>,
+    all_event_streams: HashSet<&'a str>,
+    strong_dependencies: DirectedGraph<DependencyNode<'a>>,
+    /// The capability name that induced each edge in `strong_dependencies`, when one is known.
+    /// Only the first capability recorded for an edge is kept; it's enough to explain the edge
+    /// in a cycle report.
+    dependency_edge_capabilities: HashMap<(DependencyNode<'a>, DependencyNode<'a>), String>,
+    /// Backing directory names of the storage declarations, used to annotate dependency edges
+    /// that were translated from a storage capability to its backing directory's provider.
+    all_storage_backing_dirs: HashMap<&'a str, &'a str>,
+    /// All edges added to `strong_dependencies`, in a deterministic order, so the computed
+    /// graph can be handed back to callers of `build_dependency_graph`.
+    dependency_edges: BTreeSet<(DependencyNode<'a>, DependencyNode<'a>)>,
     target_ids: IdMap<'a>,
+    /// Runner target names registered by any environment; only populated (and checked) when
+    /// `ValidationOptions::strict_runner_names` is set.
+    all_registered_runner_names: HashSet<&'a str>,
+    options: ValidationOptions,
     errors: Vec<Error>,
+    /// Diagnostics for legal-but-suspicious declarations; only surfaced through
+    /// `validate_with_warnings`.
+    warnings: Vec<Error>,
+    /// When set, errors are streamed here as validation proceeds; see [`validate_with_sink`].
+    sink: Option<&'a mut dyn FnMut(&Error)>,
+    /// Index into `errors` of the first error not yet forwarded to `sink`.
+    next_unreported: usize,
 }
 
 /// A node in the DependencyGraph. The first string describes the type of node and the second
 /// string is the name of the node.
 #[derive(Copy, Clone, Hash, Ord, Debug, PartialOrd, PartialEq, Eq)]
-enum DependencyNode<'a> {
+pub enum DependencyNode<'a> {
     Self_,
     Child(&'a str, Option<&'a str>),
     Collection(&'a str),
@@ -291,10 +1270,22 @@ impl<'a> fmt::Display for DependencyNode<'a> {
 
 impl<'a> ValidationContext<'a> {
     fn validate(
-        mut self,
+        &mut self,
         decl: &'a fdecl::Component,
         dynamic_offers: Option<&'a Vec<fdecl::Offer>>,
     ) -> Result<(), Vec<Error>> {
+        if let Some(max_decls) = self.options.max_decls {
+            let count = decl.uses.as_ref().map_or(0, Vec::len)
+                + decl.offers.as_ref().map_or(0, Vec::len)
+                + decl.exposes.as_ref().map_or(0, Vec::len)
+                + decl.children.as_ref().map_or(0, Vec::len)
+                + decl.collections.as_ref().map_or(0, Vec::len)
+                + decl.capabilities.as_ref().map_or(0, Vec::len);
+            if count > max_decls {
+                self.push_error(Error::too_many_declarations(count));
+                return Err(std::mem::take(&mut self.errors));
+            }
+        }
         // Collect all environment names first, so that references to them can be checked.
         if let Some(envs) = &decl.environments {
             self.collect_environment_names(&envs);
@@ -307,8 +1298,8 @@ impl<'a> ValidationContext<'a> {
 
         // Validate "children" and build the set of all children.
         if let Some(children) = decl.children.as_ref() {
-            for child in children {
-                self.validate_child_decl(&child);
+            for (index, child) in children.iter().enumerate() {
+                self.validate_child_decl(&child, index);
             }
         }
 
@@ -319,11 +1310,31 @@ impl<'a> ValidationContext<'a> {
             }
         }
 
+        if self.options.case_insensitive_names {
+            self.check_case_insensitive_names(decl);
+        }
+
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         // Validate "capabilities" and build the set of all capabilities.
         if let Some(capabilities) = decl.capabilities.as_ref() {
             for capability in capabilities {
                 self.validate_capability_decl(capability, false);
             }
+            // A storage declaration sourced from `self` must name one of this component's own
+            // directory declarations as its backing directory. Checked after all capabilities
+            // are processed so that declaration order doesn't matter.
+            for capability in capabilities {
+                if let fdecl::Capability::Storage(s) = capability {
+                    self.validate_storage_backing_dir(s);
+                }
+            }
+        }
+
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
         }
 
         // Validate "uses".
@@ -331,6 +1342,14 @@ impl<'a> ValidationContext<'a> {
             self.validate_use_decls(uses);
         }
 
+        if self.options.strict_path_overlap {
+            self.check_cross_section_path_overlap(decl);
+        }
+
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         // Validate "exposes".
         if let Some(exposes) = decl.exposes.as_ref() {
             let mut target_ids = HashMap::new();
@@ -354,6 +1373,10 @@ impl<'a> ValidationContext<'a> {
             self.validate_offer_group(&offers);
         }
 
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         // Validate "environments" after all other declarations are processed.
         if let Some(environment) = decl.environments.as_ref() {
             for environment in environment {
@@ -361,20 +1384,84 @@ impl<'a> ValidationContext<'a> {
             }
         }
 
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         // Validate "config"
         if let Some(config) = decl.config.as_ref() {
             self.validate_config(&config);
         }
 
+        // Validate "facets".
+        validate_facets(decl.facets.as_ref(), &mut self.errors);
+
+        if self.stop_early() {
+            return Err(std::mem::take(&mut self.errors));
+        }
+
         // Check that there are no strong cyclical dependencies
         if let Err(e) = self.strong_dependencies.topological_sort() {
-            self.errors.push(Error::dependency_cycle(e.format_cycle()));
+            let message = self.format_cycles_with_capabilities(&e);
+            self.push_error(Error::dependency_cycle(message));
+        }
+
+        // Shared helpers (`check_name` and friends) append to `errors` directly, so make
+        // sure anything they added since the last direct push reaches the sink too.
+        self.flush_sink();
+
+        // The cycle error, when present, was necessarily found last; rotating it to the
+        // front keeps the rest of the list in discovery order. There's at most one.
+        if self.options.cycle_first {
+            if let Some(position) =
+                self.errors.iter().position(|error| matches!(error, Error::DependencyCycle(_)))
+            {
+                self.errors[..=position].rotate_right(1);
+            }
         }
 
         if self.errors.is_empty() {
             Ok(())
         } else {
-            Err(self.errors)
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    /// Records a validation error, streaming it (and any errors appended by shared helpers
+    /// since the last push) to the sink when one is installed. When
+    /// [`ValidationOptions::extraneous_as_warning`] is set, extraneous-field errors are
+    /// recorded as warnings instead.
+    fn push_error(&mut self, error: Error) {
+        if self.options.extraneous_as_warning {
+            if let Error::ExtraneousField(_) = &error {
+                self.warnings.push(error);
+                return;
+            }
+        }
+        self.errors.push(error);
+        self.flush_sink();
+    }
+
+    /// Forwards any not-yet-reported errors to the sink, if one is installed.
+    fn flush_sink(&mut self) {
+        if let Some(sink) = self.sink.as_mut() {
+            for error in &self.errors[self.next_unreported..] {
+                sink(error);
+            }
+            self.next_unreported = self.errors.len();
+        }
+    }
+
+    /// When [`ValidationOptions::stop_on_first_error`] is set, trims any accumulated
+    /// errors down to the first one and reports whether validation should end early.
+    fn stop_early(&mut self) -> bool {
+        if self.options.stop_on_first_error && !self.errors.is_empty() {
+            self.errors.truncate(1);
+            self.next_unreported = std::cmp::min(self.next_unreported, self.errors.len());
+            self.flush_sink();
+            true
+        } else {
+            false
         }
     }
 
@@ -383,7 +1470,7 @@ impl<'a> ValidationContext<'a> {
         for env in envs {
             if let Some(name) = env.name.as_ref() {
                 if !self.all_environment_names.insert(name) {
-                    self.errors.push(Error::duplicate_field("Environment", "name", name));
+                    self.push_error(Error::duplicate_field("Environment", "name", name));
                 }
             }
         }
@@ -393,33 +1480,42 @@ impl<'a> ValidationContext<'a> {
     // and properties.
     fn validate_config(&mut self, config: &fdecl::ConfigSchema) {
         if let Some(fields) = &config.fields {
+            let mut seen_keys = HashSet::new();
             for field in fields {
-                if field.key.is_none() {
-                    self.errors.push(Error::missing_field("ConfigField", "key"));
+                match field.key.as_ref() {
+                    None => self.push_error(Error::missing_field("ConfigField", "key")),
+                    Some(key) if key.is_empty() => {
+                        self.push_error(Error::empty_field("ConfigField", "key"));
+                    }
+                    Some(key) => {
+                        if !seen_keys.insert(key.as_str()) {
+                            self.push_error(Error::duplicate_field("ConfigField", "key", key));
+                        }
+                    }
                 }
                 if let Some(type_) = &field.type_ {
                     self.validate_config_type(type_, true);
                 } else {
-                    self.errors.push(Error::missing_field("ConfigField", "value_type"));
+                    self.push_error(Error::missing_field("ConfigField", "value_type"));
                 }
             }
         } else {
-            self.errors.push(Error::missing_field("ConfigSchema", "fields"));
+            self.push_error(Error::missing_field("ConfigSchema", "fields"));
         }
 
         if let Some(checksum) = &config.checksum {
             match checksum {
                 fdecl::ConfigChecksum::Sha256(_) => {}
                 fdecl::ConfigChecksumUnknown!() => {
-                    self.errors.push(Error::invalid_field("ConfigSchema", "checksum"));
+                    self.push_error(Error::invalid_field("ConfigSchema", "checksum"));
                 }
             }
         } else {
-            self.errors.push(Error::missing_field("ConfigSchema", "checksum"));
+            self.push_error(Error::missing_field("ConfigSchema", "checksum"));
         }
 
         if config.value_source.is_none() {
-            self.errors.push(Error::missing_field("ConfigSchema", "value_source"));
+            self.push_error(Error::missing_field("ConfigSchema", "value_source"));
         }
     }
 
@@ -437,33 +1533,33 @@ impl<'a> ValidationContext<'a> {
                 // These layouts have no parameters or constraints
                 if let Some(parameters) = &type_.parameters {
                     if !parameters.is_empty() {
-                        self.errors.push(Error::extraneous_field("ConfigType", "parameters"));
+                        self.push_error(Error::extraneous_field("ConfigType", "parameters"));
                     }
                 } else {
-                    self.errors.push(Error::missing_field("ConfigType", "parameters"));
+                    self.push_error(Error::missing_field("ConfigType", "parameters"));
                 }
 
                 if !type_.constraints.is_empty() {
-                    self.errors.push(Error::extraneous_field("ConfigType", "constraints"));
+                    self.push_error(Error::extraneous_field("ConfigType", "constraints"));
                 }
             }
             fdecl::ConfigTypeLayout::String => {
                 // String has exactly one constraint and no parameter
                 if let Some(parameters) = &type_.parameters {
                     if !parameters.is_empty() {
-                        self.errors.push(Error::extraneous_field("ConfigType", "parameters"));
+                        self.push_error(Error::extraneous_field("ConfigType", "parameters"));
                     }
                 } else {
-                    self.errors.push(Error::missing_field("ConfigType", "parameters"));
+                    self.push_error(Error::missing_field("ConfigType", "parameters"));
                 }
 
                 if type_.constraints.is_empty() {
-                    self.errors.push(Error::missing_field("ConfigType", "constraints"));
+                    self.push_error(Error::missing_field("ConfigType", "constraints"));
                 } else if type_.constraints.len() > 1 {
-                    self.errors.push(Error::extraneous_field("ConfigType", "constraints"));
+                    self.push_error(Error::extraneous_field("ConfigType", "constraints"));
                 } else if let fdecl::LayoutConstraint::MaxSize(_) = &type_.constraints[0] {
                 } else {
-                    self.errors.push(Error::invalid_field("ConfigType", "constraints"));
+                    self.push_error(Error::invalid_field("ConfigType", "constraints"));
                 }
             }
             fdecl::ConfigTypeLayout::Vector => {
@@ -471,40 +1567,41 @@ impl<'a> ValidationContext<'a> {
                     // Vector has exactly one constraint and one parameter
                     if let Some(parameters) = &type_.parameters {
                         if parameters.is_empty() {
-                            self.errors.push(Error::missing_field("ConfigType", "parameters"));
+                            self.push_error(Error::missing_field("ConfigType", "parameters"));
                         } else if parameters.len() > 1 {
-                            self.errors.push(Error::extraneous_field("ConfigType", "parameters"));
+                            self.push_error(Error::extraneous_field("ConfigType", "parameters"));
                         } else if let fdecl::LayoutParameter::NestedType(nested_type) =
                             &parameters[0]
                         {
                             self.validate_config_type(nested_type, false);
                         } else {
-                            self.errors.push(Error::invalid_field("ConfigType", "parameters"));
+                            self.push_error(Error::invalid_field("ConfigType", "parameters"));
                         }
                     } else {
-                        self.errors.push(Error::missing_field("ConfigType", "parameters"))
+                        self.push_error(Error::missing_field("ConfigType", "parameters"))
                     }
 
                     if type_.constraints.is_empty() {
-                        self.errors.push(Error::missing_field("ConfigType", "constraints"));
+                        self.push_error(Error::missing_field("ConfigType", "constraints"));
                     } else if type_.constraints.len() > 1 {
-                        self.errors.push(Error::extraneous_field("ConfigType", "constraints"));
+                        self.push_error(Error::extraneous_field("ConfigType", "constraints"));
                     } else if let fdecl::LayoutConstraint::MaxSize(_) = &type_.constraints[0] {
                     } else {
-                        self.errors.push(Error::invalid_field("ConfigType", "constraints"));
+                        self.push_error(Error::invalid_field("ConfigType", "constraints"));
                     }
                 } else {
-                    self.errors.push(Error::nested_vector());
+                    self.push_error(Error::nested_vector());
                 }
             }
-            _ => self.errors.push(Error::invalid_field("ConfigType", "layout")),
+            _ => self.push_error(Error::invalid_field("ConfigType", "layout")),
         }
     }
 
     /// Validates an individual capability declaration as either a built-in capability or (if
     /// `as_builtin = false`) as a component or namespace capability.
-    // Storage capabilities are not currently allowed as built-ins, but there's no deep reason for this.
-    // Update this method to allow built-in storage capabilities as needed.
+    // Storage capabilities are only accepted as built-ins when
+    // `ValidationOptions::allow_builtin_storage` is set; there's no deep reason for the
+    // default rejection.
     fn validate_capability_decl(&mut self, capability: &'a fdecl::Capability, as_builtin: bool) {
         match capability {
             fdecl::Capability::Service(service) => self.validate_service_decl(&service, as_builtin),
@@ -515,10 +1612,16 @@ impl<'a> ValidationContext<'a> {
                 self.validate_directory_decl(&directory, as_builtin)
             }
             fdecl::Capability::Storage(storage) => {
-                if as_builtin {
-                    self.errors.push(Error::invalid_capability_type(
+                if as_builtin && !self.options.allow_builtin_storage {
+                    // Include the declaration's name, when it has one, so the message points
+                    // at which builtin storage declaration is offending.
+                    let field = match storage.name.as_ref() {
+                        Some(name) => format!("capability.{}", name),
+                        None => "capability".to_string(),
+                    };
+                    self.push_error(Error::invalid_capability_type(
                         "RuntimeConfig",
-                        "capability",
+                        field,
                         "storage",
                     ))
                 } else {
@@ -533,7 +1636,7 @@ impl<'a> ValidationContext<'a> {
                 if as_builtin {
                     self.validate_event_decl(&event)
                 } else {
-                    self.errors.push(Error::invalid_capability_type(
+                    self.push_error(Error::invalid_capability_type(
                         "Component",
                         "capability",
                         "event",
@@ -544,7 +1647,7 @@ impl<'a> ValidationContext<'a> {
                 if as_builtin {
                     self.validate_event_stream_decl(&event)
                 } else {
-                    self.errors.push(Error::invalid_capability_type(
+                    self.push_error(Error::invalid_capability_type(
                         "Component",
                         "capability",
                         "event",
@@ -552,18 +1655,15 @@ impl<'a> ValidationContext<'a> {
                 }
             }
             _ => {
-                if as_builtin {
-                    self.errors.push(Error::invalid_capability_type(
-                        "RuntimeConfig",
+                let decl = if as_builtin { "RuntimeConfig" } else { "Component" };
+                if self.options.reject_unknown {
+                    self.push_error(Error::unknown_variant(
+                        decl,
                         "capability",
-                        "unknown",
+                        capability.ordinal(),
                     ));
                 } else {
-                    self.errors.push(Error::invalid_capability_type(
-                        "Component",
-                        "capability",
-                        "unknown",
-                    ));
+                    self.push_error(Error::invalid_capability_type(decl, "capability", "unknown"));
                 }
             }
         }
@@ -597,8 +1697,24 @@ impl<'a> ValidationContext<'a> {
                     "UseService",
                     "source",
                 );
-                check_name(u.source_name.as_ref(), "UseService", "source_name", &mut self.errors);
-                check_path(u.target_path.as_ref(), "UseService", "target_path", &mut self.errors);
+                if check_name(u.source_name.as_ref(), "UseService", "source_name", &mut self.errors)
+                {
+                    // A use from `self` must reference one of this component's own
+                    // service declarations.
+                    if let Some(fdecl::Ref::Self_(_)) = u.source {
+                        let name = u.source_name.as_ref().unwrap();
+                        if !self.all_services.contains(name.as_str()) {
+                            self.push_error(Error::invalid_capability("UseService", "source", name));
+                        }
+                    }
+                }
+                check_path_with_depth(
+                    u.target_path.as_ref(),
+                    "UseService",
+                    "target_path",
+                    self.options.max_path_depth,
+                    &mut self.errors,
+                );
             }
             fdecl::Use::Protocol(u) => {
                 self.validate_use_source(
@@ -609,8 +1725,24 @@ impl<'a> ValidationContext<'a> {
                     "UseProtocol",
                     "source",
                 );
-                check_name(u.source_name.as_ref(), "UseProtocol", "source_name", &mut self.errors);
-                check_path(u.target_path.as_ref(), "UseProtocol", "target_path", &mut self.errors);
+                if check_name(u.source_name.as_ref(), "UseProtocol", "source_name", &mut self.errors)
+                {
+                    // A use from `self` must reference one of this component's own
+                    // protocol declarations.
+                    if let Some(fdecl::Ref::Self_(_)) = u.source {
+                        let name = u.source_name.as_ref().unwrap();
+                        if !self.all_protocols.contains(name.as_str()) {
+                            self.push_error(Error::invalid_capability("UseProtocol", "source", name));
+                        }
+                    }
+                }
+                check_path_with_depth(
+                    u.target_path.as_ref(),
+                    "UseProtocol",
+                    "target_path",
+                    self.options.max_path_depth,
+                    &mut self.errors,
+                );
             }
             fdecl::Use::Directory(u) => {
                 self.validate_use_source(
@@ -621,18 +1753,60 @@ impl<'a> ValidationContext<'a> {
                     "UseDirectory",
                     "source",
                 );
-                check_name(u.source_name.as_ref(), "UseDirectory", "source_name", &mut self.errors);
-                check_path(u.target_path.as_ref(), "UseDirectory", "target_path", &mut self.errors);
+                if check_name(u.source_name.as_ref(), "UseDirectory", "source_name", &mut self.errors)
+                {
+                    // A use from `self` must reference one of this component's own
+                    // directory declarations.
+                    if let Some(fdecl::Ref::Self_(_)) = u.source {
+                        let name = u.source_name.as_ref().unwrap();
+                        if !self.all_directories.contains_key(name.as_str()) {
+                            self.push_error(Error::invalid_capability("UseDirectory", "source", name));
+                        }
+                    }
+                }
+                check_path_with_depth(
+                    u.target_path.as_ref(),
+                    "UseDirectory",
+                    "target_path",
+                    self.options.max_path_depth,
+                    &mut self.errors,
+                );
                 if u.rights.is_none() {
-                    self.errors.push(Error::missing_field("UseDirectory", "rights"));
+                    self.push_error(Error::missing_field("UseDirectory", "rights"));
+                } else {
+                    check_rights(u.rights.as_ref(), "UseDirectory", &mut self.errors);
                 }
                 if let Some(subdir) = u.subdir.as_ref() {
-                    check_relative_path(Some(subdir), "UseDirectory", "subdir", &mut self.errors);
+                    check_subdir(
+                        Some(subdir),
+                        "UseDirectory",
+                        self.options.max_path_depth,
+                        &mut self.errors,
+                    );
                 }
             }
             fdecl::Use::Storage(u) => {
                 check_name(u.source_name.as_ref(), "UseStorage", "source_name", &mut self.errors);
-                check_path(u.target_path.as_ref(), "UseStorage", "target_path", &mut self.errors);
+                if let (Some(known), Some(name)) =
+                    (self.options.known_storage_names.as_ref(), u.source_name.as_ref())
+                {
+                    if !known.contains(name)
+                        && !self.all_storage_and_sources.contains_key(name.as_str())
+                    {
+                        self.push_error(Error::invalid_storage(
+                            "UseStorage",
+                            "source_name",
+                            name,
+                        ));
+                    }
+                }
+                check_path_with_depth(
+                    u.target_path.as_ref(),
+                    "UseStorage",
+                    "target_path",
+                    self.options.max_path_depth,
+                    &mut self.errors,
+                );
                 check_use_availability("UseStorage", u.availability.as_ref(), &mut self.errors);
             }
             fdecl::Use::EventStreamDeprecated(e) => {
@@ -652,7 +1826,11 @@ impl<'a> ValidationContext<'a> {
                 check_use_availability("UseEventStream", u.availability.as_ref(), &mut self.errors);
             }
             _ => {
-                self.errors.push(Error::invalid_field("Component", "use"));
+                if self.options.reject_unknown {
+                    self.push_error(Error::unknown_variant("Component", "use", use_.ordinal()));
+                } else {
+                    self.push_error(Error::invalid_field("Component", "use"));
+                }
             }
         }
     }
@@ -662,46 +1840,137 @@ impl<'a> ValidationContext<'a> {
             Some(fdecl::Ref::Child(_)) | Some(fdecl::Ref::Parent(_)) => {}
             Some(fdecl::Ref::Framework(_)) => match &u.scope {
                 Some(value) if value.is_empty() => {
-                    self.errors.push(Error::invalid_field("UseEventStream", "scope"));
+                    self.push_error(Error::invalid_field("UseEventStream", "scope"));
                 }
                 Some(_) => {}
                 None => {
-                    self.errors.push(Error::missing_field("UseEventStream", "scope"));
+                    self.push_error(Error::missing_field("UseEventStream", "scope"));
                 }
             },
             _ => {
-                self.errors.push(Error::invalid_field("UseEventStream", "source"));
+                self.push_error(Error::invalid_field("UseEventStream", "source"));
             }
         }
         if let Some(scope) = &u.scope {
             for reference in scope {
                 if !matches!(reference, fdecl::Ref::Child(_) | fdecl::Ref::Collection(_)) {
-                    self.errors.push(Error::invalid_field("UseEventStream", "scope"));
+                    self.push_error(Error::invalid_field("UseEventStream", "scope"));
                 }
             }
         }
-        check_path(u.target_path.as_ref(), "UseEventStream", "target_path", &mut self.errors);
+        check_path_with_depth(
+            u.target_path.as_ref(),
+            "UseEventStream",
+            "target_path",
+            self.options.max_path_depth,
+            &mut self.errors,
+        );
         if let Some(name) = &u.source_name {
             check_name(Some(name), "UseEventStream", "source_name", &mut self.errors);
         } else {
-            self.errors.push(Error::missing_field("UseEventStream", "source_name"));
+            self.push_error(Error::missing_field("UseEventStream", "source_name"));
         }
     }
 
     /// Validates the "program" declaration. This does not check runner-specific properties
-    /// since those are checked by the runner.
+    /// since those are checked by the runner, but it does check that the `info` dictionary
+    /// is structurally well-formed (no empty or duplicate keys).
     fn validate_program(&mut self, program: &fdecl::Program) {
         if program.runner.is_none() {
-            self.errors.push(Error::missing_field("Program", "runner"));
+            self.push_error(Error::missing_field("Program", "runner"));
         }
 
-        if program.info.is_none() {
-            self.errors.push(Error::missing_field("Program", "info"));
+        match program.info.as_ref() {
+            Some(info) => {
+                if let Some(entries) = info.entries.as_ref() {
+                    let mut seen_keys = HashSet::new();
+                    for entry in entries {
+                        if entry.key.is_empty() {
+                            self.push_error(Error::empty_field("Program", "info.key"));
+                        } else if !seen_keys.insert(entry.key.as_str()) {
+                            self.push_error(Error::duplicate_field(
+                                "Program",
+                                "info.key",
+                                &entry.key,
+                            ));
+                        }
+                    }
+                }
+            }
+            None => {
+                self.push_error(Error::missing_field("Program", "info"));
+            }
         }
     }
 
     /// Validates that paths-based capabilities (service, directory, protocol)
     /// are different, are not prefixes of each other, and do not collide "/pkg".
+    /// Checks the `source_path`s of self protocol/directory capabilities against the
+    /// `target_path`s of uses, reporting pairs that are equal or nested. Runs only when
+    /// [`ValidationOptions::strict_path_overlap`] is set; within-section overlap is
+    /// always checked by [`Self::validate_use_paths`] and `check_capability_source_paths`.
+    fn check_cross_section_path_overlap(&mut self, decl: &'a fdecl::Component) {
+        // (full path, decl name, directory it occupies, whether it claims the whole dir)
+        let mut capability_paths: Vec<(&String, &str, &Path, bool)> = Vec::new();
+        for capability in decl.capabilities.iter().flatten() {
+            match capability {
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    source_path: Some(path), ..
+                }) => {
+                    if let Some(dir) = Path::new(path).parent() {
+                        capability_paths.push((path, "Protocol", dir, false));
+                    }
+                }
+                fdecl::Capability::Directory(fdecl::Directory {
+                    source_path: Some(path),
+                    ..
+                }) => {
+                    capability_paths.push((path, "Directory", Path::new(path), true));
+                }
+                _ => {}
+            }
+        }
+        let mut use_paths: Vec<(&String, &str, &Path, bool)> = Vec::new();
+        for use_ in decl.uses.iter().flatten() {
+            match use_ {
+                fdecl::Use::Service(fdecl::UseService { target_path: Some(path), .. }) => {
+                    if let Some(dir) = Path::new(path).parent() {
+                        use_paths.push((path, "UseService", dir, false));
+                    }
+                }
+                fdecl::Use::Protocol(fdecl::UseProtocol { target_path: Some(path), .. }) => {
+                    if let Some(dir) = Path::new(path).parent() {
+                        use_paths.push((path, "UseProtocol", dir, false));
+                    }
+                }
+                fdecl::Use::Directory(fdecl::UseDirectory { target_path: Some(path), .. }) => {
+                    use_paths.push((path, "UseDirectory", Path::new(path), true));
+                }
+                fdecl::Use::Storage(fdecl::UseStorage { target_path: Some(path), .. }) => {
+                    use_paths.push((path, "UseStorage", Path::new(path), true));
+                }
+                _ => {}
+            }
+        }
+        for &(path_a, decl_a, dir_a, whole_dir_a) in &capability_paths {
+            for &(path_b, decl_b, dir_b, whole_dir_b) in &use_paths {
+                let overlaps = if path_a == path_b {
+                    // The same path can't be both served by the program and mapped into
+                    // its namespace.
+                    true
+                } else if !whole_dir_a && !whole_dir_b {
+                    // Two protocol-ish paths may share a directory but not nest.
+                    dir_a != dir_b && (dir_a.starts_with(dir_b) || dir_b.starts_with(dir_a))
+                } else {
+                    dir_a == dir_b || dir_a.starts_with(dir_b) || dir_b.starts_with(dir_a)
+                };
+                if overlaps {
+                    self.push_error(Error::invalid_path_overlap(decl_a, path_a, decl_b, path_b));
+                }
+            }
+        }
+    }
+
     fn validate_use_paths(&mut self, uses: &[fdecl::Use]) {
         #[derive(Debug, PartialEq, Clone, Copy)]
         struct PathCapability<'a> {
@@ -709,7 +1978,10 @@ impl<'a> ValidationContext<'a> {
             dir: &'a Path,
             use_: &'a fdecl::Use,
         }
-        let mut used_paths = HashMap::new();
+        // Collected in declaration order so duplicate and overlap errors come out
+        // deterministically, and so a repeated path still participates in overlap detection
+        // against every other path rather than only the last occurrence surviving.
+        let mut used_paths: Vec<(&String, PathCapability<'_>)> = Vec::new();
         for use_ in uses.iter() {
             match use_ {
                 fdecl::Use::Service(fdecl::UseService { target_path: Some(path), .. })
@@ -739,17 +2011,27 @@ impl<'a> ValidationContext<'a> {
                         }
                         _ => unreachable!(),
                     };
-                    if used_paths.insert(path, capability).is_some() {
-                        // Disallow multiple capabilities for the same path.
-                        self.errors.push(Error::duplicate_field(capability.decl, "path", path));
-                    }
+                    used_paths.push((path, capability));
                 }
                 _ => {}
             }
         }
-        for ((&path_a, capability_a), (&path_b, capability_b)) in
+        let mut seen_paths = HashSet::new();
+        for &(path, capability) in &used_paths {
+            if !seen_paths.insert(path.as_str()) {
+                // Disallow multiple capabilities for the same path.
+                self.push_error(Error::duplicate_field(capability.decl, "path", path.as_str()));
+            } else if path.as_str() == "/pkg" || path.starts_with("/pkg/") {
+                self.push_error(Error::pkg_path_overlap(capability.decl, path.as_str()));
+            }
+        }
+        for (&(path_a, capability_a), &(path_b, capability_b)) in
             used_paths.iter().tuple_combinations()
         {
+            if path_a == path_b {
+                // Already reported as an exact duplicate above.
+                continue;
+            }
             if match (capability_a.use_, capability_b.use_) {
                 // Directories and storage can't be the same or partially overlap.
                 (fdecl::Use::Directory(_), fdecl::Use::Directory(_))
@@ -776,7 +2058,7 @@ impl<'a> ValidationContext<'a> {
                             || capability_a.dir.starts_with(capability_b.dir))
                 }
             } {
-                self.errors.push(Error::invalid_path_overlap(
+                self.push_error(Error::invalid_path_overlap(
                     capability_a.decl,
                     path_a,
                     capability_b.decl,
@@ -784,14 +2066,23 @@ impl<'a> ValidationContext<'a> {
                 ));
             }
         }
-        for (used_path, capability) in used_paths.iter() {
-            if used_path.as_str() == "/pkg" || used_path.starts_with("/pkg/") {
-                self.errors.push(Error::pkg_path_overlap(capability.decl, *used_path));
-            }
-        }
     }
 
+    // NOTE: `fdecl::UseEvent` no longer carries an event mode, so there is nothing to check
+    // here regarding sync event subscriptions; sync events were only ever supported from the
+    // framework, and that restriction is enforced by the event system itself.
+    //
+    // A used event's `target_name` lives in this component's own namespace (`all_events`),
+    // while an offered event's `target_name` lives in the namespace of the offer's target
+    // (`target_ids`). The two never collide at runtime, so sharing a name between a use and
+    // an offer is deliberately not an error; see
+    // `test_validate_use_and_offer_event_same_target_name`.
     fn validate_event(&mut self, event: &'a fdecl::UseEvent) {
+        if let Some(api_level) = self.options.api_level {
+            if api_level >= EVENT_DEPRECATION_API_LEVEL {
+                self.push_error(Error::deprecated_capability_type("Component", "use", "event"));
+            }
+        }
         self.validate_use_source(
             event.source.as_ref(),
             event.source_name.as_ref(),
@@ -801,17 +2092,20 @@ impl<'a> ValidationContext<'a> {
             "source",
         );
         if let Some(fdecl::Ref::Self_(_)) = event.source {
-            self.errors.push(Error::invalid_field("UseEvent", "source"));
+            self.push_error(Error::invalid_field("UseEvent", "source"));
         }
         check_name(event.source_name.as_ref(), "UseEvent", "source_name", &mut self.errors);
         check_name(event.target_name.as_ref(), "UseEvent", "target_name", &mut self.errors);
+        check_event_filter(event.filter.as_ref(), "UseEvent", &mut self.errors);
         if let Some(target_name) = event.target_name.as_ref() {
             if !self.all_events.insert(target_name) {
-                self.errors.push(Error::duplicate_field("UseEvent", "target_name", target_name));
+                self.push_error(Error::duplicate_field("UseEvent", "target_name", target_name));
             }
         }
     }
 
+    // NOTE: `fdecl::EventSubscription` no longer carries a `mode`, so a missing mode cannot be
+    // (and does not need to be) reported here; subscriptions are implicitly async.
     fn validate_event_stream_deprecated(
         &mut self,
         event_stream: &'a fdecl::UseEventStreamDeprecated,
@@ -819,17 +2113,18 @@ impl<'a> ValidationContext<'a> {
         check_name(event_stream.name.as_ref(), "UseEventStream", "name", &mut self.errors);
         if let Some(name) = event_stream.name.as_ref() {
             if !self.all_event_streams.insert(name) {
-                self.errors.push(Error::duplicate_field("UseEventStream", "name", name));
+                self.push_error(Error::duplicate_field("UseEventStream", "name", name));
             }
         }
         match event_stream.subscriptions.as_ref() {
             None => {
-                self.errors.push(Error::missing_field("UseEventStream", "subscriptions"));
+                self.push_error(Error::missing_field("UseEventStream", "subscriptions"));
             }
             Some(subscriptions) if subscriptions.is_empty() => {
-                self.errors.push(Error::empty_field("UseEventStream", "subscriptions"));
+                self.push_error(Error::empty_field("UseEventStream", "subscriptions"));
             }
             Some(subscriptions) => {
+                let mut seen_events = HashSet::new();
                 for subscription in subscriptions {
                     check_name(
                         subscription.event_name.as_ref(),
@@ -838,8 +2133,16 @@ impl<'a> ValidationContext<'a> {
                         &mut self.errors,
                     );
                     let event_name = subscription.event_name.clone().unwrap_or_default();
-                    if !self.all_events.contains(event_name.as_str()) {
-                        self.errors.push(Error::event_stream_event_not_found(
+                    if !seen_events.insert(event_name.clone()) {
+                        // A stream subscribing to the same event twice is a mistake; don't
+                        // also re-report the event as not found.
+                        self.push_error(Error::duplicate_field(
+                            "UseEventStream",
+                            "event_name",
+                            event_name.as_str(),
+                        ));
+                    } else if !self.all_events.contains(event_name.as_str()) {
+                        self.push_error(Error::event_stream_event_not_found(
                             "UseEventStream",
                             "events",
                             event_name,
@@ -865,22 +2168,38 @@ impl<'a> ValidationContext<'a> {
         match source {
             Some(fdecl::Ref::Parent(_)) => {}
             Some(fdecl::Ref::Framework(_)) => {}
-            Some(fdecl::Ref::Debug(_)) => {}
+            Some(fdecl::Ref::Debug(_)) => {
+                // Debug capabilities registered in the environment are always protocols, so
+                // `debug` is not a meaningful source for any other use type.
+                if decl != "UseProtocol" {
+                    self.push_error(Error::invalid_field(decl, field));
+                }
+            }
             Some(fdecl::Ref::Self_(_)) => {}
             Some(fdecl::Ref::Capability(capability)) => {
                 if !self.all_capability_ids.contains(capability.name.as_str()) {
-                    self.errors.push(Error::invalid_capability(decl, field, &capability.name));
-                } else if dependency_type == Some(&fdecl::DependencyType::Strong) {
-                    self.add_strong_dep(
-                        source_name,
-                        DependencyNode::try_from_ref(source),
-                        Some(DependencyNode::Self_),
-                    );
+                    self.push_error(Error::invalid_capability(decl, field, &capability.name));
+                } else {
+                    // The StorageAdmin protocol is provided by the framework for storage
+                    // capabilities only; referencing any other capability type is a mistake.
+                    if decl == "UseProtocol"
+                        && source_name.map(|n| n.as_str()) == Some(STORAGE_ADMIN_PROTOCOL_NAME)
+                        && !self.all_storage_and_sources.contains_key(capability.name.as_str())
+                    {
+                        self.push_error(Error::invalid_storage(decl, field, &capability.name));
+                    }
+                    if dependency_type == Some(&fdecl::DependencyType::Strong) {
+                        self.add_strong_dep(
+                            source_name,
+                            DependencyNode::try_from_ref(source),
+                            Some(DependencyNode::Self_),
+                        );
+                    }
                 }
             }
             Some(fdecl::Ref::Child(child)) => {
                 if !self.all_children.contains_key(&child.name as &str) {
-                    self.errors.push(Error::invalid_child(decl, field, &child.name));
+                    self.push_error(Error::invalid_child(decl, field, &child.name));
                 } else if dependency_type == Some(&fdecl::DependencyType::Strong) {
                     self.add_strong_dep(
                         source_name,
@@ -890,10 +2209,10 @@ impl<'a> ValidationContext<'a> {
                 }
             }
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl, field));
+                self.push_error(Error::invalid_field(decl, field));
             }
             None => {
-                self.errors.push(Error::missing_field(decl, field));
+                self.push_error(Error::missing_field(decl, field));
             }
         };
         check_use_availability(decl, availability, &mut self.errors);
@@ -907,20 +2226,32 @@ impl<'a> ValidationContext<'a> {
                 false,
                 Some(fdecl::DependencyType::Weak) | Some(fdecl::DependencyType::WeakForMigration),
             ) => {
-                self.errors.push(Error::invalid_field(decl, "dependency_type"));
+                self.push_error(Error::invalid_field(decl, "dependency_type"));
             }
             _ => {}
         }
     }
 
-    fn validate_child_decl(&mut self, child: &'a fdecl::Child) {
-        if let Err(mut e) = validate_child(child, check_name) {
+    fn validate_child_decl(&mut self, child: &'a fdecl::Child, index: usize) {
+        if let Err(mut e) = validate_child(child, check_name, self.options.count_chars) {
             self.errors.append(&mut e.errs);
         }
         if let Some(name) = child.name.as_ref() {
             let name: &str = name;
-            if self.all_children.insert(name, child).is_some() {
-                self.errors.push(Error::duplicate_field("Child", "name", name));
+            match self.all_children.entry(name) {
+                Entry::Vacant(entry) => {
+                    entry.insert((index, child));
+                }
+                Entry::Occupied(entry) => {
+                    let (first_index, _) = *entry.get();
+                    self.push_error(Error::duplicate_field_at_index(
+                        "Child",
+                        "name",
+                        name,
+                        first_index,
+                        index,
+                    ));
+                }
             }
             if let Some(env) = child.environment.as_ref() {
                 let source = DependencyNode::Environment(env.as_str());
@@ -930,7 +2261,20 @@ impl<'a> ValidationContext<'a> {
         }
         if let Some(environment) = child.environment.as_ref() {
             if !self.all_environment_names.contains(environment.as_str()) {
-                self.errors.push(Error::invalid_environment("Child", "environment", environment));
+                self.push_error(Error::invalid_environment("Child", "environment", environment));
+            }
+        }
+        if let Some(allowlist) = self.options.reboot_on_terminate_allowlist.as_ref() {
+            if child.on_terminate == Some(fdecl::OnTerminate::Reboot) {
+                if let Some(name) = child.name.as_ref() {
+                    if !allowlist.contains(name) {
+                        self.push_error(Error::on_terminate_not_allowed(
+                            "Child",
+                            "on_terminate",
+                            name,
+                        ));
+                    }
+                }
             }
         }
     }
@@ -939,16 +2283,24 @@ impl<'a> ValidationContext<'a> {
         let name = collection.name.as_ref();
         if check_name(name, "Collection", "name", &mut self.errors) {
             let name: &str = name.unwrap();
-            if !self.all_collections.insert(name) {
-                self.errors.push(Error::duplicate_field("Collection", "name", name));
+            // `allowed_offers` defaults to `StaticOnly` when unset.
+            let allowed_offers =
+                collection.allowed_offers.unwrap_or(fdecl::AllowedOffers::StaticOnly);
+            if self.all_collections.insert(name, allowed_offers).is_some() {
+                self.push_error(Error::duplicate_field("Collection", "name", name));
+            } else if self.all_children.contains_key(name) {
+                // Children are collected before collections, so a collection sharing a
+                // child's name is reported here. The two live in the same moniker
+                // namespace, so the name is ambiguous.
+                self.push_error(Error::duplicate_field("Collection", "name", name));
             }
         }
         if collection.durability.is_none() {
-            self.errors.push(Error::missing_field("Collection", "durability"));
+            self.push_error(Error::missing_field("Collection", "durability"));
         }
         if let Some(environment) = collection.environment.as_ref() {
             if !self.all_environment_names.contains(environment.as_str()) {
-                self.errors.push(Error::invalid_environment(
+                self.push_error(Error::invalid_environment(
                     "Collection",
                     "environment",
                     environment,
@@ -963,11 +2315,48 @@ impl<'a> ValidationContext<'a> {
         // Allow `allowed_offers` & `allow_long_names` to be unset/unvalidated, for backwards compatibility.
     }
 
+    /// Reports child and collection names that collide when compared case-insensitively.
+    /// Exact duplicates are skipped here because the main child/collection passes already
+    /// report them.
+    fn check_case_insensitive_names(&mut self, decl: &'a fdecl::Component) {
+        let mut check = |names: Vec<&'a str>, decl_type: &str| {
+            let mut seen: HashMap<String, &'a str> = HashMap::new();
+            for name in names {
+                match seen.entry(name.to_ascii_lowercase()) {
+                    Entry::Occupied(entry) => {
+                        if *entry.get() != name {
+                            self.push_error(Error::duplicate_field(decl_type, "name", name));
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(name);
+                    }
+                }
+            }
+        };
+        check(
+            decl.children
+                .iter()
+                .flatten()
+                .filter_map(|c| c.name.as_deref())
+                .collect(),
+            "Child",
+        );
+        check(
+            decl.collections
+                .iter()
+                .flatten()
+                .filter_map(|c| c.name.as_deref())
+                .collect(),
+            "Collection",
+        );
+    }
+
     fn validate_environment_decl(&mut self, environment: &'a fdecl::Environment) {
         let name = environment.name.as_ref();
         check_name(name, "Environment", "name", &mut self.errors);
         if environment.extends.is_none() {
-            self.errors.push(Error::missing_field("Environment", "extends"));
+            self.push_error(Error::missing_field("Environment", "extends"));
         }
         if let Some(runners) = environment.runners.as_ref() {
             let mut registered_runners = HashSet::new();
@@ -989,15 +2378,30 @@ impl<'a> ValidationContext<'a> {
         match environment.extends.as_ref() {
             Some(fdecl::EnvironmentExtends::None) => {
                 if environment.stop_timeout_ms.is_none() {
-                    self.errors.push(Error::missing_field("Environment", "stop_timeout_ms"));
+                    self.push_error(Error::missing_field("Environment", "stop_timeout_ms"));
+                }
+                // An environment that extends nothing and registers nothing is almost
+                // certainly a mistake: children placed in it can't be resolved or run. Kept
+                // as a warning since intentionally-minimal test environments are legal.
+                let no_runners = environment.runners.as_ref().map_or(true, |r| r.is_empty());
+                let no_resolvers = environment.resolvers.as_ref().map_or(true, |r| r.is_empty());
+                let no_debug =
+                    environment.debug_capabilities.as_ref().map_or(true, |d| d.is_empty());
+                if no_runners && no_resolvers && no_debug {
+                    self.warnings.push(Error::empty_environment("Environment", "extends"));
                 }
             }
             None | Some(fdecl::EnvironmentExtends::Realm) => {}
         }
 
         if let Some(debugs) = environment.debug_capabilities.as_ref() {
+            let mut debug_target_names = HashSet::new();
             for debug in debugs {
-                self.validate_environment_debug_registration(debug, name.clone());
+                self.validate_environment_debug_registration(
+                    debug,
+                    name.clone(),
+                    &mut debug_target_names,
+                );
             }
         }
     }
@@ -1024,7 +2428,7 @@ impl<'a> ValidationContext<'a> {
             (&runner_registration.source, &runner_registration.source_name)
         {
             if !self.all_runners.contains(name as &str) {
-                self.errors.push(Error::invalid_runner("RunnerRegistration", "source_name", name));
+                self.push_error(Error::invalid_runner("RunnerRegistration", "source_name", name));
             }
         }
 
@@ -1036,7 +2440,11 @@ impl<'a> ValidationContext<'a> {
         );
         if let Some(name) = runner_registration.target_name.as_ref() {
             if !runner_names.insert(name.as_str()) {
-                self.errors.push(Error::duplicate_field("RunnerRegistration", "target_name", name));
+                self.push_error(Error::duplicate_field("RunnerRegistration", "target_name", name));
+            } else if self.options.strict_runner_names
+                && !self.all_registered_runner_names.insert(name.as_str())
+            {
+                self.push_error(Error::duplicate_field("RunnerRegistration", "target_name", name));
             }
         }
     }
@@ -1045,7 +2453,7 @@ impl<'a> ValidationContext<'a> {
         &mut self,
         resolver_registration: &'a fdecl::ResolverRegistration,
         environment_name: Option<&'a String>,
-        schemes: &mut HashSet<&'a str>,
+        schemes: &mut HashSet<String>,
     ) {
         check_name(
             resolver_registration.resolver.as_ref(),
@@ -1065,8 +2473,13 @@ impl<'a> ValidationContext<'a> {
             &mut self.errors,
         );
         if let Some(scheme) = resolver_registration.scheme.as_ref() {
-            if !schemes.insert(scheme.as_str()) {
-                self.errors.push(Error::duplicate_field("ResolverRegistration", "scheme", scheme));
+            // URL schemes are case-insensitive, so require the canonical lowercase form and
+            // treat schemes differing only by case as duplicates.
+            if scheme.chars().any(|c| c.is_ascii_uppercase()) {
+                self.push_error(Error::invalid_field("ResolverRegistration", "scheme"));
+            }
+            if !schemes.insert(scheme.to_ascii_lowercase()) {
+                self.push_error(Error::duplicate_field("ResolverRegistration", "scheme", scheme));
             }
         }
     }
@@ -1085,10 +2498,10 @@ impl<'a> ValidationContext<'a> {
                 self.validate_child_ref(ty, "source", &child_ref, OfferType::Static);
             }
             Some(_) => {
-                self.errors.push(Error::invalid_field(ty, "source"));
+                self.push_error(Error::invalid_field(ty, "source"));
             }
             None => {
-                self.errors.push(Error::missing_field(ty, "source"));
+                self.push_error(Error::missing_field(ty, "source"));
             }
         }
 
@@ -1096,7 +2509,7 @@ impl<'a> ValidationContext<'a> {
         if let Some(source) = source {
             if let Some(env_name) = &environment_name {
                 let target = DependencyNode::Environment(env_name);
-                self.strong_dependencies.add_edge(source, target);
+                self.add_dependency_edge(source, target);
             }
         }
     }
@@ -1105,21 +2518,22 @@ impl<'a> ValidationContext<'a> {
         if check_name(service.name.as_ref(), "Service", "name", &mut self.errors) {
             let name = service.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Service", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Service", "name", name.as_str()));
             }
             self.all_services.insert(name);
         }
         match as_builtin {
             true => {
                 if let Some(path) = service.source_path.as_ref() {
-                    self.errors.push(Error::extraneous_source_path("Service", path))
+                    self.push_error(Error::extraneous_source_path("Service", path))
                 }
             }
             false => {
-                check_path(
+                check_path_with_depth(
                     service.source_path.as_ref(),
                     "Service",
                     "source_path",
+                    self.options.max_path_depth,
                     &mut self.errors,
                 );
             }
@@ -1130,21 +2544,22 @@ impl<'a> ValidationContext<'a> {
         if check_name(protocol.name.as_ref(), "Protocol", "name", &mut self.errors) {
             let name = protocol.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Protocol", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Protocol", "name", name.as_str()));
             }
             self.all_protocols.insert(name);
         }
         match as_builtin {
             true => {
                 if let Some(path) = protocol.source_path.as_ref() {
-                    self.errors.push(Error::extraneous_source_path("Protocol", path))
+                    self.push_error(Error::extraneous_source_path("Protocol", path))
                 }
             }
             false => {
-                check_path(
+                check_path_with_depth(
                     protocol.source_path.as_ref(),
                     "Protocol",
                     "source_path",
+                    self.options.max_path_depth,
                     &mut self.errors,
                 );
             }
@@ -1155,27 +2570,30 @@ impl<'a> ValidationContext<'a> {
         if check_name(directory.name.as_ref(), "Directory", "name", &mut self.errors) {
             let name = directory.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Directory", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Directory", "name", name.as_str()));
             }
-            self.all_directories.insert(name);
+            self.all_directories.insert(name, directory.rights);
         }
         match as_builtin {
             true => {
                 if let Some(path) = directory.source_path.as_ref() {
-                    self.errors.push(Error::extraneous_source_path("Directory", path))
+                    self.push_error(Error::extraneous_source_path("Directory", path))
                 }
             }
             false => {
-                check_path(
+                check_path_with_depth(
                     directory.source_path.as_ref(),
                     "Directory",
                     "source_path",
+                    self.options.max_path_depth,
                     &mut self.errors,
                 );
             }
         }
         if directory.rights.is_none() {
-            self.errors.push(Error::missing_field("Directory", "rights"));
+            self.push_error(Error::missing_field("Directory", "rights"));
+        } else {
+            check_rights(directory.rights.as_ref(), "Directory", &mut self.errors);
         }
     }
 
@@ -1187,41 +2605,89 @@ impl<'a> ValidationContext<'a> {
                 let _ = self.validate_child_ref("Storage", "source", &child, OfferType::Static);
             }
             Some(_) => {
-                self.errors.push(Error::invalid_field("Storage", "source"));
+                self.push_error(Error::invalid_field("Storage", "source"));
             }
             None => {
-                self.errors.push(Error::missing_field("Storage", "source"));
+                self.push_error(Error::missing_field("Storage", "source"));
             }
         };
         if check_name(storage.name.as_ref(), "Storage", "name", &mut self.errors) {
             let name = storage.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Storage", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Storage", "name", name.as_str()));
             }
             self.all_storage_and_sources.insert(name, storage.source.as_ref());
+            if let Some(backing_dir) = storage.backing_dir.as_ref() {
+                self.all_storage_backing_dirs.insert(name, backing_dir.as_str());
+            }
         }
         if storage.storage_id.is_none() {
-            self.errors.push(Error::missing_field("Storage", "storage_id"));
+            self.push_error(Error::missing_field("Storage", "storage_id"));
+        }
+        if check_name(storage.backing_dir.as_ref(), "Storage", "backing_dir", &mut self.errors) {
+            // A storage capability can't back itself; the backing dir must be a (directory)
+            // capability distinct from the storage declaration.
+            if storage.backing_dir == storage.name {
+                self.push_error(Error::invalid_field("Storage", "backing_dir"));
+            }
+        }
+        // `subdir` is resolved inside the backing directory, so it must be a well-formed
+        // relative path; it's meaningful under either `storage_id` scheme.
+        if let Some(subdir) = storage.subdir.as_ref() {
+            check_subdir(Some(subdir), "Storage", self.options.max_path_depth, &mut self.errors);
+        }
+    }
+
+    fn validate_storage_backing_dir(&mut self, storage: &'a fdecl::Storage) {
+        if let Some(fdecl::Ref::Self_(_)) = storage.source.as_ref() {
+            if let Some(backing_dir) = storage.backing_dir.as_ref() {
+                match self.all_directories.get(backing_dir.as_str()) {
+                    None => {
+                        self.push_error(Error::invalid_capability(
+                            "Storage",
+                            "backing_dir",
+                            backing_dir,
+                        ));
+                    }
+                    Some(rights) => {
+                        // Storage hands out per-instance subdirectories of the backing
+                        // directory; none of them should ever be executable.
+                        if let Some(rights) = rights {
+                            if rights.contains(fio::Operations::EXECUTE) {
+                                self.push_error(Error::invalid_storage_backing_rights(
+                                    "Storage",
+                                    "backing_dir",
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
         }
-        check_name(storage.backing_dir.as_ref(), "Storage", "backing_dir", &mut self.errors);
     }
 
     fn validate_runner_decl(&mut self, runner: &'a fdecl::Runner, as_builtin: bool) {
         if check_name(runner.name.as_ref(), "Runner", "name", &mut self.errors) {
             let name = runner.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Runner", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Runner", "name", name.as_str()));
             }
             self.all_runners.insert(name);
         }
         match as_builtin {
             true => {
                 if let Some(path) = runner.source_path.as_ref() {
-                    self.errors.push(Error::extraneous_source_path("Runner", path))
+                    self.push_error(Error::extraneous_source_path("Runner", path))
                 }
             }
             false => {
-                check_path(runner.source_path.as_ref(), "Runner", "source_path", &mut self.errors);
+                check_path_with_depth(
+                    runner.source_path.as_ref(),
+                    "Runner",
+                    "source_path",
+                    self.options.max_path_depth,
+                    &mut self.errors,
+                );
             }
         }
     }
@@ -1230,21 +2696,22 @@ impl<'a> ValidationContext<'a> {
         if check_name(resolver.name.as_ref(), "Resolver", "name", &mut self.errors) {
             let name = resolver.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Resolver", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Resolver", "name", name.as_str()));
             }
             self.all_resolvers.insert(name);
         }
         match as_builtin {
             true => {
                 if let Some(path) = resolver.source_path.as_ref() {
-                    self.errors.push(Error::extraneous_source_path("Resolver", path))
+                    self.push_error(Error::extraneous_source_path("Resolver", path))
                 }
             }
             false => {
-                check_path(
+                check_path_with_depth(
                     resolver.source_path.as_ref(),
                     "Resolver",
                     "source_path",
+                    self.options.max_path_depth,
                     &mut self.errors,
                 );
             }
@@ -1255,6 +2722,7 @@ impl<'a> ValidationContext<'a> {
         &mut self,
         debug: &'a fdecl::DebugRegistration,
         environment_name: Option<&'a String>,
+        debug_target_names: &mut HashSet<&'a str>,
     ) {
         match debug {
             fdecl::DebugRegistration::Protocol(o) => {
@@ -1265,10 +2733,19 @@ impl<'a> ValidationContext<'a> {
                     o.source_name.as_ref(),
                     o.target_name.as_ref(),
                 );
+                if let Some(target_name) = o.target_name.as_ref() {
+                    if !debug_target_names.insert(target_name) {
+                        self.push_error(Error::duplicate_field(
+                            decl,
+                            "target_name",
+                            target_name as &str,
+                        ));
+                    }
+                }
 
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&o.source, &o.source_name) {
                     if !self.all_protocols.contains(&name as &str) {
-                        self.errors.push(Error::invalid_field(decl, "source"));
+                        self.push_error(Error::invalid_field(decl, "source"));
                     }
                 }
 
@@ -1279,7 +2756,7 @@ impl<'a> ValidationContext<'a> {
                 }
             }
             _ => {
-                self.errors.push(Error::invalid_field("Environment", "debug"));
+                self.push_error(Error::invalid_field("Environment", "debug"));
             }
         }
     }
@@ -1299,8 +2776,8 @@ impl<'a> ValidationContext<'a> {
             Some(fdecl::Ref::Child(child)) => {
                 let _ = self.validate_child_ref(decl, "source", &child, OfferType::Static);
             }
-            Some(_) => self.errors.push(Error::invalid_field(decl, "source")),
-            None => self.errors.push(Error::missing_field(decl, "source")),
+            Some(_) => self.push_error(Error::invalid_field(decl, "source")),
+            None => self.push_error(Error::missing_field(decl, "source")),
         }
         check_name(source_name, decl, "source_name", &mut self.errors);
         check_name(target_name, decl, "target_name", &mut self.errors);
@@ -1310,7 +2787,7 @@ impl<'a> ValidationContext<'a> {
         if check_name(event.name.as_ref(), "Event", "name", &mut self.errors) {
             let name = event.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("Event", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("Event", "name", name.as_str()));
             }
         }
     }
@@ -1319,7 +2796,7 @@ impl<'a> ValidationContext<'a> {
         if check_name(event.name.as_ref(), "EventStream", "name", &mut self.errors) {
             let name = event.name.as_ref().unwrap();
             if !self.all_capability_ids.insert(name) {
-                self.errors.push(Error::duplicate_field("EventStream", "name", name.as_str()));
+                self.push_error(Error::duplicate_field("EventStream", "name", name.as_str()));
             }
         }
     }
@@ -1333,8 +2810,8 @@ impl<'a> ValidationContext<'a> {
         ) {
             return;
         }
-        if !self.all_collections.contains(&collection.name as &str) {
-            self.errors.push(Error::invalid_collection(
+        if !self.all_collections.contains_key(&collection.name as &str) {
+            self.push_error(Error::invalid_collection(
                 decl_type,
                 "source",
                 &collection.name as &str,
@@ -1352,7 +2829,7 @@ impl<'a> ValidationContext<'a> {
             if source_instance_filter.is_empty() {
                 // if the  source_instance_filter is empty the offered service will have 0 instances,
                 // which means the offer shouldn't have been created at all.
-                self.errors.push(Error::invalid_field(decl_type, "source_instance_filter"));
+                self.push_error(Error::invalid_field(decl_type, "source_instance_filter"));
             }
         }
         if let Some(renamed_instances) = renamed_instances {
@@ -1360,7 +2837,7 @@ impl<'a> ValidationContext<'a> {
             let mut seen_target_names = HashSet::<String>::new();
             for mapping in renamed_instances {
                 if !seen_target_names.insert(mapping.target_name.clone()) {
-                    self.errors.push(Error::invalid_field(decl_type, "renamed_instances"));
+                    self.push_error(Error::invalid_field(decl_type, "renamed_instances"));
                     break;
                 }
             }
@@ -1374,14 +2851,14 @@ impl<'a> ValidationContext<'a> {
         field: &str,
     ) {
         if !self.all_capability_ids.contains(capability.name.as_str()) {
-            self.errors.push(Error::invalid_capability(decl_type, field, &capability.name));
+            self.push_error(Error::invalid_capability(decl_type, field, &capability.name));
         }
     }
 
     fn validate_storage_source(&mut self, source_name: &String, decl_type: &str) {
         if check_name(Some(source_name), decl_type, "source.storage.name", &mut self.errors) {
             if !self.all_storage_and_sources.contains_key(source_name.as_str()) {
-                self.errors.push(Error::invalid_storage(decl_type, "source", source_name));
+                self.push_error(Error::invalid_storage(decl_type, "source", source_name));
             }
         }
     }
@@ -1389,7 +2866,7 @@ impl<'a> ValidationContext<'a> {
     fn validate_expose_decl(
         &mut self,
         expose: &'a fdecl::Expose,
-        prev_target_ids: &mut HashMap<&'a str, AllowableIds>,
+        prev_target_ids: &mut HashMap<(ExposeTarget, &'a str), AllowableIds>,
     ) {
         match expose {
             fdecl::Expose::Service(e) => {
@@ -1408,7 +2885,7 @@ impl<'a> ValidationContext<'a> {
                 // TODO: Consider bringing this bit into validate_expose_fields.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&e.source, &e.source_name) {
                     if !self.all_services.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        self.push_error(Error::invalid_capability(decl, "source", name));
                     }
                 }
             }
@@ -1428,7 +2905,15 @@ impl<'a> ValidationContext<'a> {
                 // TODO: Consider bringing this bit into validate_expose_fields.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&e.source, &e.source_name) {
                     if !self.all_protocols.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        if self.all_capability_ids.contains(&name as &str) {
+                            // The name exists but belongs to a different capability type;
+                            // say so rather than claiming it doesn't appear at all.
+                            self.push_error(Error::capability_type_mismatch(
+                                decl, "source", name, "protocol",
+                            ));
+                        } else {
+                            self.push_error(Error::invalid_capability(decl, "source", name));
+                        }
                     }
                 }
             }
@@ -1447,27 +2932,42 @@ impl<'a> ValidationContext<'a> {
                 // If the expose source is `self`, ensure we have a corresponding Directory.
                 // TODO: Consider bringing this bit into validate_expose_fields.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&e.source, &e.source_name) {
-                    if !self.all_directories.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
-                    }
-                    if name.starts_with('/') && e.rights.is_none() {
-                        self.errors.push(Error::missing_field(decl, "rights"));
+                    match self.all_directories.get(&name as &str) {
+                        Some(declared_rights) => {
+                            // The expose may narrow the directory's rights but never widen them.
+                            if let (Some(declared), Some(requested)) =
+                                (declared_rights, e.rights.as_ref())
+                            {
+                                if !declared.contains(*requested) {
+                                    self.push_error(Error::rights_escalation(decl, "rights"));
+                                }
+                            }
+                            // Rights may be omitted only when the directory declaration
+                            // carries some to inherit.
+                            if declared_rights.is_none() && e.rights.is_none() {
+                                self.push_error(Error::missing_field(decl, "rights"));
+                            }
+                        }
+                        None => {
+                            self.push_error(Error::invalid_capability(decl, "source", name));
+                        }
                     }
                 }
+                check_rights(e.rights.as_ref(), decl, &mut self.errors);
 
                 // Subdir makes sense when routing, but when exposing to framework the subdirectory
                 // can be exposed directly.
                 match e.target.as_ref() {
                     Some(fdecl::Ref::Framework(_)) => {
                         if e.subdir.is_some() {
-                            self.errors.push(Error::invalid_field(decl, "subdir"));
+                            self.push_error(Error::invalid_field(decl, "subdir"));
                         }
                     }
                     _ => {}
                 }
 
                 if let Some(subdir) = e.subdir.as_ref() {
-                    check_relative_path(Some(subdir), decl, "subdir", &mut self.errors);
+                    check_subdir(Some(subdir), decl, self.options.max_path_depth, &mut self.errors);
                 }
             }
             fdecl::Expose::Runner(e) => {
@@ -1485,7 +2985,7 @@ impl<'a> ValidationContext<'a> {
                 // If the expose source is `self`, ensure we have a corresponding Runner.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&e.source, &e.source_name) {
                     if !self.all_runners.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        self.push_error(Error::invalid_capability(decl, "source", name));
                     }
                 }
             }
@@ -1504,7 +3004,7 @@ impl<'a> ValidationContext<'a> {
                 // If the expose source is `self`, ensure we have a corresponding Resolver.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&e.source, &e.source_name) {
                     if !self.all_resolvers.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        self.push_error(Error::invalid_capability(decl, "source", name));
                     }
                 }
             }
@@ -1522,11 +3022,11 @@ impl<'a> ValidationContext<'a> {
                 );
                 // Exposing to framework from framework should never be valid.
                 if e.target == Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})) {
-                    self.errors.push(Error::invalid_field("ExposeEventStream", "target"));
+                    self.push_error(Error::invalid_field("ExposeEventStream", "target"));
                 }
                 if let Some(scope) = &e.scope {
                     if scope.is_empty() {
-                        self.errors.push(Error::invalid_field(decl, "scope"));
+                        self.push_error(Error::invalid_field(decl, "scope"));
                     }
                     for value in scope {
                         match value {
@@ -1551,11 +3051,11 @@ impl<'a> ValidationContext<'a> {
                 if e.source == Some(fdecl::Ref::Framework(fdecl::FrameworkRef {}))
                     && e.scope == None
                 {
-                    self.errors.push(Error::invalid_field(decl, "source"));
+                    self.push_error(Error::invalid_field(decl, "source"));
                 }
             }
             _ => {
-                self.errors.push(Error::invalid_field("Component", "expose"));
+                self.push_error(Error::invalid_field("Component", "expose"));
             }
         }
     }
@@ -1569,7 +3069,7 @@ impl<'a> ValidationContext<'a> {
         source_name: Option<&String>,
         target_name: Option<&'a String>,
         target: Option<&fdecl::Ref>,
-        prev_child_target_ids: &mut HashMap<&'a str, AllowableIds>,
+        prev_child_target_ids: &mut HashMap<(ExposeTarget, &'a str), AllowableIds>,
     ) {
         match source {
             Some(r) => match r {
@@ -1585,40 +3085,105 @@ impl<'a> ValidationContext<'a> {
                     self.validate_source_collection(c, decl);
                 }
                 _ => {
-                    self.errors.push(Error::invalid_field(decl, "source"));
+                    self.push_error(Error::invalid_field(decl, "source"));
                 }
             },
             None => {
-                self.errors.push(Error::missing_field(decl, "source"));
+                self.push_error(Error::missing_field(decl, "source"));
             }
         }
-        match target {
+        let expose_target = match target {
             Some(r) => match r {
-                fdecl::Ref::Parent(_) => {}
+                fdecl::Ref::Parent(_) => Some(ExposeTarget::Parent),
                 fdecl::Ref::Framework(_) => {
+                    // Only a component's own capabilities may be exposed to the framework;
+                    // the source is the field that's wrong, for every capability type.
                     if source != Some(&fdecl::Ref::Self_(fdecl::SelfRef {})) {
-                        self.errors.push(Error::invalid_field(decl, "target"));
+                        self.push_error(Error::invalid_field(decl, "source"));
                     }
+                    Some(ExposeTarget::Framework)
                 }
                 _ => {
-                    self.errors.push(Error::invalid_field(decl, "target"));
+                    self.push_error(Error::invalid_field(decl, "target"));
+                    None
                 }
             },
             None => {
-                self.errors.push(Error::missing_field(decl, "target"));
+                self.push_error(Error::missing_field(decl, "target"));
+                None
             }
-        }
+        };
         check_name(source_name, decl, "source_name", &mut self.errors);
         if check_name(target_name, decl, "target_name", &mut self.errors) {
-            // TODO: This logic needs to pair the target name with the target before concluding
-            // there's a duplicate.
-            let target_name = target_name.unwrap();
-            if let Some(prev_state) = prev_child_target_ids.insert(target_name, allowable_ids) {
-                if prev_state == AllowableIds::One || prev_state != allowable_ids {
-                    self.errors.push(Error::duplicate_field(decl, "target_name", target_name));
+            // An expose only conflicts with another if they have the same target name *and* the
+            // same target; the same name exposed to parent and to framework is fine.
+            if let Some(expose_target) = expose_target {
+                let target_name = target_name.unwrap();
+                if let Some(prev_state) =
+                    prev_child_target_ids.insert((expose_target, target_name), allowable_ids)
+                {
+                    if prev_state == AllowableIds::One || prev_state != allowable_ids {
+                        self.push_error(Error::duplicate_field(decl, "target_name", target_name));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records a strong-dependency edge in both the cycle-detection graph and the ordered
+    /// edge set returned by `build_dependency_graph`.
+    fn add_dependency_edge(&mut self, source: DependencyNode<'a>, target: DependencyNode<'a>) {
+        self.dependency_edges.insert((source, target));
+        self.strong_dependencies.add_edge(source, target);
+    }
+
+    /// Formats dependency cycles in the same style as `directed_graph::Error::format_cycle`,
+    /// additionally annotating each edge with the capability that induced it when one was
+    /// recorded, e.g. `{{self --(fuchsia.foo.Bar)--> child logger -> self}}`. Edges that were
+    /// translated from a storage capability to its backing directory's provider are labeled
+    /// `storage <name> via <backing_dir>` so the storage's role in the cycle stays visible.
+    fn format_cycles_with_capabilities(
+        &self,
+        err: &directed_graph::Error<DependencyNode<'a>>,
+    ) -> String {
+        let cycles = match err {
+            directed_graph::Error::CyclesDetected(cycles) => cycles,
+        };
+        let mut cycle_strings = Vec::new();
+        for cycle in cycles {
+            let cycle = Self::normalize_cycle(cycle);
+            let mut s = "{".to_string();
+            for (i, node) in cycle.iter().enumerate() {
+                if i > 0 {
+                    match self.dependency_edge_capabilities.get(&(cycle[i - 1], *node)) {
+                        Some(capability) => s.push_str(&format!(" --({})--> ", capability)),
+                        None => s.push_str(" -> "),
+                    }
                 }
+                s.push_str(&node.to_string());
             }
+            s.push('}');
+            cycle_strings.push(s);
         }
+        format!("{{{}}}", cycle_strings.join(", "))
+    }
+
+    /// Rotates a cycle (a closed path whose first and last nodes are equal) so that it
+    /// starts at its smallest node. Which node a graph traversal discovers a cycle from
+    /// depends on hash order, so without this the same cycle could format differently
+    /// from run to run, breaking golden tests that match on the error string.
+    fn normalize_cycle(cycle: &[DependencyNode<'a>]) -> Vec<DependencyNode<'a>> {
+        if cycle.len() < 2 || cycle.first() != cycle.last() {
+            return cycle.to_vec();
+        }
+        // Drop the repeated endpoint, rotate, then close the path again.
+        let body = &cycle[..cycle.len() - 1];
+        let start =
+            body.iter().enumerate().min_by_key(|&(_, node)| node).map(|(i, _)| i).unwrap();
+        let mut rotated: Vec<_> =
+            body[start..].iter().chain(body[..start].iter()).copied().collect();
+        rotated.push(rotated[0]);
+        rotated
     }
 
     /// Adds a strong dependency between two nodes in the dependency graph between `source` and
@@ -1639,9 +3204,16 @@ impl<'a> ValidationContext<'a> {
         let source = source.unwrap();
         let target = target.unwrap();
 
+        let mut storage_annotation: Option<String> = None;
         let source = {
             // A dependency on a storage capability from `self` is really a dependency on the
             // backing dir.  Perform that translation here.
+            //
+            // The exact rule: if `source` is a `Ref::Capability` naming a storage declaration,
+            // or a `Ref::Self_` whose `source_name` matches a storage declaration, the edge is
+            // attributed to the storage's `source` (the provider of the backing directory)
+            // instead. If that source doesn't participate in cycle detection (e.g. `parent`),
+            // no edge is added. All other sources pass through untranslated.
             let possible_storage_name = match (source, source_name) {
                 (DependencyNode::Capability(name), _) => Some(name),
                 (DependencyNode::Self_, Some(name)) => Some(name.as_str()),
@@ -1649,6 +3221,10 @@ impl<'a> ValidationContext<'a> {
             };
             let possible_storage_source =
                 possible_storage_name.map(|name| self.all_storage_and_sources.get(name)).flatten();
+            if possible_storage_source.is_some() {
+                storage_annotation =
+                    Some(self.storage_edge_label(possible_storage_name.unwrap()));
+            }
             let source = possible_storage_source
                 .map(|r| DependencyNode::try_from_ref(*r))
                 .unwrap_or(Some(source));
@@ -1662,7 +3238,23 @@ impl<'a> ValidationContext<'a> {
             // This is already its own error, or is a valid `use from self`, don't report this as a
             // cycle.
         } else {
-            self.strong_dependencies.add_edge(source, target);
+            if let Some(label) = storage_annotation {
+                self.dependency_edge_capabilities.entry((source, target)).or_insert(label);
+            } else if let Some(name) = source_name {
+                self.dependency_edge_capabilities
+                    .entry((source, target))
+                    .or_insert_with(|| name.to_string());
+            }
+            self.add_dependency_edge(source, target);
+        }
+    }
+
+    /// Returns the edge annotation for a dependency that was translated through the named
+    /// storage capability, e.g. `storage data via minfs`.
+    fn storage_edge_label(&self, storage_name: &str) -> String {
+        match self.all_storage_backing_dirs.get(storage_name) {
+            Some(backing_dir) => format!("storage {} via {}", storage_name, backing_dir),
+            None => format!("storage {}", storage_name),
         }
     }
 
@@ -1689,11 +3281,23 @@ impl<'a> ValidationContext<'a> {
             }
             let mut source_instance_filter_entries: HashSet<String> = HashSet::new();
             let mut service_source_names: HashSet<String> = HashSet::new();
+            let mut seen_sources: HashSet<String> = HashSet::new();
             for o in offer_group {
+                // Aggregation requires the offers in the group to come from distinct sources;
+                // repeating the same source is a duplicate offer, not aggregation.
+                if !seen_sources.insert(format!("{:?}", o.source)) {
+                    if let Some(target_name) = o.target_name.as_ref() {
+                        self.push_error(Error::duplicate_field(
+                            "OfferService",
+                            "target_name",
+                            target_name as &str,
+                        ));
+                    }
+                }
                 // Currently only service capabilities can be aggregated
                 match o.source_instance_filter {
                     None => {
-                        self.errors.push(Error::invalid_aggregate_offer(
+                        self.push_error(Error::invalid_aggregate_offer(
                             "source_instance_filter must be set for all aggregate service offers",
                         ));
                     }
@@ -1702,7 +3306,7 @@ impl<'a> ValidationContext<'a> {
                             if !source_instance_filter_entries.insert(instance_name.clone()) {
                                 // If the source instance in the filter has been seen before this means there is a conflicting
                                 // aggregate service offer.
-                                self.errors.push(Error::invalid_aggregate_offer(format!("Conflicting source_instance_filter in aggregate service offer, instance_name '{}' seen in filter lists multiple times", instance_name)));
+                                self.push_error(Error::invalid_aggregate_offer(format!("Conflicting source_instance_filter in aggregate service offer, instance_name '{}' seen in filter lists multiple times", instance_name)));
                             }
                         }
                     }
@@ -1713,7 +3317,7 @@ impl<'a> ValidationContext<'a> {
                 );
             }
             if service_source_names.len() > 1 {
-                self.errors.push(Error::invalid_aggregate_offer(format!(
+                self.push_error(Error::invalid_aggregate_offer(format!(
                     "All aggregate service offers must have the same source_name, saw {}. Use renamed_instances to rename instance names to avoid conflict.",
                     service_source_names.into_iter().sorted().collect::<Vec<String>>().join(", ")
                 )));
@@ -1745,9 +3349,13 @@ impl<'a> ValidationContext<'a> {
                 // TODO: Consider bringing this bit into validate_offer_fields
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&o.source, &o.source_name) {
                     if !self.all_services.contains(&name as &str) {
-                        self.errors.push(Error::invalid_field(decl, "source"));
+                        self.push_error(Error::invalid_field(decl, "source"));
                     }
                 }
+                // `fdecl::OfferService` carries no `dependency_type`, so a service offer
+                // always contributes a strong edge; there is no weak form to skip here. If
+                // the FIDL table ever grows that field, mirror the weak-offer handling in
+                // the protocol arm below so weak service offers can break cycles too.
                 self.add_strong_dep(
                     o.source_name.as_ref(),
                     DependencyNode::try_from_ref(o.source.as_ref()),
@@ -1769,7 +3377,7 @@ impl<'a> ValidationContext<'a> {
                     offer_type,
                 );
                 if o.dependency_type.is_none() {
-                    self.errors.push(Error::missing_field(decl, "dependency_type"));
+                    self.push_error(Error::missing_field(decl, "dependency_type"));
                 } else if o.dependency_type == Some(fdecl::DependencyType::Strong) {
                     self.add_strong_dep(
                         o.source_name.as_ref(),
@@ -1782,7 +3390,13 @@ impl<'a> ValidationContext<'a> {
                 // TODO: Consider bringing this bit into validate_offer_fields.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&o.source, &o.source_name) {
                     if !self.all_protocols.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        if self.all_capability_ids.contains(&name as &str) {
+                            self.push_error(Error::capability_type_mismatch(
+                                decl, "source", name, "protocol",
+                            ));
+                        } else {
+                            self.push_error(Error::invalid_capability(decl, "source", name));
+                        }
                     }
                 }
             }
@@ -1801,7 +3415,7 @@ impl<'a> ValidationContext<'a> {
                     offer_type,
                 );
                 if o.dependency_type.is_none() {
-                    self.errors.push(Error::missing_field(decl, "dependency_type"));
+                    self.push_error(Error::missing_field(decl, "dependency_type"));
                 } else if o.dependency_type == Some(fdecl::DependencyType::Strong) {
                     self.add_strong_dep(
                         o.source_name.as_ref(),
@@ -1815,14 +3429,34 @@ impl<'a> ValidationContext<'a> {
                     if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) =
                         (&o.source, &o.source_name)
                     {
-                        if !self.all_directories.contains(&name as &str) {
-                            self.errors.push(Error::invalid_capability(decl, "source", name));
+                        match self.all_directories.get(&name as &str) {
+                            Some(declared_rights) => {
+                                // The offer may narrow the directory's rights but never widen
+                                // them.
+                                if let (Some(declared), Some(requested)) =
+                                    (declared_rights, o.rights.as_ref())
+                                {
+                                    if !declared.contains(*requested) {
+                                        self.push_error(Error::rights_escalation(decl, "rights"));
+                                    }
+                                }
+                            }
+                            None => {
+                                self.push_error(Error::invalid_capability(decl, "source", name));
+                            }
                         }
                     }
                 }
 
+                check_rights(o.rights.as_ref(), decl, &mut self.errors);
+
                 if let Some(subdir) = o.subdir.as_ref() {
-                    check_relative_path(Some(subdir), "OfferDirectory", "subdir", &mut self.errors);
+                    check_subdir(
+                        Some(subdir),
+                        "OfferDirectory",
+                        self.options.max_path_depth,
+                        &mut self.errors,
+                    );
                 }
             }
             fdecl::Offer::Storage(o) => {
@@ -1849,7 +3483,11 @@ impl<'a> ValidationContext<'a> {
                                 .unwrap_or(&None),
                         ) {
                             if let Some(target) = DependencyNode::try_from_ref(o.target.as_ref()) {
-                                self.strong_dependencies.add_edge(source, target);
+                                let label = self.storage_edge_label(source_name.as_str());
+                                self.dependency_edge_capabilities
+                                    .entry((source, target))
+                                    .or_insert(label);
+                                self.add_dependency_edge(source, target);
                             }
                         }
                     }
@@ -1877,7 +3515,7 @@ impl<'a> ValidationContext<'a> {
                 // If the offer source is `self`, ensure we have a corresponding Runner.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&o.source, &o.source_name) {
                     if !self.all_runners.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        self.push_error(Error::invalid_capability(decl, "source", name));
                     }
                 }
                 self.add_strong_dep(
@@ -1905,7 +3543,7 @@ impl<'a> ValidationContext<'a> {
                 // corresponding Resolver.
                 if let (Some(fdecl::Ref::Self_(_)), Some(ref name)) = (&o.source, &o.source_name) {
                     if !self.all_resolvers.contains(&name as &str) {
-                        self.errors.push(Error::invalid_capability(decl, "source", name));
+                        self.push_error(Error::invalid_capability(decl, "source", name));
                     }
                 }
                 self.add_strong_dep(
@@ -1921,7 +3559,7 @@ impl<'a> ValidationContext<'a> {
                 self.validate_event_stream_offer_fields(e, offer_type);
             }
             _ => {
-                self.errors.push(Error::invalid_field("Component", "offer"));
+                self.push_error(Error::invalid_field("Component", "offer"));
             }
         }
     }
@@ -1953,11 +3591,11 @@ impl<'a> ValidationContext<'a> {
                 }
             }
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl_type, field_name));
+                self.push_error(Error::invalid_field(decl_type, field_name));
                 None
             }
             None => {
-                self.errors.push(Error::missing_field(decl_type, field_name));
+                self.push_error(Error::missing_field(decl_type, field_name));
                 None
             }
         }
@@ -1988,8 +3626,8 @@ impl<'a> ValidationContext<'a> {
             Some(fdecl::Ref::Collection(c)) if collection_source == CollectionSource::Allow => {
                 self.validate_source_collection(c, decl)
             }
-            Some(_) => self.errors.push(Error::invalid_field(decl, "source")),
-            None => self.errors.push(Error::missing_field(decl, "source")),
+            Some(_) => self.push_error(Error::invalid_field(decl, "source")),
+            None => self.push_error(Error::missing_field(decl, "source")),
         }
         check_offer_availability(decl, availability, source, source_name, &mut self.errors);
         check_offer_name(source_name, decl, "source_name", offer_type, &mut self.errors);
@@ -2008,11 +3646,16 @@ impl<'a> ValidationContext<'a> {
             Some(fdecl::Ref::Collection(c)) => {
                 self.validate_target_collection(decl, allowable_names, c, target_name);
             }
+            // An offer from `self` back to `self` gets a pointed message; a bare `Self_`
+            // target is otherwise just an invalid target.
+            Some(fdecl::Ref::Self_(_)) if matches!(source, Some(fdecl::Ref::Self_(_))) => {
+                self.push_error(Error::offer_target_equals_source(decl, "self"));
+            }
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl, "target"));
+                self.push_error(Error::invalid_field(decl, "target"));
             }
             None => {
-                self.errors.push(Error::missing_field(decl, "target"));
+                self.push_error(Error::missing_field(decl, "target"));
             }
         }
         check_offer_name(target_name, decl, "target_name", offer_type, &mut self.errors);
@@ -2028,18 +3671,21 @@ impl<'a> ValidationContext<'a> {
         offer_type: OfferType,
     ) {
         if source_name.is_none() {
-            self.errors.push(Error::missing_field(decl, "source_name"));
+            self.push_error(Error::missing_field(decl, "source_name"));
         }
         match source {
             Some(fdecl::Ref::Parent(_) | fdecl::Ref::VoidType(_)) => (),
             Some(fdecl::Ref::Self_(_)) => {
-                self.validate_storage_source(source_name.unwrap(), decl);
+                // `source_name` may be absent; the missing_field error was recorded above.
+                if let Some(source_name) = source_name {
+                    self.validate_storage_source(source_name, decl);
+                }
             }
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl, "source"));
+                self.push_error(Error::invalid_field(decl, "source"));
             }
             None => {
-                self.errors.push(Error::missing_field(decl, "source"));
+                self.push_error(Error::missing_field(decl, "source"));
             }
         }
         check_offer_availability(decl, availability, source, source_name, &mut self.errors);
@@ -2055,20 +3701,20 @@ impl<'a> ValidationContext<'a> {
         check_name(event_stream.source_name.as_ref(), decl, "source_name", &mut self.errors);
         if event_stream.target == Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})) {
             // Expose to framework from framework is never valid.
-            self.errors.push(Error::invalid_field("OfferEventStream", "target"));
+            self.push_error(Error::invalid_field("OfferEventStream", "target"));
         }
         let source_name =
             event_stream.source_name.as_ref().map(|value| value.as_str()).unwrap_or("");
         match (&event_stream.filter, source_name, &event_stream.source) {
             (Some(_), "capability_requested", _) | (Some(_), "directory_ready", _) => {}
             (Some(_), _, Some(fdecl::Ref::Framework(_))) => {
-                self.errors.push(Error::invalid_field(decl, "filter"));
+                self.push_error(Error::invalid_field(decl, "filter"));
             }
             _ => {}
         }
         if let Some(scope) = &event_stream.scope {
             if scope.is_empty() {
-                self.errors.push(Error::invalid_field(decl, "scope"));
+                self.push_error(Error::invalid_field(decl, "scope"));
             }
             for value in scope {
                 match value {
@@ -2079,7 +3725,7 @@ impl<'a> ValidationContext<'a> {
                         self.validate_collection_ref("OfferEventStream", "scope", &collection);
                     }
                     _ => {
-                        self.errors.push(Error::invalid_field("OfferEventStream", "scope"));
+                        self.push_error(Error::invalid_field("OfferEventStream", "scope"));
                     }
                 }
             }
@@ -2093,10 +3739,10 @@ impl<'a> ValidationContext<'a> {
                 | fdecl::Ref::VoidType(_),
             ) => {}
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl, "source"));
+                self.push_error(Error::invalid_field(decl, "source"));
             }
             None => {
-                self.errors.push(Error::missing_field(decl, "source"));
+                self.push_error(Error::missing_field(decl, "source"));
             }
         };
 
@@ -2119,7 +3765,7 @@ impl<'a> ValidationContext<'a> {
                 .or_insert(HashMap::new())
                 .insert(target_name, AllowableIds::One)
             {
-                self.errors.push(Error::duplicate_field(decl, "target_name", target_name as &str));
+                self.push_error(Error::duplicate_field(decl, "target_name", target_name as &str));
             }
         }
         check_name(event_stream.target_name.as_ref(), decl, "target_name", &mut self.errors);
@@ -2134,15 +3780,18 @@ impl<'a> ValidationContext<'a> {
             offer_type,
             &mut self.errors,
         );
+        check_event_filter(event.filter.as_ref(), decl, &mut self.errors);
 
-        // Only parent, framework, and void are valid.
+        // Only parent, framework, and void are valid. Events are synthesized by the
+        // framework or routed down from the parent; a component can never be the
+        // source of an event, so `Ref::Self_` is always rejected here.
         match event.source {
             Some(fdecl::Ref::Parent(_) | fdecl::Ref::Framework(_) | fdecl::Ref::VoidType(_)) => {}
             Some(_) => {
-                self.errors.push(Error::invalid_field(decl, "source"));
+                self.push_error(Error::invalid_field(decl, "source"));
             }
             None => {
-                self.errors.push(Error::missing_field(decl, "source"));
+                self.push_error(Error::missing_field(decl, "source"));
             }
         };
 
@@ -2163,7 +3812,7 @@ impl<'a> ValidationContext<'a> {
                 .or_insert(HashMap::new())
                 .insert(target_name, AllowableIds::One)
             {
-                self.errors.push(Error::duplicate_field(decl, "target_name", target_name as &str));
+                self.push_error(Error::duplicate_field(decl, "target_name", target_name as &str));
             }
         }
         check_offer_name(
@@ -2199,8 +3848,10 @@ impl<'a> ValidationContext<'a> {
             valid = false;
         }
         if child.collection.is_some() {
-            self.errors
-                .push(Error::extraneous_field(decl, format!("{}.child.collection", field_name)));
+            self.push_error(Error::extraneous_field(
+                decl,
+                format!("{}.child.collection", field_name),
+            ));
             valid = false;
         }
         if !valid {
@@ -2210,7 +3861,17 @@ impl<'a> ValidationContext<'a> {
         // Ensure the child exists.
         let name: &str = &child.name;
         if !self.all_children.contains_key(name) {
-            self.errors.push(Error::invalid_child(decl, field_name, name));
+            if offer_type == OfferType::Dynamic {
+                // A dynamic offer may reference a dynamic child, but then the reference must
+                // say which collection the child lives in; without it the reference can't
+                // identify a dynamic child, and existence can't be checked here.
+                self.push_error(Error::invalid_field(
+                    decl,
+                    format!("{}.child.collection", field_name),
+                ));
+            } else {
+                self.push_error(Error::invalid_child(decl, field_name, name));
+            }
             return false;
         }
 
@@ -2268,8 +3929,8 @@ impl<'a> ValidationContext<'a> {
         }
 
         // Ensure the collection exists.
-        if !self.all_collections.contains(&collection.name as &str) {
-            self.errors.push(Error::invalid_collection(decl, field_name, &collection.name as &str));
+        if !self.all_collections.contains_key(&collection.name as &str) {
+            self.push_error(Error::invalid_collection(decl, field_name, &collection.name as &str));
             return false;
         }
 
@@ -2299,7 +3960,7 @@ impl<'a> ValidationContext<'a> {
                 .or_insert(HashMap::new());
             if let Some(prev_state) = names_for_target.insert(target_name, allowable_names) {
                 if prev_state == AllowableIds::One || prev_state != allowable_names {
-                    self.errors.push(Error::duplicate_field(
+                    self.push_error(Error::duplicate_field(
                         decl,
                         "target_name",
                         target_name as &str,
@@ -2311,7 +3972,7 @@ impl<'a> ValidationContext<'a> {
                     && dependency.unwrap_or(fdecl::DependencyType::Strong)
                         == fdecl::DependencyType::Strong
                 {
-                    self.errors.push(Error::offer_target_equals_source(decl, &child.name as &str));
+                    self.push_error(Error::offer_target_equals_source(decl, &child.name as &str));
                 }
             }
         }
@@ -2334,7 +3995,7 @@ impl<'a> ValidationContext<'a> {
                 .or_insert(HashMap::new());
             if let Some(prev_state) = names_for_target.insert(target_name, allowable_names) {
                 if prev_state == AllowableIds::One || prev_state != allowable_names {
-                    self.errors.push(Error::duplicate_field(
+                    self.push_error(Error::duplicate_field(
                         decl,
                         "target_name",
                         target_name as &str,
@@ -2357,8 +4018,8 @@ impl<'a> ValidationContext<'a> {
             Some(fdecl::Ref::Collection(c)) => {
                 self.validate_collection_ref(decl, "target", &c);
             }
-            Some(_) => self.errors.push(Error::invalid_field(decl, "target")),
-            None => self.errors.push(Error::missing_field(decl, "target")),
+            Some(_) => self.push_error(Error::invalid_field(decl, "target")),
+            None => self.push_error(Error::missing_field(decl, "target")),
         }
     }
 }
@@ -2486,7 +4147,7 @@ mod tests {
                     decl.children = Some(children);
                     let result = Err(ErrorList::new(vec![
                         Error::dependency_cycle(
-                            directed_graph::Error::CyclesDetected([vec!["child a", "child b", "child a"]].iter().cloned().collect()).format_cycle()),
+                            "{{child a --(thing)--> child b --(thing)--> child a}}".to_string()),
                     ]));
                     validate_test(decl, result);
                 }
@@ -2765,6 +4426,49 @@ mod tests {
                 ])),
             ],
         },
+        test_validate_use_reports_all_overlaps_in_chain => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("abc".to_string()),
+                        target_path: Some("/foo/bar".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::UseDirectory::EMPTY
+                    }),
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("def".to_string()),
+                        target_path: Some("/foo/bar/baz".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::UseDirectory::EMPTY
+                    }),
+                    fdecl::Use::Service(fdecl::UseService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("space".to_string()),
+                        target_path: Some("/foo/bar/baz/qux/fuchsia.logger.Log".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::UseService::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            results = vec![
+                Err(ErrorList::new(vec![
+                    Error::invalid_path_overlap(
+                        "UseDirectory", "/foo/bar", "UseDirectory", "/foo/bar/baz"),
+                    Error::invalid_path_overlap(
+                        "UseDirectory", "/foo/bar", "UseService", "/foo/bar/baz/qux/fuchsia.logger.Log"),
+                    Error::invalid_path_overlap(
+                        "UseDirectory", "/foo/bar/baz", "UseService", "/foo/bar/baz/qux/fuchsia.logger.Log"),
+                ])),
+            ],
+        },
         test_validate_use_disallows_pkg => {
             input = {
                 let mut decl = new_component_decl();
@@ -3012,3097 +4716,2552 @@ mod tests {
                 Error::missing_field("Program", "info")
             ])),
         },
-        test_validate_uses_invalid_identifiers_service => {
+        test_validate_program_info_duplicate_key => {
             input = {
                 let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Service(fdecl::UseService {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        source_name: Some("foo/".to_string()),
-                        target_path: Some("/".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::UseService::EMPTY
+                decl.program = Some(fdecl::Program {
+                    runner: Some("elf".to_string()),
+                    info: Some(fdata::Dictionary {
+                        entries: Some(vec![
+                            fdata::DictionaryEntry {
+                                key: "binary".to_string(),
+                                value: Some(Box::new(fdata::DictionaryValue::Str(
+                                    "bin/app".to_string(),
+                                ))),
+                            },
+                            fdata::DictionaryEntry {
+                                key: "binary".to_string(),
+                                value: Some(Box::new(fdata::DictionaryValue::Str(
+                                    "bin/other".to_string(),
+                                ))),
+                            },
+                        ]),
+                        ..fdata::Dictionary::EMPTY
                     }),
-                ]);
+                    ..fdecl::Program::EMPTY
+                });
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseService", "source_name"),
-                Error::invalid_field("UseService", "target_path"),
+                Error::duplicate_field("Program", "info.key", "binary"),
             ])),
         },
-        test_validate_uses_invalid_identifiers_protocol => {
+        test_validate_program_info_empty_key => {
             input = {
                 let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Protocol(fdecl::UseProtocol {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        source_name: Some("foo/".to_string()),
-                        target_path: Some("/".to_string()),
-                        ..fdecl::UseProtocol::EMPTY
+                decl.program = Some(fdecl::Program {
+                    runner: Some("elf".to_string()),
+                    info: Some(fdata::Dictionary {
+                        entries: Some(vec![
+                            fdata::DictionaryEntry {
+                                key: "".to_string(),
+                                value: Some(Box::new(fdata::DictionaryValue::Str(
+                                    "bin/app".to_string(),
+                                ))),
+                            },
+                        ]),
+                        ..fdata::Dictionary::EMPTY
                     }),
-                ]);
+                    ..fdecl::Program::EMPTY
+                });
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseProtocol", "source_name"),
-                Error::invalid_field("UseProtocol", "target_path"),
+                Error::empty_field("Program", "info.key"),
             ])),
         },
-        test_validate_uses_invalid_identifiers_directory => {
+        test_validate_directory_rights_empty => {
             input = {
                 let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::Operations::empty()),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
                 decl.uses = Some(vec![
                     fdecl::Use::Directory(fdecl::UseDirectory {
                         dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        source_name: Some("foo/".to_string()),
-                        target_path: Some("/".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: Some("/foo".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("data".to_string()),
+                        target_path: Some("/data".to_string()),
+                        rights: Some(fio::Operations::empty()),
+                        subdir: None,
                         ..fdecl::UseDirectory::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseDirectory", "source_name"),
-                Error::invalid_field("UseDirectory", "target_path"),
-                Error::invalid_field("UseDirectory", "subdir"),
+                Error::invalid_field("Directory", "rights"),
+                Error::invalid_field("UseDirectory", "rights"),
             ])),
         },
-        test_validate_uses_invalid_identifiers_storage => {
+        test_validate_expose_directory_rights_escalation => {
             input = {
                 let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Storage(fdecl::UseStorage {
-                        source_name: Some("/cache".to_string()),
-                        target_path: Some("/".to_string()),
-                        ..fdecl::UseStorage::EMPTY
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
                     }),
-                    fdecl::Use::Storage(fdecl::UseStorage {
-                        source_name: Some("temp".to_string()),
-                        target_path: Some("tmp".to_string()),
-                        ..fdecl::UseStorage::EMPTY
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseStorage", "source_name"),
-                Error::invalid_field("UseStorage", "target_path"),
-                Error::invalid_field("UseStorage", "target_path"),
+                Error::rights_escalation("ExposeDirectory", "rights"),
             ])),
         },
-        test_validate_uses_invalid_identifiers_event => {
+        test_validate_expose_directory_rights_inherited => {
             input = {
                 let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        source_name: Some("/foo".to_string()),
-                        target_name: Some("/foo".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        source_name: Some("started".to_string()),
-                        target_name: Some("started".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
-                        name: Some("bar".to_string()),
-                        subscriptions: Some(vec!["a".to_string(), "b".to_string()].into_iter().map(|name| fdecl::EventSubscription {
-                            event_name: Some(name),
-                            ..fdecl::EventSubscription::EMPTY
-                        }).collect()),
-                        ..fdecl::UseEventStreamDeprecated::EMPTY
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::R_STAR_DIR),
+                        ..fdecl::Directory::EMPTY
                     }),
-                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
-                        name: Some("bleep".to_string()),
-                        subscriptions: Some(vec![fdecl::EventSubscription {
-                            event_name: Some("started".to_string()),
-                            ..fdecl::EventSubscription::EMPTY
-                        }]),
-                        ..fdecl::UseEventStreamDeprecated::EMPTY
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        // Omitted: inherited from the Directory declaration.
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
                     }),
                 ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseEvent", "source"),
-                Error::invalid_field("UseEvent", "source_name"),
-                Error::invalid_field("UseEvent", "target_name"),
-                Error::event_stream_event_not_found("UseEventStream", "events", "a".to_string()),
-                Error::event_stream_event_not_found("UseEventStream", "events", "b".to_string()),
-            ])),
+            result = Ok(()),
         },
-        test_validate_uses_missing_source => {
+        test_validate_expose_directory_rights_required => {
             input = {
-                fdecl::Component {
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
-                                name: "this-storage-doesnt-exist".to_string(),
-                            })),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        })
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: None,
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                ]);
+                decl
             },
+            // With no rights on the declaration there is nothing to inherit, so the
+            // expose must spell them out.
             result = Err(ErrorList::new(vec![
-                Error::invalid_capability("UseProtocol", "source", "this-storage-doesnt-exist"),
+                Error::missing_field("Directory", "rights"),
+                Error::missing_field("ExposeDirectory", "rights"),
             ])),
         },
-        test_validate_uses_invalid_child => {
+        test_validate_offer_directory_rights_subset => {
             input = {
-                fdecl::Component {
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                        fdecl::Use::Service(fdecl::UseService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
-                            source_name: Some("service_name".to_string()),
-                            target_path: Some("/svc/service_name".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::UseService::EMPTY
-                        }),
-                        fdecl::Use::Directory(fdecl::UseDirectory {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
-                            source_name: Some("DirectoryName".to_string()),
-                            target_path: Some("/data/DirectoryName".to_string()),
-                            rights: Some(fio::Operations::CONNECT),
-                            subdir: None,
-                            ..fdecl::UseDirectory::EMPTY
-                        }),
-                        fdecl::Use::Event(fdecl::UseEvent {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
-                            source_name: Some("abc".to_string()),
-                            target_name: Some("abc".to_string()),
-                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                            ..fdecl::UseEvent::EMPTY
-                        }),
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_child("UseEvent", "source", "no-such-child"),
-                Error::invalid_child("UseProtocol", "source", "no-such-child"),
-                Error::invalid_child("UseService", "source", "no-such-child"),
-                Error::invalid_child("UseDirectory", "source", "no-such-child"),
-            ])),
+            result = Ok(()),
         },
-        test_validate_use_from_child_offer_to_child_strong_cycle => {
+        test_validate_offer_directory_rights_escalation => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Service(fdecl::Service {
-                            name: Some("a".to_string()),
-                            source_path: Some("/a".to_string()),
-                            ..fdecl::Service::EMPTY
-                        })]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                        fdecl::Use::Service(fdecl::UseService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("service_name".to_string()),
-                            target_path: Some("/svc/service_name".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::UseService::EMPTY
-                        }),
-                        fdecl::Use::Directory(fdecl::UseDirectory {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("DirectoryName".to_string()),
-                            target_path: Some("/data/DirectoryName".to_string()),
-                            rights: Some(fio::Operations::CONNECT),
-                            subdir: None,
-                            ..fdecl::UseDirectory::EMPTY
-                        }),
-                        fdecl::Use::Event(fdecl::UseEvent {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("abc".to_string()),
-                            target_name: Some("abc".to_string()),
-                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                            ..fdecl::UseEvent::EMPTY
-                        })
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
-                        })
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{self -> child child -> self}}".to_string()),
+                Error::rights_escalation("OfferDirectory", "rights"),
             ])),
         },
-        test_validate_use_from_child_storage_no_cycle => {
+        test_validate_use_directory_subdir_dotdot => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("cdata".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None } )),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        }),
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("pdata".to_string()),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        }),
-                    ]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child1".to_string(), collection: None})),
-                            source_name: Some("a".to_string()),
-                            target_path: Some("/svc/a".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Storage(fdecl::OfferStorage {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("cdata".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("cdata".to_string()),
-                            ..fdecl::OfferStorage::EMPTY
-                        }),
-                        fdecl::Offer::Storage(fdecl::OfferStorage {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("pdata".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("pdata".to_string()),
-                            ..fdecl::OfferStorage::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("data".to_string()),
+                        target_path: Some("/data".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: Some("foo/../bar".to_string()),
+                        ..fdecl::UseDirectory::EMPTY
+                    }),
+                ]);
+                decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("UseDirectory", "subdir"),
+            ])),
         },
-        test_validate_use_from_child_storage_cycle => {
+        test_validate_expose_directory_subdir_dotdot => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("data".to_string()),
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        }),
-                    ]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("a".to_string()),
-                            target_path: Some("/svc/a".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Storage(fdecl::OfferStorage {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("data".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                            target_name: Some("data".to_string()),
-                            ..fdecl::OfferStorage::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: Some("..".to_string()),
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                ]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{self -> child child -> self}}".to_string()),
+                Error::invalid_field("ExposeDirectory", "subdir"),
             ])),
         },
-        test_validate_storage_strong_cycle_between_children => {
+        test_validate_offer_directory_subdir_dotdot => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("data".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None } )),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        })
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Storage(fdecl::OfferStorage {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("data".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            target_name: Some("data".to_string()),
-                            ..fdecl::OfferStorage::EMPTY
-                        }),
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: Some("foo/..".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("OfferDirectory", "subdir"),
+            ])),
+        },
+        test_validate_directory_subdir_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::RW_STAR_DIR),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
+                decl.uses = Some(vec![
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("data".to_string()),
+                        target_path: Some("/data".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: Some("".to_string()),
+                        ..fdecl::UseDirectory::EMPTY
+                    }),
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: Some("".to_string()),
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: Some("".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::empty_field("UseDirectory", "subdir"),
+                Error::empty_field("ExposeDirectory", "subdir"),
+                Error::empty_field("OfferDirectory", "subdir"),
+            ])),
+        },
+        test_validate_use_event_filter_duplicate_key => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("directory_ready".to_string()),
+                        target_name: Some("directory_ready".to_string()),
+                        filter: Some(fdata::Dictionary {
+                            entries: Some(vec![
+                                fdata::DictionaryEntry {
+                                    key: "path".to_string(),
+                                    value: Some(Box::new(fdata::DictionaryValue::Str(
+                                        "/diagnostics".to_string(),
+                                    ))),
+                                },
+                                fdata::DictionaryEntry {
+                                    key: "path".to_string(),
+                                    value: Some(Box::new(fdata::DictionaryValue::Str(
+                                        "/other".to_string(),
+                                    ))),
+                                },
+                            ]),
+                            ..fdata::Dictionary::EMPTY
                         }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                        ..fdecl::UseEvent::EMPTY
+                    }),
+                ]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{child child1 -> child child2 -> child child1}}".to_string()),
+                Error::duplicate_field("UseEvent", "filter.key", "path"),
             ])),
         },
-        test_validate_strong_cycle_between_children_through_environment_debug => {
+        test_validate_offer_event_filter_empty_key => {
             input = {
-                fdecl::Component {
-                    environments: Some(vec![
-                        fdecl::Environment {
-                            name: Some("env".to_string()),
-                            extends: Some(fdecl::EnvironmentExtends::Realm),
-                            debug_capabilities: Some(vec![
-                                fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                                    source_name: Some("fuchsia.foo.Bar".to_string()),
-                                    target_name: Some("fuchsia.foo.Bar".to_string()),
-                                    ..fdecl::DebugProtocolRegistration::EMPTY
-                                }),
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("started".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("started".to_string()),
+                        filter: Some(fdata::Dictionary {
+                            entries: Some(vec![
+                                fdata::DictionaryEntry {
+                                    key: "".to_string(),
+                                    value: Some(Box::new(fdata::DictionaryValue::Str(
+                                        "/diagnostics".to_string(),
+                                    ))),
+                                },
                             ]),
-                            ..fdecl::Environment::EMPTY
-                        },
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
+                            ..fdata::Dictionary::EMPTY
                         }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            environment: Some("env".to_string()),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                ]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{child child1 -> environment env -> child child2 -> child child1}}".to_string()),
+                Error::empty_field("OfferEvent", "filter.key"),
             ])),
         },
-        test_validate_strong_cycle_between_children_through_environment_runner => {
+        test_validate_offer_storage_from_framework => {
             input = {
-                fdecl::Component {
-                    environments: Some(vec![
-                        fdecl::Environment {
-                            name: Some("env".to_string()),
-                            extends: Some(fdecl::EnvironmentExtends::Realm),
-                            runners: Some(vec![
-                                fdecl::RunnerRegistration {
-                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                                    source_name: Some("coff".to_string()),
-                                    target_name: Some("coff".to_string()),
-                                    ..fdecl::RunnerRegistration::EMPTY
-                                }
-                            ]),
-                            ..fdecl::Environment::EMPTY
-                        },
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            environment: Some("env".to_string()),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("data".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
             },
+            // Storage is never a framework capability; only parent, self, and void are
+            // accepted as storage offer sources.
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{child child1 -> environment env -> child child2 -> child child1}}".to_string()),
+                Error::invalid_field("OfferStorage", "source"),
             ])),
         },
-        test_validate_strong_cycle_between_children_through_environment_resolver => {
+        test_validate_offer_storage_from_child => {
             input = {
-                fdecl::Component {
-                    environments: Some(vec![
-                        fdecl::Environment {
-                            name: Some("env".to_string()),
-                            extends: Some(fdecl::EnvironmentExtends::Realm),
-                            resolvers: Some(vec![
-                                fdecl::ResolverRegistration {
-                                    resolver: Some("gopher".to_string()),
-                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                                    scheme: Some("gopher".to_string()),
-                                    ..fdecl::ResolverRegistration::EMPTY
-                                }
-                            ]),
-                            ..fdecl::Environment::EMPTY
-                        },
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            environment: Some("env".to_string()),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("data".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("logger".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
             },
+            // A child can't re-offer storage directly; it would have to expose it to the
+            // parent first.
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{child child1 -> environment env -> child child2 -> child child1}}".to_string()),
+                Error::invalid_field("OfferStorage", "source"),
             ])),
         },
-        test_validate_strong_cycle_between_self_and_two_children => {
+        // Aggregation from a collection is a service-only feature; every other offer kind
+        // must reject a collection source.
+        test_validate_offer_protocol_from_collection => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Protocol(fdecl::Protocol {
-                            name: Some("fuchsia.foo.Bar".to_string()),
-                            source_path: Some("/svc/fuchsia.foo.Bar".to_string()),
-                            ..fdecl::Protocol::EMPTY
-                        })
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("fuchsia.foo.Bar".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            target_name: Some("fuchsia.foo.Bar".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::OfferProtocol::EMPTY
-                        }),
-                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
-                            source_name: Some("fuchsia.bar.Baz".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
-                            target_name: Some("fuchsia.bar.Baz".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::OfferProtocol::EMPTY
-                        }),
-                    ]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child2".to_string(), collection: None})),
-                            source_name: Some("fuchsia.baz.Foo".to_string()),
-                            target_path: Some("/svc/fuchsia.baz.Foo".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child1".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        },
-                        fdecl::Child {
-                            name: Some("child2".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "coll".to_string(),
+                        })),
+                        source_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("thing".to_string()),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                ]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("coll".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("netstack".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{self -> child child1 -> child child2 -> self}}".to_string()),
+                Error::invalid_field("OfferProtocol", "source"),
             ])),
         },
-        test_validate_strong_cycle_with_self_storage => {
+        test_validate_offer_directory_from_collection => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("data".to_string()),
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        }),
-                        fdecl::Capability::Directory(fdecl::Directory {
-                            name: Some("minfs".to_string()),
-                            source_path: Some("/minfs".to_string()),
-                            rights: Some(fio::RW_STAR_DIR),
-                            ..fdecl::Directory::EMPTY
-                        }),
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Storage(fdecl::OfferStorage {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("data".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                            target_name: Some("data".to_string()),
-                            ..fdecl::OfferStorage::EMPTY
-                        }),
-                    ]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("fuchsia.foo.Bar".to_string()),
-                            target_path: Some("/svc/fuchsia.foo.Bar".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            ..fdecl::Child::EMPTY
-                        },
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "coll".to_string(),
+                        })),
+                        source_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("thing".to_string()),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("coll".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("netstack".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{self -> child child -> self}}".to_string()),
+                Error::invalid_field("OfferDirectory", "source"),
             ])),
         },
-        test_validate_strong_cycle_with_self_storage_admin_protocol => {
+        test_validate_offer_runner_from_collection => {
             input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Storage(fdecl::Storage {
-                            name: Some("data".to_string()),
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            backing_dir: Some("minfs".to_string()),
-                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                            ..fdecl::Storage::EMPTY
-                        }),
-                        fdecl::Capability::Directory(fdecl::Directory {
-                            name: Some("minfs".to_string()),
-                            source_path: Some("/minfs".to_string()),
-                            rights: Some(fio::RW_STAR_DIR),
-                            ..fdecl::Directory::EMPTY
-                        }),
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef { name: "data".to_string() })),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::OfferProtocol::EMPTY
-                        }),
-                    ]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("fuchsia.foo.Bar".to_string()),
-                            target_path: Some("/svc/fuchsia.foo.Bar".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            ..fdecl::Child::EMPTY
-                        },
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "coll".to_string(),
+                        })),
+                        source_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("thing".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                ]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("coll".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("netstack".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{self -> child child -> self}}".to_string()),
+                Error::invalid_field("OfferRunner", "source"),
             ])),
         },
-        test_validate_use_from_child_offer_to_child_weak_cycle => {
-            input = {
-                fdecl::Component {
-                    capabilities: Some(vec![
-                        fdecl::Capability::Service(fdecl::Service {
-                            name: Some("a".to_string()),
-                            source_path: Some("/a".to_string()),
-                            ..fdecl::Service::EMPTY
-                        })]),
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Weak),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                        fdecl::Use::Service(fdecl::UseService {
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("service_name".to_string()),
-                            target_path: Some("/svc/service_name".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Weak),
-                            ..fdecl::UseService::EMPTY
-                        }),
-                        fdecl::Use::Directory(fdecl::UseDirectory {
-                            dependency_type: Some(fdecl::DependencyType::WeakForMigration),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("DirectoryName".to_string()),
-                            target_path: Some("/data/DirectoryName".to_string()),
-                            rights: Some(fio::Operations::CONNECT),
-                            subdir: None,
-                            ..fdecl::UseDirectory::EMPTY
-                        }),
-                        fdecl::Use::Event(fdecl::UseEvent {
-                            dependency_type: Some(fdecl::DependencyType::WeakForMigration),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
-                            source_name: Some("abc".to_string()),
-                            target_name: Some("abc".to_string()),
-                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                            ..fdecl::UseEvent::EMPTY
-                        })
-                    ]),
-                    offers: Some(vec![
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            source_name: Some("a".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                            target_name: Some("a".to_string()),
-                            ..fdecl::OfferService::EMPTY
-                        })
-                    ]),
-                    children: Some(vec![
-                        fdecl::Child {
-                            name: Some("child".to_string()),
-                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                            startup: Some(fdecl::StartupMode::Lazy),
-                            on_terminate: None,
-                            ..fdecl::Child::EMPTY
-                        }
-                    ]),
-                    ..new_component_decl()
-                }
-            },
-            result = Ok(()),
-        },
-        test_validate_use_from_not_child_weak => {
+        test_validate_offer_resolver_from_collection => {
             input = {
-                fdecl::Component {
-                    uses: Some(vec![
-                        fdecl::Use::Protocol(fdecl::UseProtocol {
-                            dependency_type: Some(fdecl::DependencyType::Weak),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
-                            ..fdecl::UseProtocol::EMPTY
-                        }),
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "coll".to_string(),
+                        })),
+                        source_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("thing".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                ]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("coll".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("netstack".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseProtocol", "dependency_type"),
+                Error::invalid_field("OfferResolver", "source"),
             ])),
         },
-        test_validate_has_events_in_event_stream => {
+        test_validate_offer_event_to_parent => {
             input = {
                 let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
-                        name: Some("bar".to_string()),
-                        subscriptions: None,
-                        ..fdecl::UseEventStreamDeprecated::EMPTY
-                    }),
-                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
-                        name: Some("barbar".to_string()),
-                        subscriptions: Some(vec![]),
-                        ..fdecl::UseEventStreamDeprecated::EMPTY
+                decl.offers = Some(vec![
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("started".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("UseEventStream", "subscriptions"),
-                Error::empty_field("UseEventStream", "subscriptions"),
+                Error::invalid_field("OfferEvent", "target"),
             ])),
         },
-        test_validate_event_stream_offer_valid_decls => {
+        test_validate_offer_event_from_self => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
                         source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
                         target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    },
                 ]);
                 decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("OfferEvent", "source"),
+            ])),
         },
-        test_validate_event_stream_offer_to_framework_invalid => {
+        test_validate_offer_event_to_framework => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
                         source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
                         target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "target".to_string() }),
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "target".to_string() }),
+                Error::invalid_field("OfferEvent", "target"),
             ])),
         },
-        test_validate_event_stream_offer_to_scope_zero_length_invalid => {
+        test_validate_use_protocol_from_self_missing => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("fuchsia.foo.Bar".to_string()),
+                        target_path: Some("/svc/fuchsia.foo.Bar".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "scope".to_string() }),
+                Error::invalid_capability("UseProtocol", "source", "fuchsia.foo.Bar"),
             ])),
         },
-        test_validate_event_stream_offer_to_scope_framework_invalid => {
+        test_validate_use_protocol_from_self_present => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![fdecl::Ref::Framework(fdecl::FrameworkRef{})]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fuchsia.foo.Bar".to_string()),
+                        source_path: Some("/svc/fuchsia.foo.Bar".to_string()),
+                        ..fdecl::Protocol::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
+                decl.uses = Some(vec![
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("fuchsia.foo.Bar".to_string()),
+                        target_path: Some("/svc/client/fuchsia.foo.Bar".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
+                    }),
                 ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "scope".to_string() }),
-            ])),
+            result = Ok(()),
         },
-        test_validate_event_stream_offer_to_scope_valid => {
+        test_validate_use_storage_admin_path_collision => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                        target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
                     }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("fuchsia.example.Other".to_string()),
+                        target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
-            result = Ok(()),
+            // Storage-admin-style protocol uses go through the same path map as every
+            // other protocol use, so landing two on one path is a duplicate.
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field(
+                    "UseProtocol",
+                    "path",
+                    "/svc/fuchsia.sys2.StorageAdmin",
+                ),
+            ])),
         },
-        test_validate_event_stream_offer_to_scope_with_capability_requested => {
+        test_validate_uses_invalid_identifiers_service => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("capability_requested".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("directory_ready".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Service(fdecl::UseService {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("foo/".to_string()),
+                        target_path: Some("/".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::UseService::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("UseService", "source_name"),
+                Error::invalid_field("UseService", "target_path"),
+            ])),
         },
-        test_validate_event_stream_offer_to_scope_with_invalid_capability_name => {
+        test_validate_uses_invalid_identifiers_protocol => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("some_invalid_capability".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("directory_ready".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("foo/".to_string()),
+                        target_path: Some("/".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "filter".to_string() }),
+                Error::invalid_field("UseProtocol", "source_name"),
+                Error::invalid_field("UseProtocol", "target_path"),
             ])),
         },
-        test_validate_event_stream_offer_with_no_source_name_invalid => {
+        test_validate_uses_invalid_identifiers_directory => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: None,
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("foo/".to_string()),
+                        target_path: Some("/".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: Some("/foo".to_string()),
+                        ..fdecl::UseDirectory::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::MissingField(DeclField { decl: "OfferEventStream".to_string(), field: "source_name".to_string() }),
+                Error::invalid_field("UseDirectory", "source_name"),
+                Error::invalid_field("UseDirectory", "target_path"),
+                Error::invalid_field("UseDirectory", "subdir"),
             ])),
         },
-        test_validate_event_stream_offer_duplicate_target => {
+        test_validate_uses_invalid_identifiers_storage => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Storage(fdecl::UseStorage {
+                        source_name: Some("/cache".to_string()),
+                        target_path: Some("/".to_string()),
+                        ..fdecl::UseStorage::EMPTY
                     }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                    fdecl::Use::Storage(fdecl::UseStorage {
+                        source_name: Some("temp".to_string()),
+                        target_path: Some("tmp".to_string()),
+                        ..fdecl::UseStorage::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::DuplicateField(DeclField { decl: "OfferEventStream".to_string(), field: "target_name".to_string() }, "started".to_string()),
+                Error::invalid_field("UseStorage", "source_name"),
+                Error::invalid_field("UseStorage", "target_path"),
+                Error::invalid_field("UseStorage", "target_path"),
             ])),
         },
-        test_validate_event_stream_offer_invalid_source => {
+        test_validate_uses_invalid_identifiers_event => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("/foo".to_string()),
+                        target_name: Some("/foo".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::UseEvent::EMPTY
                     }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
                         source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
                         target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        source: Some(fdecl::Ref::Debug(fdecl::DebugRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
-                    }),
-                ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "source".to_string() }),
-            ])),
-        },
-
-        test_validate_event_stream_offer_missing_source => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::UseEvent::EMPTY
                     }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("started".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bar".to_string()),
+                        subscriptions: Some(vec!["a".to_string(), "b".to_string()].into_iter().map(|name| fdecl::EventSubscription {
+                            event_name: Some(name),
+                            ..fdecl::EventSubscription::EMPTY
+                        }).collect()),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
-                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
-                        source_name: Some("diagnostics_ready".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
-                        target_name: Some("diagnostics_ready".to_string()),
-                        ..fdecl::OfferEventStream::EMPTY
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bleep".to_string()),
+                        subscriptions: Some(vec![fdecl::EventSubscription {
+                            event_name: Some("started".to_string()),
+                            ..fdecl::EventSubscription::EMPTY
+                        }]),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                },
-                fdecl::Child{
-                    name: Some("test2".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }
-                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::MissingField(DeclField { decl: "OfferEventStream".to_string(), field: "source".to_string() }),
+                Error::invalid_field("UseEvent", "source"),
+                Error::invalid_field("UseEvent", "source_name"),
+                Error::invalid_field("UseEvent", "target_name"),
+                Error::event_stream_event_not_found("UseEventStream", "events", "a".to_string()),
+                Error::event_stream_event_not_found("UseEventStream", "events", "b".to_string()),
             ])),
         },
-
-        test_validate_event_stream_expose_to_framework_from_framework_invalid => {
+        test_validate_uses_missing_source => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::ExposeEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                name: "this-storage-doesnt-exist".to_string(),
+                            })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        })
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string() }),
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string() }),
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "source".to_string() }),
+                Error::invalid_capability("UseProtocol", "source", "this-storage-doesnt-exist"),
             ])),
         },
-        test_validate_event_stream_expose_to_framework_from_other_invalid => {
+        test_validate_uses_storage_admin_from_non_storage_capability => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::ExposeEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Protocol(fdecl::Protocol {
+                            name: Some("data".to_string()),
+                            source_path: Some("/svc/data".to_string()),
+                            ..fdecl::Protocol::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                name: "data".to_string(),
+                            })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        })
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "source".to_string() }),
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string() }),
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string() }),
+                Error::invalid_storage("UseProtocol", "source", "data"),
             ])),
         },
-        test_validate_event_stream_scope_must_be_non_empty => {
+        test_validate_uses_storage_admin_from_storage_capability => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
-                        source_name: Some("stopped".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        scope: Some(vec![]),
-                        target_name: Some("stopped".to_string()),
-                        ..fdecl::ExposeEventStream::EMPTY
-                    }),
-                ]);
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("test".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                name: "data".to_string(),
+                            })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        })
+                    ]),
+                    ..new_component_decl()
+                }
             },
-            result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "scope".to_string() }),
-            ])),
+            result = Ok(()),
         },
-        test_validate_event_stream_must_have_target_path => {
+        test_validate_uses_debug_source_only_for_protocols => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source_name: Some("bar".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Debug(fdecl::DebugRef {})),
+                            source_name: Some("fuchsia.debugger.Launcher".to_string()),
+                            target_path: Some("/svc/fuchsia.debugger.Launcher".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                        fdecl::Use::Service(fdecl::UseService {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Debug(fdecl::DebugRef {})),
+                            source_name: Some("fuchsia.sys2.Service".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.Service".to_string()),
+                            ..fdecl::UseService::EMPTY
+                        }),
+                        fdecl::Use::Directory(fdecl::UseDirectory {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Debug(fdecl::DebugRef {})),
+                            source_name: Some("assets".to_string()),
+                            target_path: Some("/data/assets".to_string()),
+                            rights: Some(fio::Operations::CONNECT),
+                            ..fdecl::UseDirectory::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "target_path".to_string() })
+                Error::invalid_field("UseService", "source"),
+                Error::invalid_field("UseDirectory", "source"),
             ])),
         },
-        test_validate_event_stream_must_have_source_names => {
+        test_validate_uses_invalid_child => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target_path: Some("/svc/something".to_string()),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                        fdecl::Use::Service(fdecl::UseService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
+                            source_name: Some("service_name".to_string()),
+                            target_path: Some("/svc/service_name".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::UseService::EMPTY
+                        }),
+                        fdecl::Use::Directory(fdecl::UseDirectory {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
+                            source_name: Some("DirectoryName".to_string()),
+                            target_path: Some("/data/DirectoryName".to_string()),
+                            rights: Some(fio::Operations::CONNECT),
+                            subdir: None,
+                            ..fdecl::UseDirectory::EMPTY
+                        }),
+                        fdecl::Use::Event(fdecl::UseEvent {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "no-such-child".to_string(), collection: None})),
+                            source_name: Some("abc".to_string()),
+                            target_name: Some("abc".to_string()),
+                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                            ..fdecl::UseEvent::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "source_name".to_string() })
+                Error::invalid_child("UseEvent", "source", "no-such-child"),
+                Error::invalid_child("UseProtocol", "source", "no-such-child"),
+                Error::invalid_child("UseService", "source", "no-such-child"),
+                Error::invalid_child("UseDirectory", "source", "no-such-child"),
             ])),
         },
-        test_validate_event_stream_scope_must_be_child_or_collection => {
+        test_validate_use_from_child_offer_to_child_strong_cycle => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target_path: Some("/svc/something".to_string()),
-                        source_name: Some("some_source".to_string()),
-                        scope: Some(vec![fdecl::Ref::Framework(fdecl::FrameworkRef{})]),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Service(fdecl::Service {
+                            name: Some("a".to_string()),
+                            source_path: Some("/a".to_string()),
+                            ..fdecl::Service::EMPTY
+                        })]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                        fdecl::Use::Service(fdecl::UseService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("service_name".to_string()),
+                            target_path: Some("/svc/service_name".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::UseService::EMPTY
+                        }),
+                        fdecl::Use::Directory(fdecl::UseDirectory {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("DirectoryName".to_string()),
+                            target_path: Some("/data/DirectoryName".to_string()),
+                            rights: Some(fio::Operations::CONNECT),
+                            subdir: None,
+                            ..fdecl::UseDirectory::EMPTY
+                        }),
+                        fdecl::Use::Event(fdecl::UseEvent {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("abc".to_string()),
+                            target_name: Some("abc".to_string()),
+                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                            ..fdecl::UseEvent::EMPTY
+                        })
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        })
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string() })
+                Error::dependency_cycle("{{self --(a)--> child child --(abc)--> self}}".to_string()),
             ])),
         },
-        test_validate_event_stream_source_must_be_parent_framework_or_child => {
+        test_validate_use_from_child_storage_no_cycle => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source: Some(fdecl::Ref::Debug(fdecl::DebugRef{})),
-                        target_path: Some("/svc/something".to_string()),
-                        source_name: Some("some_source".to_string()),
-                        scope: Some(vec![]),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("cdata".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None } )),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("pdata".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child1".to_string(), collection: None})),
+                            source_name: Some("a".to_string()),
+                            target_path: Some("/svc/a".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("cdata".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("cdata".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("pdata".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("pdata".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
-            result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "source".to_string() })
-            ])),
+            result = Ok(()),
         },
-        test_validate_event_stream_source_framework_must_have_nonempty_scope => {
+        test_validate_use_from_child_storage_cycle => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target_path: Some("/svc/something".to_string()),
-                        source_name: Some("some_source".to_string()),
-                        scope: Some(vec![]),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                        fdecl::Capability::Directory(fdecl::Directory {
+                            name: Some("minfs".to_string()),
+                            source_path: Some("/minfs".to_string()),
+                            rights: Some(fio::RW_STAR_DIR),
+                            ..fdecl::Directory::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("a".to_string()),
+                            target_path: Some("/svc/a".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("data".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                            target_name: Some("data".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string() })
+                Error::dependency_cycle("{{self --(storage data via minfs)--> child child --(a)--> self}}".to_string()),
             ])),
         },
-        test_validate_event_stream_source_framework_must_specify_scope => {
+        test_validate_storage_strong_cycle_between_children => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::EventStream(fdecl::UseEventStream {
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                        target_path: Some("/svc/something".to_string()),
-                        source_name: Some("some_source".to_string()),
-                        ..fdecl::UseEventStream::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None } )),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        })
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("data".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            target_name: Some("data".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string() })
+                Error::dependency_cycle("{{child child1 --(storage data via minfs)--> child child2 --(a)--> child child1}}".to_string()),
             ])),
         },
-        test_validate_uses_no_runner => {
+        test_validate_storage_strong_cycle_through_collection => {
             input = {
-                let mut decl = new_component_decl();
-                decl.program = Some(fdecl::Program {
-                    runner: None,
-                    info: Some(fdata::Dictionary {
-                        entries: None,
-                        ..fdata::Dictionary::EMPTY
-                    }),
-                    ..fdecl::Program::EMPTY
-                });
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None } )),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        })
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("data".to_string()),
+                            target: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "coll".to_string() })),
+                            target_name: Some("data".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "coll".to_string() })),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                    ]),
+                    collections: Some(vec![
+                        fdecl::Collection {
+                            name: Some("coll".to_string()),
+                            durability: Some(fdecl::Durability::Transient),
+                            ..fdecl::Collection::EMPTY
+                        },
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Program", "runner"),
+                Error::dependency_cycle("{{child child1 --(storage data via minfs)--> collection coll --(a)--> child child1}}".to_string()),
             ])),
         },
-        test_validate_uses_long_identifiers => {
+        test_validate_storage_subdir_valid => {
             input = {
-                let mut decl = new_component_decl();
-                decl.program = Some(fdecl::Program {
-                    runner: Some("elf".to_string()),
-                    info: Some(fdata::Dictionary {
-                        entries: None,
-                        ..fdata::Dictionary::EMPTY
-                    }),
-                    ..fdecl::Program::EMPTY
-                });
-                decl.uses = Some(vec![
-                    fdecl::Use::Service(fdecl::UseService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_path: Some(format!("/s/{}", "b".repeat(1024))),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::UseService::EMPTY
-                    }),
-                    fdecl::Use::Protocol(fdecl::UseProtocol {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_path: Some(format!("/p/{}", "c".repeat(1024))),
-                        ..fdecl::UseProtocol::EMPTY
-                    }),
-                    fdecl::Use::Directory(fdecl::UseDirectory {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_path: Some(format!("/d/{}", "d".repeat(1024))),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::UseDirectory::EMPTY
-                    }),
-                    fdecl::Use::Storage(fdecl::UseStorage {
-                        source_name: Some("cache".to_string()),
-                        target_path: Some(format!("/{}", "e".repeat(1024))),
-                        ..fdecl::UseStorage::EMPTY
-                    }),
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_name: Some(format!("{}", "a".repeat(101))),
-                        filter: None,
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            subdir: Some("persistent/data".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
-            result = Err(ErrorList::new(vec![
-                Error::field_too_long_with_max("UseEvent", "source_name", 100),
-                Error::field_too_long_with_max("UseEvent", "target_name", 100),
-                Error::field_too_long_with_max("UseService", "source_name", 100),
-                Error::field_too_long_with_max("UseService", "target_path", 1024),
-                Error::field_too_long_with_max("UseProtocol", "source_name", 100),
-                Error::field_too_long_with_max("UseProtocol", "target_path", 1024),
-                Error::field_too_long_with_max("UseDirectory", "source_name", 100),
-                Error::field_too_long_with_max("UseDirectory", "target_path", 1024),
-                Error::field_too_long_with_max("UseStorage", "target_path", 1024),
-            ])),
+            result = Ok(()),
         },
-        test_validate_conflicting_paths => {
+        test_validate_storage_subdir_absolute => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Service(fdecl::UseService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("foo".to_string()),
-                        target_path: Some("/bar".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::UseService::EMPTY
-                    }),
-                    fdecl::Use::Protocol(fdecl::UseProtocol {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("space".to_string()),
-                        target_path: Some("/bar".to_string()),
-                        ..fdecl::UseProtocol::EMPTY
-                    }),
-                    fdecl::Use::Directory(fdecl::UseDirectory {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("crow".to_string()),
-                        target_path: Some("/bar".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::UseDirectory::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            subdir: Some("/persistent".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::duplicate_field("UseProtocol", "path", "/bar"),
-                Error::duplicate_field("UseDirectory", "path", "/bar"),
+                Error::invalid_field("Storage", "subdir"),
             ])),
         },
-        test_validate_events_can_come_before_or_after_event_stream => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        source_name: Some("started".to_string()),
-                        target_name: Some("started".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
-                        name: Some("bar".to_string()),
-                        subscriptions: Some(
-                            vec!["started".to_string(), "stopped".to_string()]
-                                .into_iter()
-                                .map(|name| fdecl::EventSubscription {
-                                    event_name: Some(name),
-                                    ..fdecl::EventSubscription::EMPTY
-                                })
-                                .collect()
-                            ),
-                        ..fdecl::UseEventStreamDeprecated::EMPTY
-                    }),
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        source_name: Some("stopped".to_string()),
-                        target_name: Some("stopped".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                ]);
-                decl
-            },
-            result = Ok(()),
-        },
-        test_validate_uses_invalid_self_source => {
+        test_validate_storage_backing_dir_not_declared => {
             input = {
-                let mut decl = new_component_decl();
-                decl.uses = Some(vec![
-                    fdecl::Use::Event(fdecl::UseEvent {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        source_name: Some("started".to_string()),
-                        target_name: Some("foo_started".to_string()),
-                        ..fdecl::UseEvent::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("UseEvent", "source"),
+                Error::invalid_capability("Storage", "backing_dir", "minfs"),
             ])),
         },
-        // exposes
-        test_validate_exposes_empty => {
+        test_validate_storage_backing_dir_executable => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: None,
-                        source_name: None,
-                        target_name: None,
-                        target: None,
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: None,
-                        source_name: None,
-                        target_name: None,
-                        target: None,
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: None,
-                        source_name: None,
-                        target_name: None,
-                        target: None,
-                        rights: None,
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Directory(fdecl::Directory {
+                            name: Some("minfs".to_string()),
+                            source_path: Some("/minfs".to_string()),
+                            rights: Some(fio::Operations::CONNECT | fio::Operations::EXECUTE),
+                            ..fdecl::Directory::EMPTY
+                        }),
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("ExposeService", "source"),
-                Error::missing_field("ExposeService", "target"),
-                Error::missing_field("ExposeService", "source_name"),
-                Error::missing_field("ExposeService", "target_name"),
-                Error::missing_field("ExposeProtocol", "source"),
-                Error::missing_field("ExposeProtocol", "target"),
-                Error::missing_field("ExposeProtocol", "source_name"),
-                Error::missing_field("ExposeProtocol", "target_name"),
-                Error::missing_field("ExposeDirectory", "source"),
-                Error::missing_field("ExposeDirectory", "target"),
-                Error::missing_field("ExposeDirectory", "source_name"),
-                Error::missing_field("ExposeDirectory", "target_name"),
-                Error::missing_field("ExposeRunner", "source"),
-                Error::missing_field("ExposeRunner", "target"),
-                Error::missing_field("ExposeRunner", "source_name"),
-                Error::missing_field("ExposeRunner", "target_name"),
-                Error::missing_field("ExposeResolver", "source"),
-                Error::missing_field("ExposeResolver", "target"),
-                Error::missing_field("ExposeResolver", "source_name"),
-                Error::missing_field("ExposeResolver", "target_name"),
+                Error::invalid_storage_backing_rights("Storage", "backing_dir"),
             ])),
         },
-        test_validate_exposes_extraneous => {
+        test_validate_strong_cycle_between_children_through_environment_debug => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("logger".to_string()),
-                        target_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("legacy_logger".to_string()),
-                        target_name: Some("legacy_logger".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("data".to_string()),
-                        target_name: Some("data".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    environments: Some(vec![
+                        fdecl::Environment {
+                            name: Some("env".to_string()),
+                            extends: Some(fdecl::EnvironmentExtends::Realm),
+                            debug_capabilities: Some(vec![
+                                fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                                    source_name: Some("fuchsia.foo.Bar".to_string()),
+                                    target_name: Some("fuchsia.foo.Bar".to_string()),
+                                    ..fdecl::DebugProtocolRegistration::EMPTY
+                                }),
+                            ]),
+                            ..fdecl::Environment::EMPTY
+                        },
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            environment: Some("env".to_string()),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("ExposeService", "source.child.collection"),
-                Error::extraneous_field("ExposeProtocol", "source.child.collection"),
-                Error::extraneous_field("ExposeDirectory", "source.child.collection"),
-                Error::extraneous_field("ExposeRunner", "source.child.collection"),
-                Error::extraneous_field("ExposeResolver", "source.child.collection"),
+                Error::dependency_cycle("{{child child1 -> environment env -> child child2 --(a)--> child child1}}".to_string()),
             ])),
         },
-        test_validate_exposes_invalid_identifiers => {
+        test_validate_strong_cycle_between_children_through_environment_runner => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target_name: Some("/".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target_name: Some("/".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target_name: Some("/".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: Some("/foo".to_string()),
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("/path".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf!".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("/path".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg!".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    environments: Some(vec![
+                        fdecl::Environment {
+                            name: Some("env".to_string()),
+                            extends: Some(fdecl::EnvironmentExtends::Realm),
+                            runners: Some(vec![
+                                fdecl::RunnerRegistration {
+                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                                    source_name: Some("coff".to_string()),
+                                    target_name: Some("coff".to_string()),
+                                    ..fdecl::RunnerRegistration::EMPTY
+                                }
+                            ]),
+                            ..fdecl::Environment::EMPTY
+                        },
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            environment: Some("env".to_string()),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("ExposeService", "source.child.name"),
-                Error::invalid_field("ExposeService", "source_name"),
-                Error::invalid_field("ExposeService", "target_name"),
-                Error::invalid_field("ExposeProtocol", "source.child.name"),
-                Error::invalid_field("ExposeProtocol", "source_name"),
-                Error::invalid_field("ExposeProtocol", "target_name"),
-                Error::invalid_field("ExposeDirectory", "source.child.name"),
-                Error::invalid_field("ExposeDirectory", "source_name"),
-                Error::invalid_field("ExposeDirectory", "target_name"),
-                Error::invalid_field("ExposeDirectory", "subdir"),
-                Error::invalid_field("ExposeRunner", "source.child.name"),
-                Error::invalid_field("ExposeRunner", "source_name"),
-                Error::invalid_field("ExposeRunner", "target_name"),
-                Error::invalid_field("ExposeResolver", "source.child.name"),
-                Error::invalid_field("ExposeResolver", "source_name"),
-                Error::invalid_field("ExposeResolver", "target_name"),
+                Error::dependency_cycle("{{child child1 -> environment env -> child child2 --(a)--> child child1}}".to_string()),
             ])),
         },
-        test_validate_exposes_invalid_source_target => {
+        test_validate_strong_cycle_between_children_through_environment_resolver => {
             input = {
-                let mut decl = new_component_decl();
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("logger".to_string()),
-                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }]);
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: None,
-                        source_name: Some("a".to_string()),
-                        target_name: Some("b".to_string()),
-                        target: None,
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("c".to_string()),
-                        target_name: Some("d".to_string()),
-                        target: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "z".to_string()})),
-                        source_name: Some("e".to_string()),
-                        target_name: Some("f".to_string()),
-                        target: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "z".to_string()})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("g".to_string()),
-                        target_name: Some("h".to_string()),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("i".to_string()),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        target_name: Some("j".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("k".to_string()),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        target_name: Some("l".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("m".to_string()),
-                        target_name: Some("n".to_string()),
-                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    environments: Some(vec![
+                        fdecl::Environment {
+                            name: Some("env".to_string()),
+                            extends: Some(fdecl::EnvironmentExtends::Realm),
+                            resolvers: Some(vec![
+                                fdecl::ResolverRegistration {
+                                    resolver: Some("gopher".to_string()),
+                                    source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                                    scheme: Some("gopher".to_string()),
+                                    ..fdecl::ResolverRegistration::EMPTY
+                                }
+                            ]),
+                            ..fdecl::Environment::EMPTY
+                        },
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            environment: Some("env".to_string()),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("ExposeService", "source"),
-                Error::missing_field("ExposeService", "target"),
-                Error::invalid_field("ExposeProtocol", "source"),
-                Error::invalid_field("ExposeProtocol", "target"),
-                Error::invalid_field("ExposeDirectory", "source"),
-                Error::invalid_field("ExposeDirectory", "target"),
-                Error::invalid_field("ExposeDirectory", "source"),
-                Error::invalid_field("ExposeDirectory", "target"),
-                Error::invalid_field("ExposeRunner", "source"),
-                Error::invalid_field("ExposeRunner", "target"),
-                Error::invalid_field("ExposeResolver", "source"),
-                Error::invalid_field("ExposeResolver", "target"),
-                Error::invalid_field("ExposeDirectory", "target"),
+                Error::dependency_cycle("{{child child1 -> environment env -> child child2 --(a)--> child child1}}".to_string()),
             ])),
         },
-        test_validate_exposes_invalid_source_collection => {
+        test_validate_strong_cycle_between_self_and_two_children => {
             input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![fdecl::Collection{
-                    name: Some("col".to_string()),
-                    durability: Some(fdecl::Durability::Transient),
-                    allowed_offers: None,
-                    allow_long_names: None,
-                    ..fdecl::Collection::EMPTY
-                }]);
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("a".to_string()),
-                        target_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
-                        source_name: Some("b".to_string()),
-                        target_name: Some("b".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
-                        source_name: Some("c".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("c".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
-                        source_name: Some("d".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("d".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Protocol(fdecl::Protocol {
+                            name: Some("fuchsia.foo.Bar".to_string()),
+                            source_path: Some("/svc/fuchsia.foo.Bar".to_string()),
+                            ..fdecl::Protocol::EMPTY
+                        })
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("fuchsia.foo.Bar".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            target_name: Some("fuchsia.foo.Bar".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::OfferProtocol::EMPTY
+                        }),
+                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None })),
+                            source_name: Some("fuchsia.bar.Baz".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None })),
+                            target_name: Some("fuchsia.bar.Baz".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::OfferProtocol::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child2".to_string(), collection: None})),
+                            source_name: Some("fuchsia.baz.Foo".to_string()),
+                            target_path: Some("/svc/fuchsia.baz.Foo".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child1".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        },
+                        fdecl::Child {
+                            name: Some("child2".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("ExposeProtocol", "source"),
-                Error::invalid_field("ExposeDirectory", "source"),
-                Error::invalid_field("ExposeRunner", "source"),
-                Error::invalid_field("ExposeResolver", "source"),
+                Error::dependency_cycle("{{self --(fuchsia.foo.Bar)--> child child1 --(fuchsia.bar.Baz)--> child child2 --(fuchsia.baz.Foo)--> self}}".to_string()),
             ])),
         },
-        test_validate_exposes_sources_collection => {
+        test_validate_strong_cycle_with_self_storage => {
             input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![
-                    fdecl::Collection {
-                        name: Some("col".to_string()),
-                        durability: Some(fdecl::Durability::Transient),
-                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                        allow_long_names: None,
-                        ..fdecl::Collection::EMPTY
-                    }
-                ]);
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("a".to_string()),
-                        ..fdecl::ExposeService::EMPTY
-                    })
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                        fdecl::Capability::Directory(fdecl::Directory {
+                            name: Some("minfs".to_string()),
+                            source_path: Some("/minfs".to_string()),
+                            rights: Some(fio::RW_STAR_DIR),
+                            ..fdecl::Directory::EMPTY
+                        }),
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Storage(fdecl::OfferStorage {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("data".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                            target_name: Some("data".to_string()),
+                            ..fdecl::OfferStorage::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("fuchsia.foo.Bar".to_string()),
+                            target_path: Some("/svc/fuchsia.foo.Bar".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            ..fdecl::Child::EMPTY
+                        },
+                    ]),
+                    ..new_component_decl()
+                }
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::dependency_cycle("{{self --(storage data via minfs)--> child child --(fuchsia.foo.Bar)--> self}}".to_string()),
+            ])),
         },
-        test_validate_exposes_long_identifiers => {
+        test_validate_strong_cycle_with_self_storage_admin_protocol => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(1025))),
-                        target_name: Some(format!("{}", "b".repeat(1025))),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some("a".repeat(101)),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("b".repeat(101)),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some("a".repeat(101)),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("b".repeat(101)),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Storage(fdecl::Storage {
+                            name: Some("data".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            backing_dir: Some("minfs".to_string()),
+                            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                            ..fdecl::Storage::EMPTY
+                        }),
+                        fdecl::Capability::Directory(fdecl::Directory {
+                            name: Some("minfs".to_string()),
+                            source_path: Some("/minfs".to_string()),
+                            rights: Some(fio::RW_STAR_DIR),
+                            ..fdecl::Directory::EMPTY
+                        }),
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef { name: "data".to_string() })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::OfferProtocol::EMPTY
+                        }),
+                    ]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("fuchsia.foo.Bar".to_string()),
+                            target_path: Some("/svc/fuchsia.foo.Bar".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            ..fdecl::Child::EMPTY
+                        },
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::field_too_long("ExposeService", "source.child.name"),
-                Error::field_too_long("ExposeService", "source_name"),
-                Error::field_too_long("ExposeService", "target_name"),
-                Error::field_too_long("ExposeProtocol", "source.child.name"),
-                Error::field_too_long("ExposeProtocol", "source_name"),
-                Error::field_too_long("ExposeProtocol", "target_name"),
-                Error::field_too_long("ExposeDirectory", "source.child.name"),
-                Error::field_too_long("ExposeDirectory", "source_name"),
-                Error::field_too_long("ExposeDirectory", "target_name"),
-                Error::field_too_long("ExposeRunner", "source.child.name"),
-                Error::field_too_long("ExposeRunner", "source_name"),
-                Error::field_too_long("ExposeRunner", "target_name"),
-                Error::field_too_long("ExposeResolver", "source.child.name"),
-                Error::field_too_long("ExposeResolver", "source_name"),
-                Error::field_too_long("ExposeResolver", "target_name"),
+                Error::dependency_cycle("{{self --(storage data via minfs)--> child child --(fuchsia.foo.Bar)--> self}}".to_string()),
             ])),
         },
-        test_validate_exposes_invalid_child => {
+        test_validate_use_from_child_offer_to_child_weak_cycle => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("data".to_string()),
-                        target_name: Some("data".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
-                    }),
-                ]);
-                decl
+                fdecl::Component {
+                    capabilities: Some(vec![
+                        fdecl::Capability::Service(fdecl::Service {
+                            name: Some("a".to_string()),
+                            source_path: Some("/a".to_string()),
+                            ..fdecl::Service::EMPTY
+                        })]),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Weak),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
+                        }),
+                        fdecl::Use::Service(fdecl::UseService {
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("service_name".to_string()),
+                            target_path: Some("/svc/service_name".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Weak),
+                            ..fdecl::UseService::EMPTY
+                        }),
+                        fdecl::Use::Directory(fdecl::UseDirectory {
+                            dependency_type: Some(fdecl::DependencyType::WeakForMigration),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("DirectoryName".to_string()),
+                            target_path: Some("/data/DirectoryName".to_string()),
+                            rights: Some(fio::Operations::CONNECT),
+                            subdir: None,
+                            ..fdecl::UseDirectory::EMPTY
+                        }),
+                        fdecl::Use::Event(fdecl::UseEvent {
+                            dependency_type: Some(fdecl::DependencyType::WeakForMigration),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{ name: "child".to_string(), collection: None})),
+                            source_name: Some("abc".to_string()),
+                            target_name: Some("abc".to_string()),
+                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                            ..fdecl::UseEvent::EMPTY
+                        })
+                    ]),
+                    offers: Some(vec![
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            source_name: Some("a".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                            target_name: Some("a".to_string()),
+                            ..fdecl::OfferService::EMPTY
+                        })
+                    ]),
+                    children: Some(vec![
+                        fdecl::Child {
+                            name: Some("child".to_string()),
+                            url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                            startup: Some(fdecl::StartupMode::Lazy),
+                            on_terminate: None,
+                            ..fdecl::Child::EMPTY
+                        }
+                    ]),
+                    ..new_component_decl()
+                }
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_child("ExposeService", "source", "netstack"),
-                Error::invalid_child("ExposeProtocol", "source", "netstack"),
-                Error::invalid_child("ExposeDirectory", "source", "netstack"),
-                Error::invalid_child("ExposeRunner", "source", "netstack"),
-                Error::invalid_child("ExposeResolver", "source", "netstack"),
-            ])),
+            result = Ok(()),
         },
-        test_validate_exposes_invalid_source_capability => {
+        test_validate_use_from_not_child_weak => {
             input = {
                 fdecl::Component {
-                    exposes: Some(vec![
-                        fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
-                                name: "this-storage-doesnt-exist".to_string(),
-                            })),
+                    uses: Some(vec![
+                        fdecl::Use::Protocol(fdecl::UseProtocol {
+                            dependency_type: Some(fdecl::DependencyType::Weak),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
                             source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                            ..fdecl::ExposeProtocol::EMPTY
+                            target_path: Some("/svc/fuchsia.sys2.StorageAdmin".to_string()),
+                            ..fdecl::UseProtocol::EMPTY
                         }),
                     ]),
                     ..new_component_decl()
                 }
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_capability("ExposeProtocol", "source", "this-storage-doesnt-exist"),
+                Error::invalid_field("UseProtocol", "dependency_type"),
             ])),
         },
-        test_validate_exposes_duplicate_target => {
+        test_validate_event_stream_duplicate_subscription => {
             input = {
                 let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("netstack".to_string()),
-                        target_name: Some("fuchsia.net.Stack".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("netstack2".to_string()),
-                        target_name: Some("fuchsia.net.Stack".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("fonts".to_string()),
-                        target_name: Some("fuchsia.fonts.Provider".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("fonts2".to_string()),
-                        target_name: Some("fuchsia.fonts.Provider".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("assets".to_string()),
-                        target_name: Some("stuff".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: None,
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("assets2".to_string()),
-                        target_name: Some("stuff".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        rights: None,
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_elf".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_elf".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
-                    }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_pkg".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("started".to_string()),
+                        target_name: Some("started".to_string()),
+                        filter: None,
+                        ..fdecl::UseEvent::EMPTY
                     }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_pkg".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bar".to_string()),
+                        subscriptions: Some(vec!["started", "started"].into_iter().map(|name| {
+                            fdecl::EventSubscription {
+                                event_name: Some(name.to_string()),
+                                ..fdecl::EventSubscription::EMPTY
+                            }
+                        }).collect()),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
                 ]);
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("netstack".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Service::EMPTY
-                    }),
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("netstack2".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Service::EMPTY
-                    }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("fonts".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("fonts2".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("assets".to_string()),
-                        source_path: Some("/path".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
-                    }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("assets2".to_string()),
-                        source_path: Some("/path".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
-                    }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: Some("source_elf".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Runner::EMPTY
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("UseEventStream", "event_name", "started"),
+            ])),
+        },
+        test_validate_event_stream_name_too_long => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("started".to_string()),
+                        target_name: Some("started".to_string()),
+                        filter: None,
+                        ..fdecl::UseEvent::EMPTY
                     }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: Some("source_pkg".to_string()),
-                        source_path: Some("/path".to_string()),
-                        ..fdecl::Resolver::EMPTY
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("a".repeat(101)),
+                        subscriptions: Some(vec![fdecl::EventSubscription {
+                            event_name: Some("started".to_string()),
+                            ..fdecl::EventSubscription::EMPTY
+                        }]),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                // Duplicate services are allowed.
-                Error::duplicate_field("ExposeProtocol", "target_name",
-                                    "fuchsia.fonts.Provider"),
-                Error::duplicate_field("ExposeDirectory", "target_name",
-                                    "stuff"),
-                Error::duplicate_field("ExposeRunner", "target_name",
-                                    "elf"),
-                Error::duplicate_field("ExposeResolver", "target_name", "pkg"),
+                Error::field_too_long_with_max("UseEventStream", "name", 100),
             ])),
         },
-        // TODO: Add analogous test for offer
-        test_validate_exposes_invalid_capability_from_self => {
+        test_validate_event_subscription_name_too_long => {
             input = {
                 let mut decl = new_component_decl();
-                decl.exposes = Some(vec![
-                    fdecl::Expose::Service(fdecl::ExposeService {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("fuchsia.netstack.Netstack".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("foo".to_string()),
-                        ..fdecl::ExposeService::EMPTY
-                    }),
-                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("fuchsia.netstack.Netstack".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("bar".to_string()),
-                        ..fdecl::ExposeProtocol::EMPTY
-                    }),
-                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("dir".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("assets".to_string()),
-                        rights: None,
-                        subdir: None,
-                        ..fdecl::ExposeDirectory::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bar".to_string()),
+                        subscriptions: Some(vec![fdecl::EventSubscription {
+                            event_name: Some("a".repeat(101)),
+                            ..fdecl::EventSubscription::EMPTY
+                        }]),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
-                    fdecl::Expose::Runner(fdecl::ExposeRunner {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_elf".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::ExposeRunner::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long_with_max("UseEventStream", "event_name", 100),
+                Error::event_stream_event_not_found("UseEventStream", "events", "a".repeat(101)),
+            ])),
+        },
+        test_validate_has_events_in_event_stream => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bar".to_string()),
+                        subscriptions: None,
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
-                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        source_name: Some("source_pkg".to_string()),
-                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::ExposeResolver::EMPTY
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("barbar".to_string()),
+                        subscriptions: Some(vec![]),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_capability("ExposeService", "source", "fuchsia.netstack.Netstack"),
-                Error::invalid_capability("ExposeProtocol", "source", "fuchsia.netstack.Netstack"),
-                Error::invalid_capability("ExposeDirectory", "source", "dir"),
-                Error::invalid_capability("ExposeRunner", "source", "source_elf"),
-                Error::invalid_capability("ExposeResolver", "source", "source_pkg"),
+                Error::missing_field("UseEventStream", "subscriptions"),
+                Error::empty_field("UseEventStream", "subscriptions"),
             ])),
         },
-
-        // offers
-        test_validate_offers_empty => {
+        test_validate_event_stream_offer_valid_decls => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        dependency_type: None,
-                        ..fdecl::OfferProtocol::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        rights: None,
-                        subdir: None,
-                        dependency_type: None,
-                        ..fdecl::OfferDirectory::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: None,
-                        source: None,
-                        target: None,
-                        target_name: None,
-                        ..fdecl::OfferStorage::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        ..fdecl::OfferRunner::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_event_stream_offer_to_framework_invalid => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: None,
-                        source_name: None,
-                        target: None,
-                        target_name: None,
-                        filter: None,
-                        ..fdecl::OfferEvent::EMPTY
-                    })
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
+                    }),
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
                 ]);
                 decl
             },
-            // TODO(dgonyeo): we need to handle the availability being unset until we've soft
-            // migrated all manifests
             result = Err(ErrorList::new(vec![
-                Error::missing_field("OfferService", "source"),
-                Error::missing_field("OfferService", "source_name"),
-                Error::missing_field("OfferService", "target"),
-                Error::missing_field("OfferService", "target_name"),
-                //Error::missing_field("OfferService", "availability"),
-                Error::missing_field("OfferProtocol", "source"),
-                Error::missing_field("OfferProtocol", "source_name"),
-                Error::missing_field("OfferProtocol", "target"),
-                Error::missing_field("OfferProtocol", "target_name"),
-                Error::missing_field("OfferProtocol", "dependency_type"),
-                //Error::missing_field("OfferProtocol", "availability"),
-                Error::missing_field("OfferDirectory", "source"),
-                Error::missing_field("OfferDirectory", "source_name"),
-                Error::missing_field("OfferDirectory", "target"),
-                Error::missing_field("OfferDirectory", "target_name"),
-                Error::missing_field("OfferDirectory", "dependency_type"),
-                //Error::missing_field("OfferDirectory", "availability"),
-                Error::missing_field("OfferStorage", "source_name"),
-                Error::missing_field("OfferStorage", "source"),
-                Error::missing_field("OfferStorage", "target"),
-                //Error::missing_field("OfferStorage", "availability"),
-                Error::missing_field("OfferRunner", "source"),
-                Error::missing_field("OfferRunner", "source_name"),
-                Error::missing_field("OfferRunner", "target"),
-                Error::missing_field("OfferRunner", "target_name"),
-                //Error::missing_field("OfferRunner", "availability"),
-                Error::missing_field("OfferEvent", "source_name"),
-                Error::missing_field("OfferEvent", "source"),
-                Error::missing_field("OfferEvent", "target"),
-                Error::missing_field("OfferEvent", "target_name"),
-                //Error::missing_field("OfferEvent", "availability"),
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "target".to_string(), index: None }),
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "target".to_string(), index: None }),
             ])),
         },
-        test_validate_offers_long_identifiers => {
+        test_validate_event_stream_offer_to_scope_zero_length_invalid => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef {
-                            name: "b".repeat(101),
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        ..fdecl::OfferService::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef {
-                            name: "b".repeat(101),
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferProtocol::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "scope".to_string(), index: None }),
+            ])),
+        },
+        test_validate_event_stream_offer_to_scope_framework_invalid => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![fdecl::Ref::Framework(fdecl::FrameworkRef{})]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef {
-                            name: "b".repeat(101),
-                        }
-                        )),
-                        target_name: Some(format!("{}", "b".repeat(101))),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferDirectory::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "scope".to_string(), index: None }),
+            ])),
+        },
+        test_validate_event_stream_offer_to_scope_valid => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "b".repeat(101),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target: Some(fdecl::Ref::Collection(
-                            fdecl::CollectionRef { name: "b".repeat(101) }
-                        )),
-                        target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_event_stream_offer_to_scope_with_capability_requested => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("capability_requested".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some("b".repeat(101)),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef {
-                            name: "c".repeat(101),
-                        }
-                        )),
-                        target_name: Some("d".repeat(101)),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("directory_ready".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None,
-                        })),
-                        source_name: Some("b".repeat(101)),
-                        target: Some(fdecl::Ref::Collection(
-                            fdecl::CollectionRef {
-                                name: "c".repeat(101),
-                            }
-                        )),
-                        target_name: Some("d".repeat(101)),
-                        ..fdecl::OfferResolver::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_event_stream_offer_to_scope_with_invalid_capability_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("some_invalid_capability".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some(format!("{}", "a".repeat(101))),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "a".repeat(101),
-                            collection: None
-                        })),
-                        target_name: Some(format!("{}", "a".repeat(101))),
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("directory_ready".to_string()),
                         filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::OfferEvent::EMPTY
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
                 ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::field_too_long("OfferService", "source.child.name"),
-                Error::field_too_long("OfferService", "source_name"),
-                Error::field_too_long("OfferService", "target.child.name"),
-                Error::field_too_long("OfferService", "target_name"),
-                Error::field_too_long("OfferService", "target.collection.name"),
-                Error::field_too_long("OfferService", "target_name"),
-                Error::field_too_long("OfferProtocol", "source.child.name"),
-                Error::field_too_long("OfferProtocol", "source_name"),
-                Error::field_too_long("OfferProtocol", "target.child.name"),
-                Error::field_too_long("OfferProtocol", "target_name"),
-                Error::field_too_long("OfferProtocol", "target.collection.name"),
-                Error::field_too_long("OfferProtocol", "target_name"),
-                Error::field_too_long("OfferDirectory", "source.child.name"),
-                Error::field_too_long("OfferDirectory", "source_name"),
-                Error::field_too_long("OfferDirectory", "target.child.name"),
-                Error::field_too_long("OfferDirectory", "target_name"),
-                Error::field_too_long("OfferDirectory", "target.collection.name"),
-                Error::field_too_long("OfferDirectory", "target_name"),
-                Error::field_too_long("OfferStorage", "target.child.name"),
-                Error::field_too_long("OfferStorage", "target.collection.name"),
-                Error::field_too_long("OfferRunner", "source.child.name"),
-                Error::field_too_long("OfferRunner", "source_name"),
-                Error::field_too_long("OfferRunner", "target.collection.name"),
-                Error::field_too_long("OfferRunner", "target_name"),
-                Error::field_too_long("OfferResolver", "source.child.name"),
-                Error::field_too_long("OfferResolver", "source_name"),
-                Error::field_too_long("OfferResolver", "target.collection.name"),
-                Error::field_too_long("OfferResolver", "target_name"),
-                Error::field_too_long("OfferEvent", "source_name"),
-                Error::field_too_long("OfferEvent", "target.child.name"),
-                Error::field_too_long("OfferEvent", "target_name"),
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "filter".to_string(), index: None }),
             ])),
         },
-        test_validate_offers_extraneous => {
+        test_validate_event_stream_offer_with_no_source_name_invalid => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        ..fdecl::OfferService::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: None,
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        scope: Some(vec![fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})]),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("assets".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferDirectory::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{ })),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::MissingField(DeclField { decl: "OfferEventStream".to_string(), field: "source_name".to_string(), index: None }),
+            ])),
+        },
+        test_validate_event_stream_offer_duplicate_target => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: Some("modular".to_string()),
-                        })),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: Some("modular".to_string()),
-                            }
-                        )),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
                 ]);
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("fuchsia.logger.Log".to_string()),
-                        source_path: Some("/svc/logger".to_string()),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("assets".to_string()),
-                        source_path: Some("/data/assets".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
-                    }),
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("OfferService", "source.child.collection"),
-                Error::extraneous_field("OfferService", "target.child.collection"),
-                Error::extraneous_field("OfferProtocol", "source.child.collection"),
-                Error::extraneous_field("OfferProtocol", "target.child.collection"),
-                Error::extraneous_field("OfferDirectory", "source.child.collection"),
-                Error::extraneous_field("OfferDirectory", "target.child.collection"),
-                Error::extraneous_field("OfferStorage", "target.child.collection"),
-                Error::extraneous_field("OfferRunner", "source.child.collection"),
-                Error::extraneous_field("OfferRunner", "target.child.collection"),
-                Error::extraneous_field("OfferResolver", "source.child.collection"),
-                Error::extraneous_field("OfferResolver", "target.child.collection"),
+                Error::DuplicateField(DeclField { decl: "OfferEventStream".to_string(), field: "target_name".to_string(), index: None }, "started".to_string()),
             ])),
         },
-        test_validate_offers_invalid_filtered_service_fields => {
+        test_validate_event_stream_offer_invalid_source => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "logger".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec![]),
-                        ..fdecl::OfferService::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Service(fdecl::OfferService {
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
                         source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "logger".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log1".to_string()),
-                        renamed_instances: Some(vec![fdecl::NameMapping{source_name: "a".to_string(), target_name: "b".to_string()}, fdecl::NameMapping{source_name: "c".to_string(), target_name: "b".to_string()}]),
-                        ..fdecl::OfferService::EMPTY
-                    })
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
+                    }),
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        source: Some(fdecl::Ref::Debug(fdecl::DebugRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
+                    }),
                 ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("logger".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("OfferService", "source_instance_filter"),
-                Error::invalid_field("OfferService", "renamed_instances"),
+                Error::InvalidField(DeclField { decl: "OfferEventStream".to_string(), field: "source".to_string(), index: None }),
             ])),
         },
-        test_validate_offers_invalid_identifiers => {
+
+        test_validate_event_stream_offer_missing_source => {
             input = {
                 let mut decl = new_component_decl();
                 decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("/".to_string()),
-                        ..fdecl::OfferService::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("/".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("foo/".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("/".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: Some("/foo".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
-                    }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("/path".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("elf!".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("started".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "^bad".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("/path".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("pkg!".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                    fdecl::Offer::EventStream(fdecl::OfferEventStream {
+                        source_name: Some("diagnostics_ready".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test2".to_string(), collection: None})),
+                        target_name: Some("diagnostics_ready".to_string()),
+                        ..fdecl::OfferEventStream::EMPTY
                     }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("/path".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "%bad".to_string(),
-                            collection: None,
-                        })),
-                        target_name: Some("/path".to_string()),
-                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                        ..fdecl::OfferEvent::EMPTY
-                    })
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                },
+                fdecl::Child{
+                    name: Some("test2".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/fake_component#meta/fake_component.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("OfferService", "source.child.name"),
-                Error::invalid_field("OfferService", "source_name"),
-                Error::invalid_field("OfferService", "target.child.name"),
-                Error::invalid_field("OfferService", "target_name"),
-                Error::invalid_field("OfferProtocol", "source.child.name"),
-                Error::invalid_field("OfferProtocol", "source_name"),
-                Error::invalid_field("OfferProtocol", "target.child.name"),
-                Error::invalid_field("OfferProtocol", "target_name"),
-                Error::invalid_field("OfferDirectory", "source.child.name"),
-                Error::invalid_field("OfferDirectory", "source_name"),
-                Error::invalid_field("OfferDirectory", "target.child.name"),
-                Error::invalid_field("OfferDirectory", "target_name"),
-                Error::invalid_field("OfferDirectory", "subdir"),
-                Error::invalid_field("OfferRunner", "source.child.name"),
-                Error::invalid_field("OfferRunner", "source_name"),
-                Error::invalid_field("OfferRunner", "target.child.name"),
-                Error::invalid_field("OfferRunner", "target_name"),
-                Error::invalid_field("OfferResolver", "source.child.name"),
-                Error::invalid_field("OfferResolver", "source_name"),
-                Error::invalid_field("OfferResolver", "target.child.name"),
-                Error::invalid_field("OfferResolver", "target_name"),
-                Error::invalid_field("OfferEvent", "source_name"),
-                Error::invalid_field("OfferEvent", "target.child.name"),
-                Error::invalid_field("OfferEvent", "target_name"),
+                Error::MissingField(DeclField { decl: "OfferEventStream".to_string(), field: "source".to_string(), index: None }),
             ])),
         },
-        test_validate_offers_target_equals_source => {
+
+        test_validate_event_stream_expose_to_framework_from_framework_invalid => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("logger".to_string()),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("legacy_logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("weak_legacy_logger".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("legacy_logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("strong_legacy_logger".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("assets".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
+                decl.exposes = Some(vec![
+                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::ExposeEventStream::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("web".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("web".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string(), index: None }),
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string(), index: None }),
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "source".to_string(), index: None }),
+            ])),
+        },
+        test_validate_event_stream_expose_to_framework_from_other_invalid => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![
+                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::ExposeEventStream::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "source".to_string(), index: None }),
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string(), index: None }),
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "target".to_string(), index: None }),
+            ])),
+        },
+        test_validate_event_stream_scope_must_be_non_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![
+                    fdecl::Expose::EventStream(fdecl::ExposeEventStream {
+                        source_name: Some("stopped".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "test".to_string(), collection: None})),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        scope: Some(vec![]),
+                        target_name: Some("stopped".to_string()),
+                        ..fdecl::ExposeEventStream::EMPTY
                     }),
                 ]);
                 decl.children = Some(vec![fdecl::Child{
-                    name: Some("logger".to_string()),
+                    name: Some("test".to_string()),
                     url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
                     startup: Some(fdecl::StartupMode::Lazy),
                     on_terminate: None,
@@ -6112,304 +7271,245 @@ mod tests {
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::offer_target_equals_source("OfferService", "logger"),
-                // Only the DependencyType::Strong offer-target-equals-source
-                // should result in an error.
-                Error::offer_target_equals_source("OfferProtocol", "logger"),
-                Error::offer_target_equals_source("OfferDirectory", "logger"),
-                Error::offer_target_equals_source("OfferRunner", "logger"),
-                Error::offer_target_equals_source("OfferResolver", "logger"),
+                Error::InvalidField(DeclField { decl: "ExposeEventStream".to_string(), field: "scope".to_string(), index: None }),
             ])),
         },
-        test_validate_offers_storage_target_equals_source => {
-            input = fdecl::Component {
-                offers: Some(vec![
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef { })),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "logger".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
-                    })
-                ]),
-                capabilities: Some(vec![
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("data".to_string()),
-                        backing_dir: Some("minfs".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+        test_validate_event_stream_must_have_target_path => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source_name: Some("bar".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        ..fdecl::UseEventStream::EMPTY
                     }),
-                ]),
-                children: Some(vec![
-                    fdecl::Child {
-                        name: Some("logger".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                ]),
-                ..new_component_decl()
+                ]);
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle("{{child logger -> child logger}}".to_string()),
+                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "target_path".to_string(), index: None })
             ])),
         },
-        test_validate_offers_invalid_child => {
+        test_validate_event_stream_must_have_source_names => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target_path: Some("/svc/something".to_string()),
+                        ..fdecl::UseEventStream::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("assets".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferDirectory::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "source_name".to_string(), index: None })
+            ])),
+        },
+        test_validate_event_stream_scope_must_be_child_or_collection => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target_path: Some("/svc/something".to_string()),
+                        source_name: Some("some_source".to_string()),
+                        scope: Some(vec![fdecl::Ref::Framework(fdecl::FrameworkRef{})]),
+                        ..fdecl::UseEventStream::EMPTY
                     }),
                 ]);
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("memfs".to_string()),
-                        backing_dir: Some("memfs".to_string()),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "logger".to_string(),
-                            collection: None,
-                        })),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string(), index: None })
+            ])),
+        },
+        test_validate_event_stream_source_must_be_parent_framework_or_child => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source: Some(fdecl::Ref::Debug(fdecl::DebugRef{})),
+                        target_path: Some("/svc/something".to_string()),
+                        source_name: Some("some_source".to_string()),
+                        scope: Some(vec![]),
+                        ..fdecl::UseEventStream::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("netstack".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "source".to_string(), index: None })
+            ])),
+        },
+        test_validate_event_stream_source_framework_must_have_nonempty_scope => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target_path: Some("/svc/something".to_string()),
+                        source_name: Some("some_source".to_string()),
+                        scope: Some(vec![]),
+                        ..fdecl::UseEventStream::EMPTY
+                    }),
                 ]);
-                decl.collections = Some(vec![
-                    fdecl::Collection {
-                        name: Some("modular".to_string()),
-                        durability: Some(fdecl::Durability::Transient),
-                        environment: None,
-                        allowed_offers: Some(fdecl::AllowedOffers::StaticAndDynamic),
-                        allow_long_names: None,
-                        ..fdecl::Collection::EMPTY
-                    },
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::InvalidField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string(), index: None })
+            ])),
+        },
+        test_validate_event_stream_source_framework_must_specify_scope => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::EventStream(fdecl::UseEventStream {
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                        target_path: Some("/svc/something".to_string()),
+                        source_name: Some("some_source".to_string()),
+                        ..fdecl::UseEventStream::EMPTY
+                    }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_child("Storage", "source", "logger"),
-                Error::invalid_child("OfferService", "source", "logger"),
-                Error::invalid_child("OfferProtocol", "source", "logger"),
-                Error::invalid_child("OfferDirectory", "source", "logger"),
+                Error::MissingField(DeclField { decl: "UseEventStream".to_string(), field: "scope".to_string(), index: None })
             ])),
         },
-        test_validate_offers_invalid_source_capability => {
+        test_validate_uses_no_runner => {
             input = {
-                fdecl::Component {
-                    offers: Some(vec![
-                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
-                                name: "this-storage-doesnt-exist".to_string(),
-                            })),
-                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            }
-                            )),
-                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            ..fdecl::OfferProtocol::EMPTY
-                        }),
-                    ]),
-                    ..new_component_decl()
-                }
+                let mut decl = new_component_decl();
+                decl.program = Some(fdecl::Program {
+                    runner: None,
+                    info: Some(fdata::Dictionary {
+                        entries: None,
+                        ..fdata::Dictionary::EMPTY
+                    }),
+                    ..fdecl::Program::EMPTY
+                });
+                decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_capability("OfferProtocol", "source", "this-storage-doesnt-exist"),
-                Error::invalid_child("OfferProtocol", "target", "netstack"),
+                Error::missing_field("Program", "runner"),
             ])),
         },
-        test_validate_offers_target => {
+        test_validate_uses_long_identifiers => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec!["instance_0".to_string()]),
-                        ..fdecl::OfferService::EMPTY
+                decl.program = Some(fdecl::Program {
+                    runner: Some("elf".to_string()),
+                    info: Some(fdata::Dictionary {
+                        entries: None,
+                        ..fdata::Dictionary::EMPTY
                     }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec!["instance_1".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                    ..fdecl::Program::EMPTY
+                });
+                decl.uses = Some(vec![
+                    fdecl::Use::Service(fdecl::UseService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_path: Some(format!("/s/{}", "b".repeat(1024))),
                         dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
+                        ..fdecl::UseService::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
                         dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_path: Some(format!("/p/{}", "c".repeat(1024))),
+                        ..fdecl::UseProtocol::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("assets".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
+                    fdecl::Use::Directory(fdecl::UseDirectory {
                         dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("assets".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_path: Some(format!("/d/{}", "d".repeat(1024))),
                         rights: Some(fio::Operations::CONNECT),
                         subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferDirectory::EMPTY
+                        ..fdecl::UseDirectory::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("duplicated".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Use::Storage(fdecl::UseStorage {
+                        source_name: Some("cache".to_string()),
+                        target_path: Some(format!("/{}", "e".repeat(1024))),
+                        ..fdecl::UseStorage::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("duplicated".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_name: Some(format!("{}", "a".repeat(101))),
+                        filter: None,
+                        ..fdecl::UseEvent::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string() }
-                        )),
-                        target_name: Some("duplicated".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long_with_max("UseEvent", "source_name", 100),
+                Error::field_too_long_with_max("UseEvent", "target_name", 100),
+                Error::field_too_long_with_max("UseService", "source_name", 100),
+                Error::field_too_long_with_max("UseService", "target_path", 1024),
+                Error::field_too_long_with_max("UseProtocol", "source_name", 100),
+                Error::field_too_long_with_max("UseProtocol", "target_path", 1024),
+                Error::field_too_long_with_max("UseDirectory", "source_name", 100),
+                Error::field_too_long_with_max("UseDirectory", "target_path", 1024),
+                Error::field_too_long_with_max("UseStorage", "target_path", 1024),
+            ])),
+        },
+        test_validate_conflicting_paths => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Service(fdecl::UseService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("foo".to_string()),
+                        target_path: Some("/bar".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::UseService::EMPTY
                     }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
+                    fdecl::Use::Protocol(fdecl::UseProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
                         source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("stopped".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        })),
+                        source_name: Some("space".to_string()),
+                        target_path: Some("/bar".to_string()),
+                        ..fdecl::UseProtocol::EMPTY
+                    }),
+                    fdecl::Use::Directory(fdecl::UseDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("crow".to_string()),
+                        target_path: Some("/bar".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::UseDirectory::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("UseProtocol", "path", "/bar"),
+                Error::duplicate_field("UseDirectory", "path", "/bar"),
+            ])),
+        },
+        test_validate_use_and_offer_event_same_target_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("started".to_string()),
                         target_name: Some("started".to_string()),
-                        filter: None,
-                        ..fdecl::OfferEvent::EMPTY
+                        ..fdecl::UseEvent::EMPTY
                     }),
+                ]);
+                decl.offers = Some(vec![
                     fdecl::Offer::Event(fdecl::OfferEvent {
                         source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        source_name: Some("started_on_x".to_string()),
+                        source_name: Some("started".to_string()),
                         target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "netstack".to_string(),
+                            name: "test".to_string(),
                             collection: None,
                         })),
                         target_name: Some("started".to_string()),
@@ -6417,2730 +7517,6670 @@ mod tests {
                         ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
-                decl.children = Some(vec![
-                    fdecl::Child{
-                        name: Some("netstack".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Eager),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("test".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/test#meta/test.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            // The used name and the offered name live in different namespaces (the component's
+            // own vs. the target child's), so this is not a clash.
+            result = Ok(()),
+        },
+        test_validate_events_can_come_before_or_after_event_stream => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("started".to_string()),
+                        target_name: Some("started".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::UseEvent::EMPTY
+                    }),
+                    fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                        name: Some("bar".to_string()),
+                        subscriptions: Some(
+                            vec!["started".to_string(), "stopped".to_string()]
+                                .into_iter()
+                                .map(|name| fdecl::EventSubscription {
+                                    event_name: Some(name),
+                                    ..fdecl::EventSubscription::EMPTY
+                                })
+                                .collect()
+                            ),
+                        ..fdecl::UseEventStreamDeprecated::EMPTY
+                    }),
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        source_name: Some("stopped".to_string()),
+                        target_name: Some("stopped".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::UseEvent::EMPTY
+                    }),
                 ]);
-                decl.collections = Some(vec![
-                    fdecl::Collection{
-                        name: Some("modular".to_string()),
-                        durability: Some(fdecl::Durability::Transient),
-                        environment: None,
-                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                        allow_long_names: None,
-                        ..fdecl::Collection::EMPTY
-                    },
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_uses_invalid_self_source => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.uses = Some(vec![
+                    fdecl::Use::Event(fdecl::UseEvent {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("started".to_string()),
+                        target_name: Some("foo_started".to_string()),
+                        ..fdecl::UseEvent::EMPTY
+                    }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                // Duplicate services are allowed.
-                Error::duplicate_field("OfferProtocol", "target_name", "fuchsia.logger.LegacyLog"),
-                Error::duplicate_field("OfferDirectory", "target_name", "assets"),
-                Error::duplicate_field("OfferRunner", "target_name", "duplicated"),
-                Error::duplicate_field("OfferResolver", "target_name", "duplicated"),
-                Error::duplicate_field("OfferEvent", "target_name", "started"),
+                Error::invalid_field("UseEvent", "source"),
             ])),
         },
-        test_validate_offers_target_invalid => {
+        // exposes
+        test_validate_exposes_empty => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        ..fdecl::OfferService::EMPTY
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: None,
+                        source_name: None,
+                        target_name: None,
+                        target: None,
+                        ..fdecl::ExposeService::EMPTY
                     }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("logger".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        ..fdecl::OfferService::EMPTY
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: None,
+                        source_name: None,
+                        target_name: None,
+                        target: None,
+                        ..fdecl::ExposeProtocol::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("legacy_logger".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferProtocol::EMPTY
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: None,
+                        source_name: None,
+                        target_name: None,
+                        target: None,
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
                     }),
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("legacy_logger".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
-                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        ..fdecl::ExposeRunner::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef {
-                            name: "netstack".to_string(),
-                            collection: None,
-                        }
-                        )),
-                        target_name: Some("data".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        ..fdecl::ExposeResolver::EMPTY
                     }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("assets".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
-                        target_name: Some("data".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Weak),
-                        ..fdecl::OfferDirectory::EMPTY
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ExposeService", "source"),
+                Error::missing_field("ExposeService", "target"),
+                Error::missing_field("ExposeService", "source_name"),
+                Error::missing_field("ExposeService", "target_name"),
+                Error::missing_field("ExposeProtocol", "source"),
+                Error::missing_field("ExposeProtocol", "target"),
+                Error::missing_field("ExposeProtocol", "source_name"),
+                Error::missing_field("ExposeProtocol", "target_name"),
+                Error::missing_field("ExposeDirectory", "source"),
+                Error::missing_field("ExposeDirectory", "target"),
+                Error::missing_field("ExposeDirectory", "source_name"),
+                Error::missing_field("ExposeDirectory", "target_name"),
+                Error::missing_field("ExposeRunner", "source"),
+                Error::missing_field("ExposeRunner", "target"),
+                Error::missing_field("ExposeRunner", "source_name"),
+                Error::missing_field("ExposeRunner", "target_name"),
+                Error::missing_field("ExposeResolver", "source"),
+                Error::missing_field("ExposeResolver", "target"),
+                Error::missing_field("ExposeResolver", "source_name"),
+                Error::missing_field("ExposeResolver", "target_name"),
+            ])),
+        },
+        test_validate_exposes_extraneous => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("logger".to_string()),
+                        target_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("legacy_logger".to_string()),
+                        target_name: Some("legacy_logger".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
                         source_name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        target: Some(fdecl::Ref::Collection(
-                            fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
                         target_name: Some("data".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
-                    }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("elf".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
                         source_name: Some("elf".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
                         target_name: Some("elf".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
-                    }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                        source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("pkg".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                        ..fdecl::ExposeRunner::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
                         source_name: Some("pkg".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
                         target_name: Some("pkg".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
-                    }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("started".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        filter: None,
-                        ..fdecl::OfferEvent::EMPTY
-                    }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source_name: Some("started".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        target_name: Some("started".to_string()),
-                        target: Some(fdecl::Ref::Collection(
-                        fdecl::CollectionRef { name: "modular".to_string(), }
-                        )),
-                        filter: None,
-                        ..fdecl::OfferEvent::EMPTY
+                        ..fdecl::ExposeResolver::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_child("OfferService", "target", "netstack"),
-                Error::invalid_collection("OfferService", "target", "modular"),
-                Error::invalid_child("OfferProtocol", "target", "netstack"),
-                Error::invalid_collection("OfferProtocol", "target", "modular"),
-                Error::invalid_child("OfferDirectory", "target", "netstack"),
-                Error::invalid_collection("OfferDirectory", "target", "modular"),
-                Error::invalid_child("OfferStorage", "target", "netstack"),
-                Error::invalid_collection("OfferStorage", "target", "modular"),
-                Error::invalid_child("OfferRunner", "target", "netstack"),
-                Error::invalid_collection("OfferRunner", "target", "modular"),
-                Error::invalid_child("OfferResolver", "target", "netstack"),
-                Error::invalid_collection("OfferResolver", "target", "modular"),
-                Error::invalid_child("OfferEvent", "target", "netstack"),
-                Error::invalid_collection("OfferEvent", "target", "modular"),
-            ])),
+                Error::extraneous_field("ExposeService", "source.child.collection"),
+                Error::extraneous_field("ExposeProtocol", "source.child.collection"),
+                Error::extraneous_field("ExposeDirectory", "source.child.collection"),
+                Error::extraneous_field("ExposeRunner", "source.child.collection"),
+                Error::extraneous_field("ExposeResolver", "source.child.collection"),
+            ])),
         },
-        test_validate_offers_invalid_source_collection => {
+        test_validate_exposes_invalid_identifiers => {
             input = {
                 let mut decl = new_component_decl();
-                decl.collections = Some(vec![
-                    fdecl::Collection {
-                        name: Some("col".to_string()),
-                        durability: Some(fdecl::Durability::Transient),
-                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                        allow_long_names: None,
-                        ..fdecl::Collection::EMPTY
-                    }
-                ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("child".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        ..fdecl::Child::EMPTY
-                    }
-                ]);
-                decl.offers = Some(vec![
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("a".to_string()),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("b".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("b".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        subdir: None,
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferDirectory::EMPTY
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target_name: Some("/".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
                     }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("c".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("c".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target_name: Some("/".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
                     }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("d".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("d".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target_name: Some("/".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: Some("/foo".to_string()),
+                        ..fdecl::ExposeDirectory::EMPTY
                     }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("e".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("e".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("/path".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf!".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
                     }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
-                        source_name: Some("f".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("f".to_string()),
-                        filter: None,
-                        ..fdecl::OfferEvent::EMPTY
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("/path".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("pkg!".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("OfferProtocol", "source"),
-                Error::invalid_field("OfferDirectory", "source"),
-                Error::invalid_field("OfferStorage", "source"),
-                Error::invalid_field("OfferRunner", "source"),
-                Error::invalid_field("OfferResolver", "source"),
-                Error::invalid_field("OfferEvent", "source"),
+                Error::invalid_field("ExposeService", "source.child.name"),
+                Error::invalid_field("ExposeService", "source_name"),
+                Error::invalid_field("ExposeService", "target_name"),
+                Error::invalid_field("ExposeProtocol", "source.child.name"),
+                Error::invalid_field("ExposeProtocol", "source_name"),
+                Error::invalid_field("ExposeProtocol", "target_name"),
+                Error::invalid_field("ExposeDirectory", "source.child.name"),
+                Error::invalid_field("ExposeDirectory", "source_name"),
+                Error::invalid_field("ExposeDirectory", "target_name"),
+                Error::invalid_field("ExposeDirectory", "subdir"),
+                Error::invalid_field("ExposeRunner", "source.child.name"),
+                Error::invalid_field("ExposeRunner", "source_name"),
+                Error::invalid_field("ExposeRunner", "target_name"),
+                Error::invalid_field("ExposeResolver", "source.child.name"),
+                Error::invalid_field("ExposeResolver", "source_name"),
+                Error::invalid_field("ExposeResolver", "target_name"),
             ])),
         },
-        test_validate_offers_source_collection => {
+        test_validate_exposes_invalid_source_target => {
             input = {
                 let mut decl = new_component_decl();
-                decl.collections = Some(vec![
-                    fdecl::Collection {
-                        name: Some("col".to_string()),
-                        durability: Some(fdecl::Durability::Transient),
-                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                        allow_long_names: None,
-                        ..fdecl::Collection::EMPTY
-                    }
-                ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("child".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        ..fdecl::Child::EMPTY
-                    }
-                ]);
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("logger".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: None,
                         source_name: Some("a".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                        target_name: Some("a".to_string()),
-                        ..fdecl::OfferService::EMPTY
-                    })
+                        target_name: Some("b".to_string()),
+                        target: None,
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("c".to_string()),
+                        target_name: Some("d".to_string()),
+                        target: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "z".to_string()})),
+                        source_name: Some("e".to_string()),
+                        target_name: Some("f".to_string()),
+                        target: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "z".to_string()})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("g".to_string()),
+                        target_name: Some("h".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("i".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        target_name: Some("j".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("k".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        target_name: Some("l".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("m".to_string()),
+                        target_name: Some("n".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
                 ]);
                 decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ExposeService", "source"),
+                Error::missing_field("ExposeService", "target"),
+                Error::invalid_field("ExposeProtocol", "source"),
+                Error::invalid_field("ExposeProtocol", "target"),
+                Error::invalid_field("ExposeDirectory", "source"),
+                Error::invalid_field("ExposeDirectory", "target"),
+                Error::invalid_field("ExposeDirectory", "source"),
+                Error::invalid_field("ExposeDirectory", "source"),
+                Error::invalid_field("ExposeRunner", "source"),
+                Error::invalid_field("ExposeRunner", "source"),
+                Error::invalid_field("ExposeResolver", "source"),
+                Error::invalid_field("ExposeResolver", "source"),
+                Error::invalid_field("ExposeDirectory", "source"),
+            ])),
         },
-        test_validate_offers_event_from_realm => {
+        test_validate_exposes_to_framework_from_child => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(
-                    vec![
-                        fdecl::Ref::Self_(fdecl::SelfRef {}),
-                        fdecl::Ref::Child(fdecl::ChildRef {name: "netstack".to_string(), collection: None }),
-                        fdecl::Ref::Collection(fdecl::CollectionRef {name: "modular".to_string() }),
-                    ]
-                    .into_iter()
-                    .enumerate()
-                    .map(|(i, source)| {
-                        fdecl::Offer::Event(fdecl::OfferEvent {
-                            source: Some(source),
-                            source_name: Some("started".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                name: "netstack".to_string(),
-                                collection: None,
-                            })),
-                            target_name: Some(format!("started_{}", i)),
-
-                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
-                            ..fdecl::OfferEvent::EMPTY
-                        })
-                    })
-                    .collect());
-                decl.children = Some(vec![
-                    fdecl::Child{
-                        name: Some("netstack".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Eager),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("logger".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("a".to_string()),
+                        target_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("b".to_string()),
+                        target_name: Some("b".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("c".to_string()),
+                        target_name: Some("c".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("d".to_string()),
+                        target_name: Some("d".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("ExposeService", "source"),
+                Error::invalid_field("ExposeProtocol", "source"),
+                Error::invalid_field("ExposeRunner", "source"),
+                Error::invalid_field("ExposeResolver", "source"),
+            ])),
+        },
+        test_validate_exposes_invalid_source_collection => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.collections = Some(vec![fdecl::Collection{
+                    name: Some("col".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    allowed_offers: None,
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("a".to_string()),
+                        target_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
+                        source_name: Some("b".to_string()),
+                        target_name: Some("b".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
+                        source_name: Some("c".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("c".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {name: "col".to_string()})),
+                        source_name: Some("d".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("d".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
                 ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("ExposeProtocol", "source"),
+                Error::invalid_field("ExposeDirectory", "source"),
+                Error::invalid_field("ExposeRunner", "source"),
+                Error::invalid_field("ExposeResolver", "source"),
+            ])),
+        },
+        test_validate_exposes_sources_collection => {
+            input = {
+                let mut decl = new_component_decl();
                 decl.collections = Some(vec![
                     fdecl::Collection {
-                        name: Some("modular".to_string()),
+                        name: Some("col".to_string()),
                         durability: Some(fdecl::Durability::Transient),
-                        environment: None,
                         allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
                         allow_long_names: None,
                         ..fdecl::Collection::EMPTY
-                    },
+                    }
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("a".to_string()),
+                        ..fdecl::ExposeService::EMPTY
+                    })
                 ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_field("OfferEvent", "source"),
-                Error::invalid_field("OfferEvent", "source"),
-                Error::invalid_field("OfferEvent", "source"),
-            ])),
+            result = Ok(()),
         },
-        test_validate_offers_long_dependency_cycle => {
+        test_validate_expose_service_and_protocol_same_target_name => {
             input = {
                 let mut decl = new_component_decl();
-                let dependencies = vec![
-                    ("d", "b"),
-                    ("a", "b"),
-                    ("b", "c"),
-                    ("b", "d"),
-                    ("c", "a"),
-                ];
-                let offers = dependencies.into_iter().map(|(from,to)|
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        source: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef { name: from.to_string(), collection: None },
-                        )),
-                        source_name: Some(format!("thing_{}", from)),
-                        target: Some(fdecl::Ref::Child(
-                        fdecl::ChildRef { name: to.to_string(), collection: None },
-                        )),
-                        target_name: Some(format!("thing_{}", from)),
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        ..fdecl::OfferProtocol::EMPTY
-                    })).collect();
-                let children = ["a", "b", "c", "d"].iter().map(|name| {
+                decl.children = Some(vec![
                     fdecl::Child {
-                        name: Some(name.to_string()),
-                        url: Some(format!("fuchsia-pkg://fuchsia.com/pkg#meta/{}.cm", name)),
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child_a.cm".to_string()),
                         startup: Some(fdecl::StartupMode::Lazy),
                         on_terminate: None,
                         environment: None,
                         ..fdecl::Child::EMPTY
-                    }
-                }).collect();
-                decl.offers = Some(offers);
-                decl.children = Some(children);
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child_b.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl.exposes = Some(vec![
+                    // Two service exposes may aggregate under one target name...
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child_a".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.examples.Echo".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("fuchsia.examples.Echo".to_string()),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child_b".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.examples.Echo".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("fuchsia.examples.Echo".to_string()),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    // ...but a protocol expose cannot share that name.
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child_a".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.examples.Echo".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("fuchsia.examples.Echo".to_string()),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle(directed_graph::Error::CyclesDetected([vec!["child a", "child b", "child c", "child a"], vec!["child b", "child d", "child b"]].iter().cloned().collect()).format_cycle()),
+                Error::duplicate_field("ExposeProtocol", "target_name", "fuchsia.examples.Echo"),
             ])),
         },
-        test_validate_offers_not_required_invalid_source_service => {
+        test_validate_exposes_long_identifiers => {
             input = {
-                let mut decl = generate_offer_invalid_source_and_availability_decl(
-                    |source, availability, target_name|
-                        fdecl::Offer::Service(fdecl::OfferService {
-                            source: Some(source),
-                            source_name: Some("fuchsia.examples.Echo".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                name: "sink".to_string(),
-                                collection: None,
-                            })),
-                            target_name: Some(target_name.into()),
-                            availability: Some(availability),
-                            ..fdecl::OfferService::EMPTY
-                        })
-                );
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("fuchsia.examples.Echo".to_string()),
-                        source_path: Some("/svc/fuchsia.examples.Echo".to_string()),
-                        ..fdecl::Service::EMPTY
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(1025))),
+                        target_name: Some(format!("{}", "b".repeat(1025))),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
                     }),
-                ]);
-                decl
-            },
-            result = {
-                Err(ErrorList::new(vec![
-                    Error::availability_must_be_optional(
-                        "OfferService",
-                        "availability",
-                        Some(&"fuchsia.examples.Echo".to_string()),
-                    ),
-                    Error::availability_must_be_optional(
-                        "OfferService",
-                        "availability",
-                        Some(&"fuchsia.examples.Echo".to_string()),
-                    ),
-                ]))
-            },
-        },
-        test_validate_offers_not_required_invalid_source_protocol => {
-            input = {
-                let mut decl = generate_offer_invalid_source_and_availability_decl(
-                    |source, availability, target_name|
-                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                            source: Some(source),
-                            source_name: Some("fuchsia.examples.Echo".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                name: "sink".to_string(),
-                                collection: None,
-                            })),
-                            target_name: Some(target_name.into()),
-                            dependency_type: Some(fdecl::DependencyType::Strong),
-                            availability: Some(availability),
-                            ..fdecl::OfferProtocol::EMPTY
-                        })
-                );
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("fuchsia.examples.Echo".to_string()),
-                        source_path: Some("/svc/fuchsia.examples.Echo".to_string()),
-                        ..fdecl::Protocol::EMPTY
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some("a".repeat(101)),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("b".repeat(101)),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some("a".repeat(101)),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("b".repeat(101)),
+                        ..fdecl::ExposeResolver::EMPTY
                     }),
                 ]);
                 decl
             },
-            result = {
-                Err(ErrorList::new(vec![
-                    Error::availability_must_be_optional(
-                        "OfferProtocol",
-                        "availability",
-                        Some(&"fuchsia.examples.Echo".to_string()),
-                    ),
-                    Error::availability_must_be_optional(
-                        "OfferProtocol",
-                        "availability",
-                        Some(&"fuchsia.examples.Echo".to_string()),
-                    ),
-                ]))
-            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long("ExposeService", "source.child.name"),
+                Error::field_too_long("ExposeService", "source_name"),
+                Error::field_too_long("ExposeService", "target_name"),
+                Error::field_too_long("ExposeProtocol", "source.child.name"),
+                Error::field_too_long("ExposeProtocol", "source_name"),
+                Error::field_too_long("ExposeProtocol", "target_name"),
+                Error::field_too_long("ExposeDirectory", "source.child.name"),
+                Error::field_too_long("ExposeDirectory", "source_name"),
+                Error::field_too_long("ExposeDirectory", "target_name"),
+                Error::field_too_long("ExposeRunner", "source.child.name"),
+                Error::field_too_long("ExposeRunner", "source_name"),
+                Error::field_too_long("ExposeRunner", "target_name"),
+                Error::field_too_long("ExposeResolver", "source.child.name"),
+                Error::field_too_long("ExposeResolver", "source_name"),
+                Error::field_too_long("ExposeResolver", "target_name"),
+            ])),
         },
-        test_validate_offers_not_required_invalid_source_directory => {
+        test_validate_exposes_invalid_child => {
             input = {
-                let mut decl = generate_offer_invalid_source_and_availability_decl(
-                    |source, availability, target_name|
-                        fdecl::Offer::Directory(fdecl::OfferDirectory {
-                            source: Some(source),
-                            source_name: Some("assets".to_string()),
-                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                name: "sink".to_string(),
-                                collection: None,
-                            })),
-                            target_name: Some(target_name.into()),
-                            rights: Some(fio::Operations::CONNECT),
-                            subdir: None,
-                            dependency_type: Some(fdecl::DependencyType::Weak),
-                            availability: Some(availability),
-                            ..fdecl::OfferDirectory::EMPTY
-                        })
-                );
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("assets".to_string()),
-                        source_path: Some("/assets".to_string()),
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("data".to_string()),
+                        target_name: Some("data".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
                         rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
                     }),
                 ]);
                 decl
             },
-            result = {
-                Err(ErrorList::new(vec![
-                    Error::availability_must_be_optional(
-                        "OfferDirectory",
-                        "availability",
-                        Some(&"assets".to_string()),
-                    ),
-                    Error::availability_must_be_optional(
-                        "OfferDirectory",
-                        "availability",
-                        Some(&"assets".to_string()),
-                    ),
-                ]))
+            result = Err(ErrorList::new(vec![
+                Error::invalid_child("ExposeService", "source", "netstack"),
+                Error::invalid_child("ExposeProtocol", "source", "netstack"),
+                Error::invalid_child("ExposeDirectory", "source", "netstack"),
+                Error::invalid_child("ExposeRunner", "source", "netstack"),
+                Error::invalid_child("ExposeResolver", "source", "netstack"),
+            ])),
+        },
+        test_validate_exposes_invalid_source_capability => {
+            input = {
+                fdecl::Component {
+                    exposes: Some(vec![
+                        fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                name: "this-storage-doesnt-exist".to_string(),
+                            })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                            ..fdecl::ExposeProtocol::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_capability("ExposeProtocol", "source", "this-storage-doesnt-exist"),
+            ])),
         },
-        test_validate_offers_not_required_invalid_source_storage => {
+        test_validate_exposes_duplicate_target => {
             input = {
                 let mut decl = new_component_decl();
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("sink".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/sink#meta/sink.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                ]);
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("data".to_string()),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                        backing_dir: Some("minfs".to_string()),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("netstack".to_string()),
+                        target_name: Some("fuchsia.net.Stack".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
                     }),
-                ]);
-                let new_offer = |source: fdecl::Ref, availability: fdecl::Availability,
-                                        target_name: &str|
-                {
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source: Some(source),
-                        source_name: Some("data".to_string()),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "sink".to_string(),
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("netstack2".to_string()),
+                        target_name: Some("fuchsia.net.Stack".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fonts".to_string()),
+                        target_name: Some("fuchsia.fonts.Provider".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fonts2".to_string()),
+                        target_name: Some("fuchsia.fonts.Provider".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets".to_string()),
+                        target_name: Some("stuff".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("assets2".to_string()),
+                        target_name: Some("stuff".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_elf".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_elf".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_pkg".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_pkg".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
+                ]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("netstack".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("netstack2".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fonts".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fonts2".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/path".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets2".to_string()),
+                        source_path: Some("/path".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("source_elf".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("source_pkg".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                // Duplicate services are allowed.
+                Error::duplicate_field("ExposeProtocol", "target_name",
+                                    "fuchsia.fonts.Provider"),
+                Error::duplicate_field("ExposeDirectory", "target_name",
+                                    "stuff"),
+                Error::duplicate_field("ExposeRunner", "target_name",
+                                    "elf"),
+                Error::duplicate_field("ExposeResolver", "target_name", "pkg"),
+            ])),
+        },
+        test_validate_expose_directory_service_same_target_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("netstack".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
                             collection: None,
                         })),
-                        target_name: Some(target_name.into()),
-                        availability: Some(availability),
-                        ..fdecl::OfferStorage::EMPTY
-                    })
-                };
-                decl.offers = Some(vec![
-                    // These offers are fine, offers with a source of parent or void can be
-                    // optional.
-                    new_offer(
-                        fdecl::Ref::Parent(fdecl::ParentRef {}),
-                        fdecl::Availability::Required,
-                        "data0",
-                    ),
-                    new_offer(
-                        fdecl::Ref::Parent(fdecl::ParentRef {}),
-                        fdecl::Availability::Optional,
-                        "data1",
-                    ),
-                    new_offer(
-                        fdecl::Ref::Parent(fdecl::ParentRef {}),
-                        fdecl::Availability::SameAsTarget,
-                        "data2",
-                    ),
-                    new_offer(
-                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                        fdecl::Availability::Optional,
-                        "data3",
-                    ),
-                    // These offers are not fine, offers with a source other than parent or void
-                    // must be required.
-                    new_offer(
-                        fdecl::Ref::Self_(fdecl::SelfRef {}),
-                        fdecl::Availability::Optional,
-                        "data4",
-                    ),
-                    new_offer(
-                        fdecl::Ref::Self_(fdecl::SelfRef {}),
-                        fdecl::Availability::SameAsTarget,
-                        "data5",
-                    ),
-                    // These offers are also not fine, offers with a source of void must be optional
-                    new_offer(
-                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                        fdecl::Availability::Required,
-                        "data6",
-                    ),
-                    new_offer(
-                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                        fdecl::Availability::SameAsTarget,
-                        "data7",
-                    ),
+                        source_name: Some("assets".to_string()),
+                        target_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("svc".to_string()),
+                        target_name: Some("thing".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
                 ]);
                 decl
             },
-            result = {
-                Err(ErrorList::new(vec![
-                    Error::availability_must_be_optional(
-                        "OfferStorage",
-                        "availability",
-                        Some(&"data".to_string()),
-                    ),
-                    Error::availability_must_be_optional(
-                        "OfferStorage",
-                        "availability",
-                        Some(&"data".to_string()),
-                    ),
-                ]))
-            },
+            // Services may aggregate under one target name, but only with other services;
+            // the directory's claim on "thing" is exclusive across capability kinds.
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("ExposeService", "target_name", "thing"),
+            ])),
         },
-
-        test_validate_offers_valid_service_aggregation => {
+        test_validate_exposes_same_target_name_different_targets => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec!["default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fonts".to_string()),
+                        target_name: Some("fuchsia.fonts.Provider".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fonts".to_string()),
+                        target_name: Some("fuchsia.fonts.Provider".to_string()),
+                        target: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                ]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fonts".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Protocol::EMPTY
                     }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec!["a_different_default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    })
-                ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("child_a".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_b".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_c".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
                 ]);
                 decl
             },
             result = Ok(()),
         },
-
-        // environments
-        test_validate_environment_empty => {
+        test_validate_exposes_invalid_capability_from_self => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: None,
-                    extends: None,
-                    runners: None,
-                    resolvers: None,
-                    stop_timeout_ms: None,
-                    debug_capabilities: None,
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Service(fdecl::ExposeService {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fuchsia.netstack.Netstack".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("foo".to_string()),
+                        ..fdecl::ExposeService::EMPTY
+                    }),
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fuchsia.netstack.Netstack".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("bar".to_string()),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                    fdecl::Expose::Directory(fdecl::ExposeDirectory {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("dir".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("assets".to_string()),
+                        rights: None,
+                        subdir: None,
+                        ..fdecl::ExposeDirectory::EMPTY
+                    }),
+                    fdecl::Expose::Runner(fdecl::ExposeRunner {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_elf".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::ExposeRunner::EMPTY
+                    }),
+                    fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("source_pkg".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::ExposeResolver::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Environment", "name"),
-                Error::missing_field("Environment", "extends"),
+                Error::invalid_capability("ExposeService", "source", "fuchsia.netstack.Netstack"),
+                Error::invalid_capability("ExposeProtocol", "source", "fuchsia.netstack.Netstack"),
+                Error::invalid_capability("ExposeDirectory", "source", "dir"),
+                Error::invalid_capability("ExposeRunner", "source", "source_elf"),
+                Error::invalid_capability("ExposeResolver", "source", "source_pkg"),
             ])),
         },
-
-        test_validate_environment_no_stop_timeout => {
-            input = {  let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("env".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: None,
-                    resolvers: None,
-                    stop_timeout_ms: None,
-                    ..fdecl::Environment::EMPTY
-                }]);
+        test_validate_exposes_capability_type_mismatch => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("fuchsia.fonts.Provider".to_string()),
+                        source_path: Some("/svc/fuchsia.fonts.Provider".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                ]);
+                decl.exposes = Some(vec![
+                    fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        source_name: Some("fuchsia.fonts.Provider".to_string()),
+                        target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("fuchsia.fonts.Provider".to_string()),
+                        ..fdecl::ExposeProtocol::EMPTY
+                    }),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![Error::missing_field("Environment", "stop_timeout_ms")])),
+            result = Err(ErrorList::new(vec![
+                Error::capability_type_mismatch(
+                    "ExposeProtocol", "source", "fuchsia.fonts.Provider", "protocol"),
+            ])),
         },
 
-        test_validate_environment_extends_stop_timeout => {
-            input = {  let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("env".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::Realm),
-                    runners: None,
-                    resolvers: None,
-                    stop_timeout_ms: None,
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl
-            },
-            result = Ok(()),
-        },
-        test_validate_environment_long_identifiers => {
+        // offers
+        test_validate_offers_empty => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".repeat(101)),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("a".repeat(101)),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            target_name: Some("a".repeat(101)),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("a".repeat(101)),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            scheme: Some("a".repeat(101)),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        dependency_type: None,
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        rights: None,
+                        subdir: None,
+                        dependency_type: None,
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: None,
+                        source: None,
+                        target: None,
+                        target_name: None,
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: None,
+                        source_name: None,
+                        target: None,
+                        target_name: None,
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
+                    })
+                ]);
                 decl
             },
+            // TODO(dgonyeo): we need to handle the availability being unset until we've soft
+            // migrated all manifests
             result = Err(ErrorList::new(vec![
-                Error::field_too_long("Environment", "name"),
-                Error::field_too_long("RunnerRegistration", "source_name"),
-                Error::field_too_long("RunnerRegistration", "target_name"),
-                Error::field_too_long("ResolverRegistration", "resolver"),
-                Error::field_too_long("ResolverRegistration", "scheme"),
+                Error::missing_field("OfferService", "source"),
+                Error::missing_field("OfferService", "source_name"),
+                Error::missing_field("OfferService", "target"),
+                Error::missing_field("OfferService", "target_name"),
+                //Error::missing_field("OfferService", "availability"),
+                Error::missing_field("OfferProtocol", "source"),
+                Error::missing_field("OfferProtocol", "source_name"),
+                Error::missing_field("OfferProtocol", "target"),
+                Error::missing_field("OfferProtocol", "target_name"),
+                Error::missing_field("OfferProtocol", "dependency_type"),
+                //Error::missing_field("OfferProtocol", "availability"),
+                Error::missing_field("OfferDirectory", "source"),
+                Error::missing_field("OfferDirectory", "source_name"),
+                Error::missing_field("OfferDirectory", "target"),
+                Error::missing_field("OfferDirectory", "target_name"),
+                Error::missing_field("OfferDirectory", "dependency_type"),
+                //Error::missing_field("OfferDirectory", "availability"),
+                Error::missing_field("OfferStorage", "source_name"),
+                Error::missing_field("OfferStorage", "source"),
+                Error::missing_field("OfferStorage", "target"),
+                //Error::missing_field("OfferStorage", "availability"),
+                Error::missing_field("OfferRunner", "source"),
+                Error::missing_field("OfferRunner", "source_name"),
+                Error::missing_field("OfferRunner", "target"),
+                Error::missing_field("OfferRunner", "target_name"),
+                //Error::missing_field("OfferRunner", "availability"),
+                Error::missing_field("OfferEvent", "source_name"),
+                Error::missing_field("OfferEvent", "source"),
+                Error::missing_field("OfferEvent", "target"),
+                Error::missing_field("OfferEvent", "target_name"),
+                //Error::missing_field("OfferEvent", "availability"),
             ])),
         },
-        test_validate_environment_empty_runner_resolver_fields => {
+        test_validate_offers_long_identifiers => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: None,
-                            source: None,
-                            target_name: None,
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: None,
-                            source: None,
-                            scheme: None,
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("RunnerRegistration", "source_name"),
-                Error::missing_field("RunnerRegistration", "source"),
-                Error::missing_field("RunnerRegistration", "target_name"),
-                Error::missing_field("ResolverRegistration", "resolver"),
-                Error::missing_field("ResolverRegistration", "source"),
-                Error::missing_field("ResolverRegistration", "scheme"),
-            ])),
-        },
-        test_validate_environment_invalid_fields => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("^a".to_string()),
-                            source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                            target_name: Some("%a".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("^a".to_string()),
-                            source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
-                            scheme: Some("9scheme".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef {
+                            name: "b".repeat(101),
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef {
+                            name: "b".repeat(101),
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef {
+                            name: "b".repeat(101),
+                        }
+                        )),
+                        target_name: Some(format!("{}", "b".repeat(101))),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "b".repeat(101),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target: Some(fdecl::Ref::Collection(
+                            fdecl::CollectionRef { name: "b".repeat(101) }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some("b".repeat(101)),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef {
+                            name: "c".repeat(101),
+                        }
+                        )),
+                        target_name: Some("d".repeat(101)),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None,
+                        })),
+                        source_name: Some("b".repeat(101)),
+                        target: Some(fdecl::Ref::Collection(
+                            fdecl::CollectionRef {
+                                name: "c".repeat(101),
+                            }
+                        )),
+                        target_name: Some("d".repeat(101)),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some(format!("{}", "a".repeat(101))),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "a".repeat(101),
+                            collection: None
+                        })),
+                        target_name: Some(format!("{}", "a".repeat(101))),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("RunnerRegistration", "source_name"),
-                Error::invalid_field("RunnerRegistration", "source"),
-                Error::invalid_field("RunnerRegistration", "target_name"),
-                Error::invalid_field("ResolverRegistration", "resolver"),
-                Error::invalid_field("ResolverRegistration", "source"),
-                Error::invalid_field("ResolverRegistration", "scheme"),
+                Error::field_too_long("OfferService", "source.child.name"),
+                Error::field_too_long("OfferService", "source_name"),
+                Error::field_too_long("OfferService", "target.child.name"),
+                Error::field_too_long("OfferService", "target_name"),
+                Error::field_too_long("OfferService", "target.collection.name"),
+                Error::field_too_long("OfferService", "target_name"),
+                Error::field_too_long("OfferProtocol", "source.child.name"),
+                Error::field_too_long("OfferProtocol", "source_name"),
+                Error::field_too_long("OfferProtocol", "target.child.name"),
+                Error::field_too_long("OfferProtocol", "target_name"),
+                Error::field_too_long("OfferProtocol", "target.collection.name"),
+                Error::field_too_long("OfferProtocol", "target_name"),
+                Error::field_too_long("OfferDirectory", "source.child.name"),
+                Error::field_too_long("OfferDirectory", "source_name"),
+                Error::field_too_long("OfferDirectory", "target.child.name"),
+                Error::field_too_long("OfferDirectory", "target_name"),
+                Error::field_too_long("OfferDirectory", "target.collection.name"),
+                Error::field_too_long("OfferDirectory", "target_name"),
+                Error::field_too_long("OfferStorage", "target.child.name"),
+                Error::field_too_long("OfferStorage", "target.collection.name"),
+                Error::field_too_long("OfferRunner", "source.child.name"),
+                Error::field_too_long("OfferRunner", "source_name"),
+                Error::field_too_long("OfferRunner", "target.collection.name"),
+                Error::field_too_long("OfferRunner", "target_name"),
+                Error::field_too_long("OfferResolver", "source.child.name"),
+                Error::field_too_long("OfferResolver", "source_name"),
+                Error::field_too_long("OfferResolver", "target.collection.name"),
+                Error::field_too_long("OfferResolver", "target_name"),
+                Error::field_too_long("OfferEvent", "source_name"),
+                Error::field_too_long("OfferEvent", "target.child.name"),
+                Error::field_too_long("OfferEvent", "target_name"),
             ])),
         },
-        test_validate_environment_missing_runner => {
+        test_validate_offers_extraneous => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("dart".to_string()),
-                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                            target_name: Some("dart".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: None,
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_runner("RunnerRegistration", "source_name", "dart"),
-            ])),
-        },
-        test_validate_environment_duplicate_registrations => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("dart".to_string()),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            target_name: Some("dart".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                        fdecl::RunnerRegistration {
-                            source_name: Some("other-dart".to_string()),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            target_name: Some("dart".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("pkg_resolver".to_string()),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            scheme: Some("fuchsia-pkg".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                        fdecl::ResolverRegistration {
-                            resolver: Some("base_resolver".to_string()),
-                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
-                            scheme: Some("fuchsia-pkg".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{ })),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: Some("modular".to_string()),
+                        })),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: Some("modular".to_string()),
+                            }
+                        )),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                ]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fuchsia.logger.Log".to_string()),
+                        source_path: Some("/svc/logger".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/data/assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::duplicate_field("RunnerRegistration", "target_name", "dart"),
-                Error::duplicate_field("ResolverRegistration", "scheme", "fuchsia-pkg"),
+                Error::extraneous_field("OfferService", "source.child.collection"),
+                Error::extraneous_field("OfferService", "target.child.collection"),
+                Error::extraneous_field("OfferProtocol", "source.child.collection"),
+                Error::extraneous_field("OfferProtocol", "target.child.collection"),
+                Error::extraneous_field("OfferDirectory", "source.child.collection"),
+                Error::extraneous_field("OfferDirectory", "target.child.collection"),
+                Error::extraneous_field("OfferStorage", "target.child.collection"),
+                Error::extraneous_field("OfferRunner", "source.child.collection"),
+                Error::extraneous_field("OfferRunner", "target.child.collection"),
+                Error::extraneous_field("OfferResolver", "source.child.collection"),
+                Error::extraneous_field("OfferResolver", "target.child.collection"),
             ])),
         },
-        test_validate_environment_from_missing_child => {
+        test_validate_offers_invalid_filtered_service_fields => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("a".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("elf".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
-                                name: "missing".to_string(),
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "logger".to_string(),
                                 collection: None,
-                            })),
-                            target_name: Some("elf".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("pkg_resolver".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
-                                name: "missing".to_string(),
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec![]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "logger".to_string(),
                                 collection: None,
-                            })),
-                            scheme: Some("fuchsia-pkg".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log1".to_string()),
+                        renamed_instances: Some(vec![fdecl::NameMapping{source_name: "a".to_string(), target_name: "b".to_string()}, fdecl::NameMapping{source_name: "c".to_string(), target_name: "b".to_string()}]),
+                        ..fdecl::OfferService::EMPTY
+                    })
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("logger".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_child("RunnerRegistration", "source", "missing"),
-                Error::invalid_child("ResolverRegistration", "source", "missing"),
+                Error::invalid_field("OfferService", "source_instance_filter"),
+                Error::invalid_field("OfferService", "renamed_instances"),
             ])),
         },
-        test_validate_environment_runner_child_cycle => {
+        test_validate_offers_invalid_identifiers => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("env".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: Some(vec![
-                        fdecl::RunnerRegistration {
-                            source_name: Some("elf".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
-                                name: "child".to_string(),
-                                collection: None,
-                            })),
-                            target_name: Some("elf".to_string()),
-                            ..fdecl::RunnerRegistration::EMPTY
-                        },
-                    ]),
-                    resolvers: None,
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl.children = Some(vec![fdecl::Child {
-                    name: Some("child".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    url: Some("fuchsia-pkg://child".to_string()),
-                    environment: Some("env".to_string()),
-                    ..fdecl::Child::EMPTY
-                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("/".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("/".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("foo/".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("/".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: Some("/foo".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("/path".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("elf!".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "^bad".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("/path".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("pkg!".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("/path".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "%bad".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("/path".to_string()),
+                        filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                        ..fdecl::OfferEvent::EMPTY
+                    })
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle(
-                    directed_graph::Error::CyclesDetected([vec!["child child", "environment env", "child child"]].iter().cloned().collect()).format_cycle()
-                ),
+                Error::invalid_field("OfferService", "source.child.name"),
+                Error::invalid_field("OfferService", "source_name"),
+                Error::invalid_field("OfferService", "target.child.name"),
+                Error::invalid_field("OfferService", "target_name"),
+                Error::invalid_field("OfferProtocol", "source.child.name"),
+                Error::invalid_field("OfferProtocol", "source_name"),
+                Error::invalid_field("OfferProtocol", "target.child.name"),
+                Error::invalid_field("OfferProtocol", "target_name"),
+                Error::invalid_field("OfferDirectory", "source.child.name"),
+                Error::invalid_field("OfferDirectory", "source_name"),
+                Error::invalid_field("OfferDirectory", "target.child.name"),
+                Error::invalid_field("OfferDirectory", "target_name"),
+                Error::invalid_field("OfferDirectory", "subdir"),
+                Error::invalid_field("OfferRunner", "source.child.name"),
+                Error::invalid_field("OfferRunner", "source_name"),
+                Error::invalid_field("OfferRunner", "target.child.name"),
+                Error::invalid_field("OfferRunner", "target_name"),
+                Error::invalid_field("OfferResolver", "source.child.name"),
+                Error::invalid_field("OfferResolver", "source_name"),
+                Error::invalid_field("OfferResolver", "target.child.name"),
+                Error::invalid_field("OfferResolver", "target_name"),
+                Error::invalid_field("OfferEvent", "source_name"),
+                Error::invalid_field("OfferEvent", "target.child.name"),
+                Error::invalid_field("OfferEvent", "target_name"),
             ])),
         },
-        test_validate_environment_resolver_child_cycle => {
+        test_validate_offers_duplicate_runner_target => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("env".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: None,
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("pkg_resolver".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
-                                name: "child".to_string(),
-                                collection: None,
-                            })),
-                            scheme: Some("fuchsia-pkg".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("source_elf".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                ]);
                 decl.children = Some(vec![fdecl::Child {
                     name: Some("child".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child.cm".to_string()),
                     startup: Some(fdecl::StartupMode::Lazy),
                     on_terminate: None,
-                    url: Some("fuchsia-pkg://child".to_string()),
-                    environment: Some("env".to_string()),
+                    environment: None,
                     ..fdecl::Child::EMPTY
                 }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("source_elf".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("source_elf".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle(
-                    directed_graph::Error::CyclesDetected([vec!["child child", "environment env", "child child"]].iter().cloned().collect()).format_cycle()
-                ),
+                Error::duplicate_field("OfferRunner", "target_name", "elf"),
             ])),
         },
-        test_validate_environment_resolver_multiple_children_cycle => {
+        test_validate_offers_duplicate_resolver_target => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![fdecl::Environment {
-                    name: Some("env".to_string()),
-                    extends: Some(fdecl::EnvironmentExtends::None),
-                    runners: None,
-                    resolvers: Some(vec![
-                        fdecl::ResolverRegistration {
-                            resolver: Some("pkg_resolver".to_string()),
-                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
-                                name: "a".to_string(),
-                                collection: None,
-                            })),
-                            scheme: Some("fuchsia-pkg".to_string()),
-                            ..fdecl::ResolverRegistration::EMPTY
-                        },
-                    ]),
-                    stop_timeout_ms: Some(1234),
-                    ..fdecl::Environment::EMPTY
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("source_pkg".to_string()),
+                        source_path: Some("/path".to_string()),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
                 }]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("a".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        url: Some("fuchsia-pkg://child-a".to_string()),
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("b".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        url: Some("fuchsia-pkg://child-b".to_string()),
-                        environment: Some("env".to_string()),
-                        ..fdecl::Child::EMPTY
-                    },
+                decl.offers = Some(vec![
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("source_pkg".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("source_pkg".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "child".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
                 ]);
-                decl.offers = Some(vec![fdecl::Offer::Service(fdecl::OfferService {
-                    source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                        name: "b".to_string(),
-                        collection: None,
-                    })),
-                    source_name: Some("thing".to_string()),
-                    target: Some(fdecl::Ref::Child(
-                    fdecl::ChildRef {
-                        name: "a".to_string(),
-                        collection: None,
-                    }
-                    )),
-                    target_name: Some("thing".to_string()),
-                    ..fdecl::OfferService::EMPTY
-                })]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::dependency_cycle(
-                    directed_graph::Error::CyclesDetected([vec!["child a", "environment env", "child b", "child a"]].iter().cloned().collect()).format_cycle()
-                ),
+                Error::duplicate_field("OfferResolver", "target_name", "pkg"),
             ])),
         },
-        test_validate_environment_debug_empty => {
+        test_validate_static_offer_to_static_only_collection => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: None,
-                                source_name: None,
-                                target_name: None,
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("DebugProtocolRegistration", "source"),
-                Error::missing_field("DebugProtocolRegistration", "source_name"),
-                Error::missing_field("DebugProtocolRegistration", "target_name"),
-            ])),
-        },
-        test_validate_environment_debug_log_identifier => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                    name: "a".repeat(101),
-                                    collection: None,
-                                })),
-                                source_name: Some(format!("{}", "a".repeat(101))),
-                                target_name: Some(format!("{}", "b".repeat(101))),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                                source_name: Some("a".to_string()),
-                                target_name: Some(format!("{}", "b".repeat(101))),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.collections = Some(vec![
+                    fdecl::Collection {
+                        name: Some("coll".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                        ..fdecl::Collection::EMPTY
+                    },
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "coll".to_string(),
+                        })),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::field_too_long("DebugProtocolRegistration", "source.child.name"),
-                Error::field_too_long("DebugProtocolRegistration", "source_name"),
-                Error::field_too_long("DebugProtocolRegistration", "target_name"),
-                Error::field_too_long("DebugProtocolRegistration", "target_name"),
-            ])),
+            // `StaticOnly` restricts dynamic offers; static offers into the collection are
+            // always allowed.
+            result = Ok(()),
         },
-        test_validate_environment_debug_log_extraneous => {
+        test_validate_offers_self_target_equals_source => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                    name: "logger".to_string(),
-                                    collection: Some("modular".to_string()),
-                                })),
-                                source_name: Some("fuchsia.logger.Log".to_string()),
-                                target_name: Some("fuchsia.logger.Log".to_string()),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
-                }]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fuchsia.logger.Log".to_string()),
+                        source_path: Some("/svc/fuchsia.logger.Log".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("DebugProtocolRegistration", "source.child.collection"),
+                Error::offer_target_equals_source("OfferProtocol", "self"),
             ])),
         },
-        test_validate_environment_debug_log_invalid_identifiers => {
+        test_validate_offers_target_equals_source => {
             input = {
                 let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                    name: "^bad".to_string(),
-                                    collection: None,
-                                })),
-                                source_name: Some("foo/".to_string()),
-                                target_name: Some("/".to_string()),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("logger".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("legacy_logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("weak_legacy_logger".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("legacy_logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("strong_legacy_logger".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("web".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("web".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("logger".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/logger#meta/logger.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
                 }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("DebugProtocolRegistration", "source.child.name"),
-                Error::invalid_field("DebugProtocolRegistration", "source_name"),
-                Error::invalid_field("DebugProtocolRegistration", "target_name"),
+                Error::offer_target_equals_source("OfferService", "logger"),
+                // Only the DependencyType::Strong offer-target-equals-source
+                // should result in an error.
+                Error::offer_target_equals_source("OfferProtocol", "logger"),
+                Error::offer_target_equals_source("OfferDirectory", "logger"),
+                Error::offer_target_equals_source("OfferRunner", "logger"),
+                Error::offer_target_equals_source("OfferResolver", "logger"),
             ])),
         },
-        test_validate_environment_debug_log_invalid_child => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                                    name: "logger".to_string(),
-                                    collection: None,
-                                })),
-                                source_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                                target_name: Some("fuchsia.logger.LegacyLog".to_string()),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl.children = Some(vec![
+        test_validate_offers_storage_target_equals_source => {
+            input = fdecl::Component {
+                offers: Some(vec![
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef { })),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "logger".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    })
+                ]),
+                capabilities: Some(vec![
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("data".to_string()),
+                        backing_dir: Some("minfs".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                ]),
+                children: Some(vec![
                     fdecl::Child {
-                        name: Some("netstack".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
+                        name: Some("logger".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
                         startup: Some(fdecl::StartupMode::Lazy),
                         on_terminate: None,
                         environment: None,
                         ..fdecl::Child::EMPTY
                     },
-                ]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_child("DebugProtocolRegistration", "source", "logger"),
-
-            ])),
-        },
-        test_validate_environment_debug_source_capability => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.environments = Some(vec![
-                    fdecl::Environment {
-                        name: Some("a".to_string()),
-                        extends: Some(fdecl::EnvironmentExtends::None),
-                        stop_timeout_ms: Some(2),
-                        debug_capabilities:Some(vec![
-                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
-                                source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
-                                    name: "storage".to_string(),
-                                })),
-                                source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                                target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
-                                ..fdecl::DebugProtocolRegistration::EMPTY
-                            }),
-                    ]),
-                    ..fdecl::Environment::EMPTY
-                }]);
-                decl
+                ]),
+                ..new_component_decl()
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("DebugProtocolRegistration", "source"),
+                Error::dependency_cycle("{{child logger --(storage data via minfs)--> child logger}}".to_string()),
             ])),
         },
-
-        // children
-        test_validate_children_empty => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.children = Some(vec![fdecl::Child{
-                    name: None,
-                    url: None,
-                    startup: None,
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }]);
-                decl
+        test_validate_offers_storage_from_self_missing_source_name => {
+            input = fdecl::Component {
+                offers: Some(vec![
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: None,
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef { })),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "logger".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    })
+                ]),
+                children: Some(vec![
+                    fdecl::Child {
+                        name: Some("logger".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]),
+                ..new_component_decl()
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Child", "name"),
-                Error::missing_field("Child", "url"),
-                Error::missing_field("Child", "startup"),
-                // `on_terminate` is allowed to be None
+                Error::missing_field("OfferStorage", "source_name"),
             ])),
         },
-        test_validate_children_invalid_identifiers => {
+        test_validate_offers_invalid_child => {
             input = {
                 let mut decl = new_component_decl();
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("^bad".to_string()),
-                    url: Some("bad-scheme&://blah".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                ]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("mem".to_string()),
+                        backing_dir: Some("memfs".to_string()),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "logger".to_string(),
+                            collection: None,
+                        })),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl.collections = Some(vec![
+                    fdecl::Collection {
+                        name: Some("modular".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
+                        environment: None,
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticAndDynamic),
+                        allow_long_names: None,
+                        ..fdecl::Collection::EMPTY
+                    },
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("Child", "name"),
-                Error::invalid_url("Child", "url", "\"bad-scheme&://blah\": Invalid scheme"),
+                Error::invalid_child("Storage", "source", "logger"),
+                Error::invalid_child("OfferService", "source", "logger"),
+                Error::invalid_child("OfferProtocol", "source", "logger"),
+                Error::invalid_child("OfferDirectory", "source", "logger"),
             ])),
         },
-        test_validate_children_long_identifiers => {
+        test_validate_offers_invalid_source_capability => {
             input = {
-                let mut decl = new_component_decl();
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("a".repeat(1025)),
-                    url: Some(format!("fuchsia-pkg://{}", "a".repeat(4083))),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: Some("a".repeat(1025)),
-                    ..fdecl::Child::EMPTY
-                }]);
-                decl
+                fdecl::Component {
+                    offers: Some(vec![
+                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                            source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                name: "this-storage-doesnt-exist".to_string(),
+                            })),
+                            source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: None,
+                            }
+                            )),
+                            target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            ..fdecl::OfferProtocol::EMPTY
+                        }),
+                    ]),
+                    ..new_component_decl()
+                }
             },
             result = Err(ErrorList::new(vec![
-                Error::field_too_long_with_max("Child", "name", 100),
-                Error::field_too_long_with_max("Child", "url", 4096),
-                Error::field_too_long_with_max("Child", "environment", 100),
-                Error::invalid_environment("Child", "environment", "a".repeat(1025)),
+                Error::invalid_capability("OfferProtocol", "source", "this-storage-doesnt-exist"),
+                Error::invalid_child("OfferProtocol", "target", "netstack"),
             ])),
         },
-        test_validate_child_references_unknown_env => {
+        test_validate_offers_target => {
             input = {
                 let mut decl = new_component_decl();
-                decl.children = Some(vec![fdecl::Child{
-                    name: Some("foo".to_string()),
-                    url: Some("fuchsia-pkg://foo".to_string()),
-                    startup: Some(fdecl::StartupMode::Lazy),
-                    on_terminate: None,
-                    environment: Some("test_env".to_string()),
-                    ..fdecl::Child::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_environment("Child", "environment", "test_env"),
-            ])),
-        },
-
-        // collections
-        test_validate_collections_empty => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![fdecl::Collection{
-                    name: None,
-                    durability: None,
-                    environment: None,
-                    allowed_offers: None,
-                    allow_long_names: None,
-                    ..fdecl::Collection::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("Collection", "name"),
-                Error::missing_field("Collection", "durability"),
-            ])),
-        },
-        test_validate_collections_invalid_identifiers => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![fdecl::Collection{
-                    name: Some("^bad".to_string()),
-                    durability: Some(fdecl::Durability::Transient),
-                    environment: None,
-                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                    allow_long_names: None,
-                    ..fdecl::Collection::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_field("Collection", "name"),
-            ])),
-        },
-        test_validate_collections_long_identifiers => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![fdecl::Collection{
-                    name: Some("a".repeat(1025)),
-                    durability: Some(fdecl::Durability::Transient),
-                    environment: None,
-                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                    allow_long_names: None,
-                    ..fdecl::Collection::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::field_too_long("Collection", "name"),
-            ])),
-        },
-        test_validate_collection_references_unknown_env => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.collections = Some(vec![fdecl::Collection {
-                    name: Some("foo".to_string()),
-                    durability: Some(fdecl::Durability::Transient),
-                    environment: Some("test_env".to_string()),
-                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
-                    allow_long_names: None,
-                    ..fdecl::Collection::EMPTY
-                }]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_environment("Collection", "environment", "test_env"),
-            ])),
-        },
-
-        // capabilities
-        test_validate_capabilities_empty => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: None,
-                        source_path: None,
-                        ..fdecl::Service::EMPTY
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["instance_0".to_string()]),
+                        ..fdecl::OfferService::EMPTY
                     }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: None,
-                        source_path: None,
-                        ..fdecl::Protocol::EMPTY
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["instance_1".to_string()]),
+                        ..fdecl::OfferService::EMPTY
                     }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: None,
-                        source_path: None,
-                        rights: None,
-                        ..fdecl::Directory::EMPTY
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
                     }),
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: None,
-                        source: None,
-                        backing_dir: None,
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
                         subdir: None,
-                        storage_id: None,
-                        ..fdecl::Storage::EMPTY
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
                     }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: None,
-                        source_path: None,
-                        ..fdecl::Runner::EMPTY
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferDirectory::EMPTY
                     }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: None,
-                        source_path: None,
-                        ..fdecl::Resolver::EMPTY
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("duplicated".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("duplicated".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string() }
+                        )),
+                        target_name: Some("duplicated".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("stopped".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("started".to_string()),
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        source_name: Some("started_on_x".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some("started".to_string()),
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
+                decl.children = Some(vec![
+                    fdecl::Child{
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Eager),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl.collections = Some(vec![
+                    fdecl::Collection{
+                        name: Some("modular".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
+                        environment: None,
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                        allow_long_names: None,
+                        ..fdecl::Collection::EMPTY
+                    },
+                ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Service", "name"),
-                Error::missing_field("Service", "source_path"),
-                Error::missing_field("Protocol", "name"),
-                Error::missing_field("Protocol", "source_path"),
-                Error::missing_field("Directory", "name"),
-                Error::missing_field("Directory", "source_path"),
-                Error::missing_field("Directory", "rights"),
-                Error::missing_field("Storage", "source"),
-                Error::missing_field("Storage", "name"),
-                Error::missing_field("Storage", "storage_id"),
-                Error::missing_field("Storage", "backing_dir"),
-                Error::missing_field("Runner", "name"),
-                Error::missing_field("Runner", "source_path"),
-                Error::missing_field("Resolver", "name"),
-                Error::missing_field("Resolver", "source_path"),
+                // Duplicate services are allowed.
+                Error::duplicate_field("OfferProtocol", "target_name", "fuchsia.logger.LegacyLog"),
+                Error::duplicate_field("OfferDirectory", "target_name", "assets"),
+                Error::duplicate_field("OfferRunner", "target_name", "duplicated"),
+                Error::duplicate_field("OfferResolver", "target_name", "duplicated"),
+                Error::duplicate_field("OfferEvent", "target_name", "started"),
             ])),
         },
-        test_validate_capabilities_invalid_identifiers => {
+        test_validate_offers_target_invalid => {
             input = {
                 let mut decl = new_component_decl();
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("^bad".to_string()),
-                        source_path: Some("&bad".to_string()),
-                        ..fdecl::Service::EMPTY
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        ..fdecl::OfferService::EMPTY
                     }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("^bad".to_string()),
-                        source_path: Some("&bad".to_string()),
-                        ..fdecl::Protocol::EMPTY
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("logger".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        ..fdecl::OfferService::EMPTY
                     }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("^bad".to_string()),
-                        source_path: Some("&bad".to_string()),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("legacy_logger".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("legacy_logger".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef {
+                            name: "netstack".to_string(),
+                            collection: None,
+                        }
+                        )),
+                        target_name: Some("data".to_string()),
                         rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
+                        subdir: None,
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
                     }),
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("^bad".to_string()),
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
-                            name: "/bad".to_string()
-                        })),
-                        backing_dir: Some("&bad".to_string()),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("assets".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("data".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
                         subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+                        dependency_type: Some(fdecl::DependencyType::Weak),
+                        ..fdecl::OfferDirectory::EMPTY
                     }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: Some("^bad".to_string()),
-                        source_path: Some("&bad".to_string()),
-                        ..fdecl::Runner::EMPTY
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
                     }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: Some("^bad".to_string()),
-                        source_path: Some("&bad".to_string()),
-                        ..fdecl::Resolver::EMPTY
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source_name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        target: Some(fdecl::Ref::Collection(
+                            fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("data".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
                     }),
-                ]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_field("Service", "name"),
-                Error::invalid_field("Service", "source_path"),
-                Error::invalid_field("Protocol", "name"),
-                Error::invalid_field("Protocol", "source_path"),
-                Error::invalid_field("Directory", "name"),
-                Error::invalid_field("Directory", "source_path"),
-                Error::invalid_field("Storage", "source"),
-                Error::invalid_field("Storage", "name"),
-                Error::invalid_field("Storage", "backing_dir"),
-                Error::invalid_field("Runner", "name"),
-                Error::invalid_field("Runner", "source_path"),
-                Error::invalid_field("Resolver", "name"),
-                Error::invalid_field("Resolver", "source_path"),
-            ])),
-        },
-        test_validate_capabilities_invalid_child => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("foo".to_string()),
-                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
-                            name: "invalid".to_string(),
-                        })),
-                        backing_dir: Some("foo".to_string()),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("elf".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                        source_name: Some("pkg".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        target_name: Some("pkg".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("started".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "netstack".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source_name: Some("started".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("started".to_string()),
+                        target: Some(fdecl::Ref::Collection(
+                        fdecl::CollectionRef { name: "modular".to_string(), }
+                        )),
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_field("Storage", "source"),
+                Error::invalid_child("OfferService", "target", "netstack"),
+                Error::invalid_collection("OfferService", "target", "modular"),
+                Error::invalid_child("OfferProtocol", "target", "netstack"),
+                Error::invalid_collection("OfferProtocol", "target", "modular"),
+                Error::invalid_child("OfferDirectory", "target", "netstack"),
+                Error::invalid_collection("OfferDirectory", "target", "modular"),
+                Error::invalid_child("OfferStorage", "target", "netstack"),
+                Error::invalid_collection("OfferStorage", "target", "modular"),
+                Error::invalid_child("OfferRunner", "target", "netstack"),
+                Error::invalid_collection("OfferRunner", "target", "modular"),
+                Error::invalid_child("OfferResolver", "target", "netstack"),
+                Error::invalid_collection("OfferResolver", "target", "modular"),
+                Error::invalid_child("OfferEvent", "target", "netstack"),
+                Error::invalid_collection("OfferEvent", "target", "modular"),
             ])),
         },
-        test_validate_capabilities_long_identifiers => {
+        test_validate_offers_invalid_source_collection => {
             input = {
                 let mut decl = new_component_decl();
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("a".repeat(101)),
-                        source_path: Some(format!("/{}", "c".repeat(1024))),
-                        ..fdecl::Service::EMPTY
-                    }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("a".repeat(101)),
-                        source_path: Some(format!("/{}", "c".repeat(1024))),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("a".repeat(101)),
-                        source_path: Some(format!("/{}", "c".repeat(1024))),
-                        rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
-                    }),
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("a".repeat(101)),
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "b".repeat(101),
-                            collection: None,
-                        })),
-                        backing_dir: Some(format!("{}", "c".repeat(101))),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
-                    }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: Some("a".repeat(101)),
-                        source_path: Some(format!("/{}", "c".repeat(1024))),
-                        ..fdecl::Runner::EMPTY
-                    }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: Some("a".repeat(101)),
-                        source_path: Some(format!("/{}", "b".repeat(1024))),
-                        ..fdecl::Resolver::EMPTY
-                    }),
+                decl.collections = Some(vec![
+                    fdecl::Collection {
+                        name: Some("col".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                        allow_long_names: None,
+                        ..fdecl::Collection::EMPTY
+                    }
                 ]);
-                decl
-            },
-            result = Err(ErrorList::new(vec![
-                Error::field_too_long_with_max("Service", "name", 100),
-                Error::field_too_long_with_max("Service", "source_path", 1024),
-                Error::field_too_long_with_max("Protocol", "name", 100),
-                Error::field_too_long_with_max("Protocol", "source_path", 1024),
-                Error::field_too_long_with_max("Directory", "name", 100),
-                Error::field_too_long_with_max("Directory", "source_path", 1024),
-                Error::field_too_long_with_max("Storage", "source.child.name", 100),
-                Error::field_too_long_with_max("Storage", "name", 100),
-                Error::field_too_long_with_max("Storage", "backing_dir", 100),
-                Error::field_too_long_with_max("Runner", "name", 100),
-                Error::field_too_long_with_max("Runner", "source_path", 1024),
-                Error::field_too_long_with_max("Resolver", "name", 100),
-                Error::field_too_long_with_max("Resolver", "source_path", 1024),
-            ])),
-        },
-        test_validate_capabilities_duplicate_name => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.capabilities = Some(vec![
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("service".to_string()),
-                        source_path: Some("/service".to_string()),
-                        ..fdecl::Service::EMPTY
-                    }),
-                    fdecl::Capability::Service(fdecl::Service {
-                        name: Some("service".to_string()),
-                        source_path: Some("/service".to_string()),
-                        ..fdecl::Service::EMPTY
-                    }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("protocol".to_string()),
-                        source_path: Some("/protocol".to_string()),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("protocol".to_string()),
-                        source_path: Some("/protocol".to_string()),
-                        ..fdecl::Protocol::EMPTY
-                    }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("directory".to_string()),
-                        source_path: Some("/directory".to_string()),
-                        rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        ..fdecl::Child::EMPTY
+                    }
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("a".to_string()),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
                     }),
-                    fdecl::Capability::Directory(fdecl::Directory {
-                        name: Some("directory".to_string()),
-                        source_path: Some("/directory".to_string()),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("b".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("b".to_string()),
                         rights: Some(fio::Operations::CONNECT),
-                        ..fdecl::Directory::EMPTY
-                    }),
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("storage".to_string()),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        backing_dir: Some("directory".to_string()),
-                        subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
-                    }),
-                    fdecl::Capability::Storage(fdecl::Storage {
-                        name: Some("storage".to_string()),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
-                        backing_dir: Some("directory".to_string()),
                         subdir: None,
-                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
-                        ..fdecl::Storage::EMPTY
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferDirectory::EMPTY
                     }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: Some("runner".to_string()),
-                        source_path: Some("/runner".to_string()),
-                        ..fdecl::Runner::EMPTY
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("c".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("c".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
                     }),
-                    fdecl::Capability::Runner(fdecl::Runner {
-                        name: Some("runner".to_string()),
-                        source_path: Some("/runner".to_string()),
-                        ..fdecl::Runner::EMPTY
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("d".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("d".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
                     }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: Some("resolver".to_string()),
-                        source_path: Some("/resolver".to_string()),
-                        ..fdecl::Resolver::EMPTY
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("e".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("e".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
                     }),
-                    fdecl::Capability::Resolver(fdecl::Resolver {
-                        name: Some("resolver".to_string()),
-                        source_path: Some("/resolver".to_string()),
-                        ..fdecl::Resolver::EMPTY
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("f".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("f".to_string()),
+                        filter: None,
+                        ..fdecl::OfferEvent::EMPTY
                     }),
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::duplicate_field("Service", "name", "service"),
-                Error::duplicate_field("Protocol", "name", "protocol"),
-                Error::duplicate_field("Directory", "name", "directory"),
-                Error::duplicate_field("Storage", "name", "storage"),
-                Error::duplicate_field("Runner", "name", "runner"),
-                Error::duplicate_field("Resolver", "name", "resolver"),
+                Error::invalid_field("OfferProtocol", "source"),
+                Error::invalid_field("OfferDirectory", "source"),
+                Error::invalid_field("OfferStorage", "source"),
+                Error::invalid_field("OfferRunner", "source"),
+                Error::invalid_field("OfferResolver", "source"),
+                Error::invalid_field("OfferEvent", "source"),
             ])),
         },
-
-        test_validate_invalid_service_aggregation_missing_filter => {
+        test_validate_offers_source_collection => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: Some(vec!["default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log".to_string()),
-                        source_instance_filter: None,
-                        ..fdecl::OfferService::EMPTY
-                    }),
+                decl.collections = Some(vec![
+                    fdecl::Collection {
+                        name: Some("col".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                        allow_long_names: None,
+                        ..fdecl::Collection::EMPTY
+                    }
                 ]);
                 decl.children = Some(vec![
                     fdecl::Child {
-                        name: Some("child_a".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_b".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_c".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        name: Some("child".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
                         startup: Some(fdecl::StartupMode::Lazy),
                         on_terminate: None,
-                        environment: None,
                         ..fdecl::Child::EMPTY
-                    },
+                    }
+                ]);
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef { name: "col".to_string() })),
+                        source_name: Some("a".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                        target_name: Some("a".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    })
                 ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_aggregate_offer("source_instance_filter must be set for all aggregate service offers"),
-            ])),
+            result = Ok(()),
         },
-
-        test_validate_invalid_service_aggregation_conflicting_filter => {
+        test_validate_offers_event_from_realm => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log1".to_string()),
-                        source_instance_filter: Some(vec!["default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
+                decl.offers = Some(
+                    vec![
+                        fdecl::Ref::Self_(fdecl::SelfRef {}),
+                        fdecl::Ref::Child(fdecl::ChildRef {name: "netstack".to_string(), collection: None }),
+                        fdecl::Ref::Collection(fdecl::CollectionRef {name: "modular".to_string() }),
+                    ]
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, source)| {
+                        fdecl::Offer::Event(fdecl::OfferEvent {
+                            source: Some(source),
+                            source_name: Some("started".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                name: "netstack".to_string(),
                                 collection: None,
-                            }
-                        )),
-                        target_name: Some("fuchsia.logger.Log1".to_string()),
-                        source_instance_filter: Some(vec!["default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                ]);
+                            })),
+                            target_name: Some(format!("started_{}", i)),
+
+                            filter: Some(fdata::Dictionary { entries: None, ..fdata::Dictionary::EMPTY }),
+                            ..fdecl::OfferEvent::EMPTY
+                        })
+                    })
+                    .collect());
                 decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("child_a".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_b".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
+                    fdecl::Child{
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Eager),
                         on_terminate: None,
                         environment: None,
                         ..fdecl::Child::EMPTY
                     },
-                    fdecl::Child {
-                        name: Some("child_c".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
+                ]);
+                decl.collections = Some(vec![
+                    fdecl::Collection {
+                        name: Some("modular".to_string()),
+                        durability: Some(fdecl::Durability::Transient),
                         environment: None,
-                        ..fdecl::Child::EMPTY
+                        allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                        allow_long_names: None,
+                        ..fdecl::Collection::EMPTY
                     },
                 ]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_aggregate_offer("Conflicting source_instance_filter in aggregate service offer, instance_name 'default' seen in filter lists multiple times"),
+                Error::invalid_field("OfferEvent", "source"),
+                Error::invalid_field("OfferEvent", "source"),
+                Error::invalid_field("OfferEvent", "source"),
             ])),
         },
-
-        test_validate_invalid_service_aggregation_conflicting_source_name => {
+        test_validate_offers_long_dependency_cycle => {
             input = {
                 let mut decl = new_component_decl();
-                decl.offers = Some(vec![
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.Log".to_string()),
-                        target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
+                let dependencies = vec![
+                    ("d", "b"),
+                    ("a", "b"),
+                    ("b", "c"),
+                    ("b", "d"),
+                    ("c", "a"),
+                ];
+                let offers = dependencies.into_iter().map(|(from,to)|
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        source: Some(fdecl::Ref::Child(
+                        fdecl::ChildRef { name: from.to_string(), collection: None },
                         )),
-                        target_name: Some("fuchsia.logger.Log2".to_string()),
-                        source_instance_filter: Some(vec!["default2".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
-                        source_name: Some("fuchsia.logger.LogAlt".to_string()),
+                        source_name: Some(format!("thing_{}", from)),
                         target: Some(fdecl::Ref::Child(
-                            fdecl::ChildRef {
-                                name: "child_c".to_string(),
-                                collection: None,
-                            }
+                        fdecl::ChildRef { name: to.to_string(), collection: None },
                         )),
-                        target_name: Some("fuchsia.logger.Log2".to_string()),
-                        source_instance_filter: Some(vec!["default".to_string()]),
-                        ..fdecl::OfferService::EMPTY
-                    })
-                ]);
-                decl.children = Some(vec![
-                    fdecl::Child {
-                        name: Some("child_a".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
-                    fdecl::Child {
-                        name: Some("child_b".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
-                        startup: Some(fdecl::StartupMode::Lazy),
-                        on_terminate: None,
-                        environment: None,
-                        ..fdecl::Child::EMPTY
-                    },
+                        target_name: Some(format!("thing_{}", from)),
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        ..fdecl::OfferProtocol::EMPTY
+                    })).collect();
+                let children = ["a", "b", "c", "d"].iter().map(|name| {
                     fdecl::Child {
-                        name: Some("child_c".to_string()),
-                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        name: Some(name.to_string()),
+                        url: Some(format!("fuchsia-pkg://fuchsia.com/pkg#meta/{}.cm", name)),
                         startup: Some(fdecl::StartupMode::Lazy),
                         on_terminate: None,
                         environment: None,
                         ..fdecl::Child::EMPTY
-                    },
-                ]);
+                    }
+                }).collect();
+                decl.offers = Some(offers);
+                decl.children = Some(children);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::invalid_aggregate_offer("All aggregate service offers must have the same source_name, saw fuchsia.logger.Log, fuchsia.logger.LogAlt. Use renamed_instances to rename instance names to avoid conflict."),
+                Error::dependency_cycle("{{child a --(thing_a)--> child b --(thing_b)--> child c --(thing_c)--> child a}, {child b --(thing_b)--> child d --(thing_d)--> child b}}".to_string()),
             ])),
         },
-
-        test_validate_resolvers_missing_from_offer => {
+        test_validate_offers_not_required_invalid_source_service => {
             input = {
-                let mut decl = new_component_decl();
-                decl.offers = Some(vec![fdecl::Offer::Resolver(fdecl::OfferResolver {
-                    source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                    source_name: Some("a".to_string()),
-                    target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
-                    target_name: Some("a".to_string()),
-                    ..fdecl::OfferResolver::EMPTY
-                })]);
-                decl.children = Some(vec![fdecl::Child {
-                    name: Some("child".to_string()),
-                    url: Some("test:///child".to_string()),
-                    startup: Some(fdecl::StartupMode::Eager),
-                    on_terminate: None,
-                    environment: None,
-                    ..fdecl::Child::EMPTY
-                }]);
+                let mut decl = generate_offer_invalid_source_and_availability_decl(
+                    |source, availability, target_name|
+                        fdecl::Offer::Service(fdecl::OfferService {
+                            source: Some(source),
+                            source_name: Some("fuchsia.examples.Echo".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                name: "sink".to_string(),
+                                collection: None,
+                            })),
+                            target_name: Some(target_name.into()),
+                            availability: Some(availability),
+                            ..fdecl::OfferService::EMPTY
+                        })
+                );
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("fuchsia.examples.Echo".to_string()),
+                        source_path: Some("/svc/fuchsia.examples.Echo".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_capability("OfferResolver", "source", "a"),
-            ])),
+            result = {
+                Err(ErrorList::new(vec![
+                    Error::availability_must_be_optional(
+                        "OfferService",
+                        "availability",
+                        Some(&"fuchsia.examples.Echo".to_string()),
+                    ),
+                    Error::availability_must_be_optional(
+                        "OfferService",
+                        "availability",
+                        Some(&"fuchsia.examples.Echo".to_string()),
+                    ),
+                ]))
+            },
         },
-        test_validate_resolvers_missing_from_expose => {
+        test_validate_offers_not_required_invalid_source_protocol => {
             input = {
-                let mut decl = new_component_decl();
-                decl.exposes = Some(vec![fdecl::Expose::Resolver(fdecl::ExposeResolver {
-                    source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
-                    source_name: Some("a".to_string()),
-                    target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
-                    target_name: Some("a".to_string()),
-                    ..fdecl::ExposeResolver::EMPTY
-                })]);
+                let mut decl = generate_offer_invalid_source_and_availability_decl(
+                    |source, availability, target_name|
+                        fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                            source: Some(source),
+                            source_name: Some("fuchsia.examples.Echo".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                name: "sink".to_string(),
+                                collection: None,
+                            })),
+                            target_name: Some(target_name.into()),
+                            dependency_type: Some(fdecl::DependencyType::Strong),
+                            availability: Some(availability),
+                            ..fdecl::OfferProtocol::EMPTY
+                        })
+                );
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("fuchsia.examples.Echo".to_string()),
+                        source_path: Some("/svc/fuchsia.examples.Echo".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::invalid_capability("ExposeResolver", "source", "a"),
-            ])),
-        },
-
-        test_validate_config_missing_config => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: None,
-                    checksum: None,
-                    value_source: None,
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+            result = {
+                Err(ErrorList::new(vec![
+                    Error::availability_must_be_optional(
+                        "OfferProtocol",
+                        "availability",
+                        Some(&"fuchsia.examples.Echo".to_string()),
+                    ),
+                    Error::availability_must_be_optional(
+                        "OfferProtocol",
+                        "availability",
+                        Some(&"fuchsia.examples.Echo".to_string()),
+                    ),
+                ]))
+            },
+        },
+        test_validate_offers_not_required_invalid_source_directory => {
+            input = {
+                let mut decl = generate_offer_invalid_source_and_availability_decl(
+                    |source, availability, target_name|
+                        fdecl::Offer::Directory(fdecl::OfferDirectory {
+                            source: Some(source),
+                            source_name: Some("assets".to_string()),
+                            target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                name: "sink".to_string(),
+                                collection: None,
+                            })),
+                            target_name: Some(target_name.into()),
+                            rights: Some(fio::Operations::CONNECT),
+                            subdir: None,
+                            dependency_type: Some(fdecl::DependencyType::Weak),
+                            availability: Some(availability),
+                            ..fdecl::OfferDirectory::EMPTY
+                        })
+                );
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("assets".to_string()),
+                        source_path: Some("/assets".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigSchema", "fields"),
-                Error::missing_field("ConfigSchema", "checksum"),
-                Error::missing_field("ConfigSchema", "value_source"),
-            ])),
+            result = {
+                Err(ErrorList::new(vec![
+                    Error::availability_must_be_optional(
+                        "OfferDirectory",
+                        "availability",
+                        Some(&"assets".to_string()),
+                    ),
+                    Error::availability_must_be_optional(
+                        "OfferDirectory",
+                        "availability",
+                        Some(&"assets".to_string()),
+                    ),
+                ]))
+            },
         },
-
-        test_validate_config_missing_config_field => {
+        test_validate_offers_not_required_invalid_source_storage => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: None,
-                            type_: None,
-                            ..fdecl::ConfigField::EMPTY
-                        }
-                    ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("sink".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/sink#meta/sink.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        backing_dir: Some("minfs".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                ]);
+                let new_offer = |source: fdecl::Ref, availability: fdecl::Availability,
+                                        target_name: &str|
+                {
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(source),
+                        source_name: Some("data".to_string()),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "sink".to_string(),
+                            collection: None,
+                        })),
+                        target_name: Some(target_name.into()),
+                        availability: Some(availability),
+                        ..fdecl::OfferStorage::EMPTY
+                    })
+                };
+                decl.offers = Some(vec![
+                    // These offers are fine, offers with a source of parent or void can be
+                    // optional.
+                    new_offer(
+                        fdecl::Ref::Parent(fdecl::ParentRef {}),
+                        fdecl::Availability::Required,
+                        "data0",
+                    ),
+                    new_offer(
+                        fdecl::Ref::Parent(fdecl::ParentRef {}),
+                        fdecl::Availability::Optional,
+                        "data1",
+                    ),
+                    new_offer(
+                        fdecl::Ref::Parent(fdecl::ParentRef {}),
+                        fdecl::Availability::SameAsTarget,
+                        "data2",
+                    ),
+                    new_offer(
+                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                        fdecl::Availability::Optional,
+                        "data3",
+                    ),
+                    // These offers are not fine, offers with a source other than parent or void
+                    // must be required.
+                    new_offer(
+                        fdecl::Ref::Self_(fdecl::SelfRef {}),
+                        fdecl::Availability::Optional,
+                        "data4",
+                    ),
+                    new_offer(
+                        fdecl::Ref::Self_(fdecl::SelfRef {}),
+                        fdecl::Availability::SameAsTarget,
+                        "data5",
+                    ),
+                    // These offers are also not fine, offers with a source of void must be optional
+                    new_offer(
+                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                        fdecl::Availability::Required,
+                        "data6",
+                    ),
+                    new_offer(
+                        fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                        fdecl::Availability::SameAsTarget,
+                        "data7",
+                    ),
+                ]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigField", "key"),
-                Error::missing_field("ConfigField", "value_type"),
-            ])),
+            result = {
+                Err(ErrorList::new(vec![
+                    Error::availability_must_be_optional(
+                        "OfferStorage",
+                        "availability",
+                        Some(&"data".to_string()),
+                    ),
+                    Error::availability_must_be_optional(
+                        "OfferStorage",
+                        "availability",
+                        Some(&"data".to_string()),
+                    ),
+                ]))
+            },
         },
 
-        test_validate_config_bool => {
+        test_validate_offers_valid_service_aggregation => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Bool,
-                                parameters: Some(vec![]),
-                                constraints: vec![]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
-                    ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["a_different_default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    })
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
                 decl
             },
             result = Ok(()),
         },
 
-        test_validate_config_bool_extra_constraint => {
+        // environments
+        test_validate_environment_empty => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Bool,
-                                parameters: Some(vec![]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
-                    ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: None,
+                    extends: None,
+                    runners: None,
+                    resolvers: None,
+                    stop_timeout_ms: None,
+                    debug_capabilities: None,
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("ConfigType", "constraints")
+                Error::missing_field("Environment", "name"),
+                Error::missing_field("Environment", "extends"),
             ])),
         },
 
-        test_validate_config_bool_missing_parameters => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Bool,
-                                parameters: None,
-                                constraints: vec![]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
-                    ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+        test_validate_environment_no_stop_timeout => {
+            input = {  let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: None,
+                    resolvers: None,
+                    stop_timeout_ms: None,
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
-            result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigType", "parameters")
-            ])),
+            result = Err(ErrorList::new(vec![Error::missing_field("Environment", "stop_timeout_ms")])),
         },
 
-        test_validate_config_string => {
+        test_validate_environment_extends_stop_timeout => {
+            input = {  let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::Realm),
+                    runners: None,
+                    resolvers: None,
+                    stop_timeout_ms: None,
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_environment_long_identifiers => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::String,
-                                parameters: Some(vec![]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".repeat(101)),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("a".repeat(101)),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            target_name: Some("a".repeat(101)),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("a".repeat(101)),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("a".repeat(101)),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long("Environment", "name"),
+                Error::field_too_long("RunnerRegistration", "source_name"),
+                Error::field_too_long("RunnerRegistration", "target_name"),
+                Error::field_too_long("ResolverRegistration", "resolver"),
+                Error::field_too_long("ResolverRegistration", "scheme"),
+            ])),
         },
-
-        test_validate_config_string_missing_parameter => {
+        test_validate_environment_empty_runner_resolver_fields => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::String,
-                                parameters: None,
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: None,
+                            source: None,
+                            target_name: None,
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: None,
+                            source: None,
+                            scheme: None,
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigType", "parameters")
+                Error::missing_field("RunnerRegistration", "source_name"),
+                Error::missing_field("RunnerRegistration", "source"),
+                Error::missing_field("RunnerRegistration", "target_name"),
+                Error::missing_field("ResolverRegistration", "resolver"),
+                Error::missing_field("ResolverRegistration", "source"),
+                Error::missing_field("ResolverRegistration", "scheme"),
             ])),
         },
-
-        test_validate_config_string_missing_constraint => {
+        test_validate_environment_invalid_fields => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::String,
-                                parameters: Some(vec![]),
-                                constraints: vec![]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("^a".to_string()),
+                            source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                            target_name: Some("%a".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("^a".to_string()),
+                            source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef{})),
+                            scheme: Some("9scheme".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigType", "constraints")
+                Error::invalid_field("RunnerRegistration", "source_name"),
+                Error::invalid_field("RunnerRegistration", "source"),
+                Error::invalid_field("RunnerRegistration", "target_name"),
+                Error::invalid_field("ResolverRegistration", "resolver"),
+                Error::invalid_field("ResolverRegistration", "source"),
+                Error::invalid_field("ResolverRegistration", "scheme"),
             ])),
         },
-
-        test_validate_config_string_extra_constraint => {
+        test_validate_environment_missing_runner => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::String,
-                                parameters: Some(vec![]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10), fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("dart".to_string()),
+                            source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                            target_name: Some("dart".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: None,
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("ConfigType", "constraints")
+                Error::invalid_runner("RunnerRegistration", "source_name", "dart"),
             ])),
         },
-
-        test_validate_config_vector_bool => {
+        test_validate_environment_duplicate_registrations => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Vector,
-                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                    layout: fdecl::ConfigTypeLayout::Bool,
-                                    parameters: Some(vec![]),
-                                    constraints: vec![],
-                                })]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("dart".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            target_name: Some("dart".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
+                        fdecl::RunnerRegistration {
+                            source_name: Some("other-dart".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            target_name: Some("dart".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
-                decl
-            },
-            result = Ok(()),
-        },
-
-        test_validate_config_vector_extra_parameter => {
-            input = {
-                let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Vector,
-                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                    layout: fdecl::ConfigTypeLayout::Bool,
-                                    parameters: Some(vec![]),
-                                    constraints: vec![],
-                                }), fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                    layout: fdecl::ConfigTypeLayout::Uint8,
-                                    parameters: Some(vec![]),
-                                    constraints: vec![],
-                                })]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                        fdecl::ResolverRegistration {
+                            resolver: Some("base_resolver".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::extraneous_field("ConfigType", "parameters")
+                Error::duplicate_field("RunnerRegistration", "target_name", "dart"),
+                Error::duplicate_field("ResolverRegistration", "scheme", "fuchsia-pkg"),
             ])),
         },
-
-        test_validate_config_vector_missing_parameter => {
+        test_validate_environment_from_missing_child => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Vector,
-                                parameters: Some(vec![]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("elf".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "missing".to_string(),
+                                collection: None,
+                            })),
+                            target_name: Some("elf".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "missing".to_string(),
+                                collection: None,
+                            })),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("ConfigType", "parameters")
+                Error::invalid_child("RunnerRegistration", "source", "missing"),
+                Error::invalid_child("ResolverRegistration", "source", "missing"),
             ])),
         },
-
-        test_validate_config_vector_string => {
+        test_validate_environment_from_child_in_collection => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Vector,
-                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                    layout: fdecl::ConfigTypeLayout::String,
-                                    parameters: Some(vec![]),
-                                    constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                                })]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("a".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("elf".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "child".to_string(),
+                                collection: Some("coll".to_string()),
+                            })),
+                            target_name: Some("elf".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "child".to_string(),
+                                collection: Some("coll".to_string()),
+                            })),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    url: Some("fuchsia-pkg://child".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("coll".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    ..fdecl::Collection::EMPTY
+                }]);
                 decl
             },
-            result = Ok(()),
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_field("RunnerRegistration", "source.child.collection"),
+                Error::extraneous_field("ResolverRegistration", "source.child.collection"),
+            ])),
         },
-
-        test_validate_config_vector_vector => {
+        test_validate_environment_runner_child_cycle => {
             input = {
                 let mut decl = new_component_decl();
-                decl.config = Some(fdecl::ConfigSchema{
-                    fields: Some(vec![
-                        fdecl::ConfigField {
-                            key: Some("test".to_string()),
-                            type_: Some(fdecl::ConfigType {
-                                layout: fdecl::ConfigTypeLayout::Vector,
-                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                    layout: fdecl::ConfigTypeLayout::Vector,
-                                    parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
-                                        layout: fdecl::ConfigTypeLayout::String,
-                                        parameters: Some(vec![]),
-                                        constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                                    })]),
-                                    constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                                })]),
-                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
-                            }),
-                            ..fdecl::ConfigField::EMPTY
-                        }
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: Some(vec![
+                        fdecl::RunnerRegistration {
+                            source_name: Some("elf".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "child".to_string(),
+                                collection: None,
+                            })),
+                            target_name: Some("elf".to_string()),
+                            ..fdecl::RunnerRegistration::EMPTY
+                        },
                     ]),
-                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
-                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
-                    ..fdecl::ConfigSchema::EMPTY
-                });
+                    resolvers: None,
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    url: Some("fuchsia-pkg://child".to_string()),
+                    environment: Some("env".to_string()),
+                    ..fdecl::Child::EMPTY
+                }]);
                 decl
             },
             result = Err(ErrorList::new(vec![
-                Error::nested_vector()
+                Error::dependency_cycle(
+                    "{{child child -> environment env -> child child}}".to_string()
+                ),
             ])),
         },
-    }
-
-    test_validate_capabilities! {
-        test_validate_capabilities_individually_ok => {
-            input = vec![
-                fdecl::Capability::Protocol(fdecl::Protocol {
-                    name: Some("foo_svc".into()),
-                    source_path: Some("/svc/foo".into()),
-                    ..fdecl::Protocol::EMPTY
-                }),
-                fdecl::Capability::Directory(fdecl::Directory {
-                    name: Some("foo_dir".into()),
-                    source_path: Some("/foo".into()),
-                    rights: Some(fio::Operations::CONNECT),
-                    ..fdecl::Directory::EMPTY
-                }),
-            ],
-            as_builtin = false,
-            result = Ok(()),
-        },
-        test_validate_capabilities_individually_err => {
-            input = vec![
-                fdecl::Capability::Protocol(fdecl::Protocol {
-                    name: None,
-                    source_path: None,
-                    ..fdecl::Protocol::EMPTY
-                }),
-                fdecl::Capability::Directory(fdecl::Directory {
-                    name: None,
-                    source_path: None,
-                    rights: None,
-                    ..fdecl::Directory::EMPTY
-                }),
-                fdecl::Capability::Event(fdecl::Event {
-                    name: None,
-                    ..fdecl::Event::EMPTY
-                }),
-            ],
-            as_builtin = false,
+        test_validate_environment_resolver_child_cycle => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: None,
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "child".to_string(),
+                                collection: None,
+                            })),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    url: Some("fuchsia-pkg://child".to_string()),
+                    environment: Some("env".to_string()),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Protocol", "name"),
-                Error::missing_field("Protocol", "source_path"),
-                Error::missing_field("Directory", "name"),
-                Error::missing_field("Directory", "source_path"),
-                Error::missing_field("Directory", "rights"),
-                Error::invalid_capability_type("Component", "capability", "event")
+                Error::dependency_cycle(
+                    "{{child child -> environment env -> child child}}".to_string()
+                ),
             ])),
         },
-        test_validate_builtin_capabilities_individually_ok => {
-            input = vec![
-                fdecl::Capability::Protocol(fdecl::Protocol {
-                    name: Some("foo_protocol".into()),
-                    source_path: None,
-                    ..fdecl::Protocol::EMPTY
-                }),
-                fdecl::Capability::Directory(fdecl::Directory {
-                    name: Some("foo_dir".into()),
-                    source_path: None,
-                    rights: Some(fio::Operations::CONNECT),
-                    ..fdecl::Directory::EMPTY
-                }),
-                fdecl::Capability::Service(fdecl::Service {
-                    name: Some("foo_svc".into()),
-                    source_path: None,
-                    ..fdecl::Service::EMPTY
-                }),
-                fdecl::Capability::Runner(fdecl::Runner {
-                    name: Some("foo_runner".into()),
-                    source_path: None,
-                    ..fdecl::Runner::EMPTY
-                }),
-                fdecl::Capability::Resolver(fdecl::Resolver {
-                    name: Some("foo_resolver".into()),
-                    source_path: None,
-                    ..fdecl::Resolver::EMPTY
-                }),
-                fdecl::Capability::Event(fdecl::Event {
-                    name: Some("foo_event".into()),
-                    ..fdecl::Event::EMPTY
-                }),
-            ],
-            as_builtin = true,
-            result = Ok(()),
+        test_validate_environment_resolver_multiple_children_cycle => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::None),
+                    runners: None,
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Child(fdecl::ChildRef{
+                                name: "a".to_string(),
+                                collection: None,
+                            })),
+                            scheme: Some("fuchsia-pkg".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    stop_timeout_ms: Some(1234),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("a".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        url: Some("fuchsia-pkg://child-a".to_string()),
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("b".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        url: Some("fuchsia-pkg://child-b".to_string()),
+                        environment: Some("env".to_string()),
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl.offers = Some(vec![fdecl::Offer::Service(fdecl::OfferService {
+                    source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                        name: "b".to_string(),
+                        collection: None,
+                    })),
+                    source_name: Some("thing".to_string()),
+                    target: Some(fdecl::Ref::Child(
+                    fdecl::ChildRef {
+                        name: "a".to_string(),
+                        collection: None,
+                    }
+                    )),
+                    target_name: Some("thing".to_string()),
+                    ..fdecl::OfferService::EMPTY
+                })]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::dependency_cycle(
+                    "{{child a -> environment env -> child b --(thing)--> child a}}".to_string()
+                ),
+            ])),
         },
-        test_validate_builtin_capabilities_individually_err => {
-            input = vec![
-                fdecl::Capability::Protocol(fdecl::Protocol {
-                    name: None,
-                    source_path: Some("/svc/foo".into()),
-                    ..fdecl::Protocol::EMPTY
-                }),
-                fdecl::Capability::Directory(fdecl::Directory {
-                    name: None,
-                    source_path: Some("/foo".into()),
-                    rights: None,
-                    ..fdecl::Directory::EMPTY
-                }),
-                fdecl::Capability::Service(fdecl::Service {
-                    name: None,
-                    source_path: Some("/svc/foo".into()),
-                    ..fdecl::Service::EMPTY
-                }),
-                fdecl::Capability::Runner(fdecl::Runner {
-                    name: None,
-                    source_path:  Some("/foo".into()),
-                    ..fdecl::Runner::EMPTY
-                }),
-                fdecl::Capability::Resolver(fdecl::Resolver {
-                    name: None,
-                    source_path:  Some("/foo".into()),
-                    ..fdecl::Resolver::EMPTY
-                }),
-                fdecl::Capability::Event(fdecl::Event {
-                    name: None,
-                    ..fdecl::Event::EMPTY
-                }),
-                fdecl::Capability::Storage(fdecl::Storage {
-                    name: None,
-                    ..fdecl::Storage::EMPTY
-                }),
-            ],
-            as_builtin = true,
+        test_validate_environment_resolver_scheme_uppercase => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::Realm),
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("HTTP".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
             result = Err(ErrorList::new(vec![
-                Error::missing_field("Protocol", "name"),
-                Error::extraneous_source_path("Protocol", "/svc/foo"),
-                Error::missing_field("Directory", "name"),
-                Error::extraneous_source_path("Directory", "/foo"),
-                Error::missing_field("Directory", "rights"),
-                Error::missing_field("Service", "name"),
-                Error::extraneous_source_path("Service", "/svc/foo"),
-                Error::missing_field("Runner", "name"),
-                Error::extraneous_source_path("Runner", "/foo"),
-                Error::missing_field("Resolver", "name"),
-                Error::extraneous_source_path("Resolver", "/foo"),
-                Error::missing_field("Event", "name"),
-                Error::invalid_capability_type("RuntimeConfig", "capability", "storage"),
+                Error::invalid_field("ResolverRegistration", "scheme"),
+            ])),
+        },
+        test_validate_environment_resolver_scheme_duplicate_case_insensitive => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::Realm),
+                    resolvers: Some(vec![
+                        fdecl::ResolverRegistration {
+                            resolver: Some("pkg_resolver".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("http".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                        fdecl::ResolverRegistration {
+                            resolver: Some("other_resolver".to_string()),
+                            source: Some(fdecl::Ref::Parent(fdecl::ParentRef{})),
+                            scheme: Some("HTTP".to_string()),
+                            ..fdecl::ResolverRegistration::EMPTY
+                        },
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("ResolverRegistration", "scheme"),
+                Error::duplicate_field("ResolverRegistration", "scheme", "HTTP"),
             ])),
         },
+        test_validate_environment_debug_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: None,
+                                source_name: None,
+                                target_name: None,
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("DebugProtocolRegistration", "source"),
+                Error::missing_field("DebugProtocolRegistration", "source_name"),
+                Error::missing_field("DebugProtocolRegistration", "target_name"),
+            ])),
+        },
+        test_validate_environment_debug_log_identifier => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                    name: "a".repeat(101),
+                                    collection: None,
+                                })),
+                                source_name: Some(format!("{}", "a".repeat(101))),
+                                target_name: Some(format!("{}", "b".repeat(101))),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                                source_name: Some("a".to_string()),
+                                target_name: Some(format!("{}", "c".repeat(101))),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long("DebugProtocolRegistration", "source.child.name"),
+                Error::field_too_long("DebugProtocolRegistration", "source_name"),
+                Error::field_too_long("DebugProtocolRegistration", "target_name"),
+                Error::field_too_long("DebugProtocolRegistration", "target_name"),
+            ])),
+        },
+        test_validate_environment_debug_duplicate_target_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                                source_name: Some("fuchsia.logger.Log".to_string()),
+                                target_name: Some("fuchsia.logger.Log".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                                source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                                target_name: Some("fuchsia.logger.Log".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field(
+                    "DebugProtocolRegistration",
+                    "target_name",
+                    "fuchsia.logger.Log",
+                ),
+            ])),
+        },
+        test_validate_environment_debug_log_extraneous => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                    name: "logger".to_string(),
+                                    collection: Some("modular".to_string()),
+                                })),
+                                source_name: Some("fuchsia.logger.Log".to_string()),
+                                target_name: Some("fuchsia.logger.Log".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_field("DebugProtocolRegistration", "source.child.collection"),
+            ])),
+        },
+        test_validate_environment_debug_log_invalid_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                    name: "^bad".to_string(),
+                                    collection: None,
+                                })),
+                                source_name: Some("foo/".to_string()),
+                                target_name: Some("/".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("DebugProtocolRegistration", "source.child.name"),
+                Error::invalid_field("DebugProtocolRegistration", "source_name"),
+                Error::invalid_field("DebugProtocolRegistration", "target_name"),
+            ])),
+        },
+        test_validate_environment_debug_log_invalid_child => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                                    name: "logger".to_string(),
+                                    collection: None,
+                                })),
+                                source_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                                target_name: Some("fuchsia.logger.LegacyLog".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("netstack".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/netstack/stable#meta/netstack.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_child("DebugProtocolRegistration", "source", "logger"),
+
+            ])),
+        },
+        test_validate_environment_debug_source_capability => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![
+                    fdecl::Environment {
+                        name: Some("a".to_string()),
+                        extends: Some(fdecl::EnvironmentExtends::None),
+                        stop_timeout_ms: Some(2),
+                        debug_capabilities:Some(vec![
+                            fdecl::DebugRegistration::Protocol(fdecl::DebugProtocolRegistration {
+                                source: Some(fdecl::Ref::Capability(fdecl::CapabilityRef {
+                                    name: "storage".to_string(),
+                                })),
+                                source_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                                target_name: Some("fuchsia.sys2.StorageAdmin".to_string()),
+                                ..fdecl::DebugProtocolRegistration::EMPTY
+                            }),
+                    ]),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("DebugProtocolRegistration", "source"),
+            ])),
+        },
+
+        // children
+        test_validate_children_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: None,
+                    url: None,
+                    startup: None,
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Child", "name"),
+                Error::missing_field("Child", "url"),
+                Error::missing_field("Child", "startup"),
+                // `on_terminate` is allowed to be None
+            ])),
+        },
+        test_validate_children_invalid_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("^bad".to_string()),
+                    url: Some("bad-scheme&://blah".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Child", "name"),
+                Error::invalid_url("Child", "url", "\"bad-scheme&://blah\": Invalid scheme"),
+            ])),
+        },
+        test_validate_child_relative_url_valid => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("child".to_string()),
+                    url: Some("#meta/child.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Ok(()),
+        },
+        test_validate_child_relative_url_empty_fragment => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("child".to_string()),
+                    url: Some("#".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Child", "url"),
+            ])),
+        },
+        test_validate_children_long_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("a".repeat(1025)),
+                    url: Some(format!("fuchsia-pkg://{}", "a".repeat(4083))),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: Some("a".repeat(1025)),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long_with_max("Child", "name", 100),
+                Error::field_too_long_with_max("Child", "url", 4096),
+                Error::field_too_long_with_max("Child", "environment", 100),
+                Error::invalid_environment("Child", "environment", "a".repeat(1025)),
+            ])),
+        },
+        test_validate_child_references_unknown_env => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child{
+                    name: Some("foo".to_string()),
+                    url: Some("fuchsia-pkg://foo".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    environment: Some("test_env".to_string()),
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_environment("Child", "environment", "test_env"),
+            ])),
+        },
+        // Pins the behavior when the `environments` field is entirely absent (not just empty):
+        // `collect_environment_names` is only invoked for `Some`, so the name set stays empty
+        // and the child's reference must still be reported as unknown.
+        test_validate_child_env_without_environments_block => {
+            input = {
+                fdecl::Component {
+                    children: Some(vec![fdecl::Child{
+                        name: Some("foo".to_string()),
+                        url: Some("fuchsia-pkg://foo".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        environment: Some("env".to_string()),
+                        ..fdecl::Child::EMPTY
+                    }]),
+                    environments: None,
+                    ..new_component_decl()
+                }
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_environment("Child", "environment", "env"),
+            ])),
+        },
+
+        // collections
+        test_validate_collections_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.collections = Some(vec![fdecl::Collection{
+                    name: None,
+                    durability: None,
+                    environment: None,
+                    allowed_offers: None,
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Collection", "name"),
+                Error::missing_field("Collection", "durability"),
+            ])),
+        },
+        test_validate_collections_invalid_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.collections = Some(vec![fdecl::Collection{
+                    name: Some("^bad".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    environment: None,
+                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Collection", "name"),
+            ])),
+        },
+        test_validate_collections_long_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.collections = Some(vec![fdecl::Collection{
+                    name: Some("a".repeat(1025)),
+                    durability: Some(fdecl::Durability::Transient),
+                    environment: None,
+                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long("Collection", "name"),
+            ])),
+        },
+        test_validate_collection_name_shadows_child => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("foo".to_string()),
+                    url: Some("fuchsia-pkg://fuchsia.com/foo#meta/foo.cm".to_string()),
+                    startup: Some(fdecl::StartupMode::Lazy),
+                    on_terminate: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl.collections = Some(vec![fdecl::Collection{
+                    name: Some("foo".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    environment: None,
+                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            // Children and collections share a moniker namespace, so the name is
+            // ambiguous.
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("Collection", "name", "foo"),
+            ])),
+        },
+        test_validate_collection_references_unknown_env => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("foo".to_string()),
+                    durability: Some(fdecl::Durability::Transient),
+                    environment: Some("test_env".to_string()),
+                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_environment("Collection", "environment", "test_env"),
+            ])),
+        },
+        test_validate_single_run_collection_environment => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.environments = Some(vec![fdecl::Environment {
+                    name: Some("test_env".to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::Realm),
+                    ..fdecl::Environment::EMPTY
+                }]);
+                decl.collections = Some(vec![fdecl::Collection {
+                    name: Some("single_run_coll".to_string()),
+                    durability: Some(fdecl::Durability::SingleRun),
+                    environment: Some("test_env".to_string()),
+                    allowed_offers: Some(fdecl::AllowedOffers::StaticOnly),
+                    allow_long_names: None,
+                    ..fdecl::Collection::EMPTY
+                }]);
+                decl
+            },
+            // Assigning an environment to a `SingleRun` collection is valid: each instance
+            // simply resolves and runs in that environment for its single run. Pinned here
+            // because the combination is otherwise unspecified.
+            result = Ok(()),
+        },
+
+        // capabilities
+        test_validate_capabilities_empty => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: None,
+                        source_path: None,
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: None,
+                        source_path: None,
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: None,
+                        source_path: None,
+                        rights: None,
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: None,
+                        source: None,
+                        backing_dir: None,
+                        subdir: None,
+                        storage_id: None,
+                        ..fdecl::Storage::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: None,
+                        source_path: None,
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: None,
+                        source_path: None,
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Service", "name"),
+                Error::missing_field("Service", "source_path"),
+                Error::missing_field("Protocol", "name"),
+                Error::missing_field("Protocol", "source_path"),
+                Error::missing_field("Directory", "name"),
+                Error::missing_field("Directory", "source_path"),
+                Error::missing_field("Directory", "rights"),
+                Error::missing_field("Storage", "source"),
+                Error::missing_field("Storage", "name"),
+                Error::missing_field("Storage", "storage_id"),
+                Error::missing_field("Storage", "backing_dir"),
+                Error::missing_field("Runner", "name"),
+                Error::missing_field("Runner", "source_path"),
+                Error::missing_field("Resolver", "name"),
+                Error::missing_field("Resolver", "source_path"),
+            ])),
+        },
+        test_validate_capabilities_invalid_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("^bad".to_string()),
+                        source_path: Some("&bad".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("^bad".to_string()),
+                        source_path: Some("&bad".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("^bad".to_string()),
+                        source_path: Some("&bad".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("^bad".to_string()),
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "/bad".to_string()
+                        })),
+                        backing_dir: Some("&bad".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("^bad".to_string()),
+                        source_path: Some("&bad".to_string()),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("^bad".to_string()),
+                        source_path: Some("&bad".to_string()),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Service", "name"),
+                Error::invalid_field("Service", "source_path"),
+                Error::invalid_field("Protocol", "name"),
+                Error::invalid_field("Protocol", "source_path"),
+                Error::invalid_field("Directory", "name"),
+                Error::invalid_field("Directory", "source_path"),
+                Error::invalid_field("Storage", "source"),
+                Error::invalid_field("Storage", "name"),
+                Error::invalid_field("Storage", "backing_dir"),
+                Error::invalid_field("Runner", "name"),
+                Error::invalid_field("Runner", "source_path"),
+                Error::invalid_field("Resolver", "name"),
+                Error::invalid_field("Resolver", "source_path"),
+            ])),
+        },
+        test_validate_capabilities_invalid_child => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("foo".to_string()),
+                        source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                            name: "invalid".to_string(),
+                        })),
+                        backing_dir: Some("minfs".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Storage", "source"),
+            ])),
+        },
+        test_validate_storage_backing_dir_equals_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("data".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        backing_dir: Some("data".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            // A storage capability can't name itself as its own backing directory.
+            result = Err(ErrorList::new(vec![
+                Error::invalid_field("Storage", "backing_dir"),
+            ])),
+        },
+        test_validate_capabilities_long_identifiers => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("a".repeat(101)),
+                        source_path: Some(format!("/{}", "c".repeat(1024))),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("a".repeat(101)),
+                        source_path: Some(format!("/{}", "c".repeat(1024))),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("a".repeat(101)),
+                        source_path: Some(format!("/{}", "c".repeat(1024))),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("a".repeat(101)),
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "b".repeat(101),
+                            collection: None,
+                        })),
+                        backing_dir: Some(format!("{}", "c".repeat(101))),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("a".repeat(101)),
+                        source_path: Some(format!("/{}", "c".repeat(1024))),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("a".repeat(101)),
+                        source_path: Some(format!("/{}", "b".repeat(1024))),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::field_too_long_with_max("Service", "name", 100),
+                Error::field_too_long_with_max("Service", "source_path", 1024),
+                Error::field_too_long_with_max("Protocol", "name", 100),
+                Error::field_too_long_with_max("Protocol", "source_path", 1024),
+                Error::field_too_long_with_max("Directory", "name", 100),
+                Error::field_too_long_with_max("Directory", "source_path", 1024),
+                Error::field_too_long_with_max("Storage", "source.child.name", 100),
+                Error::field_too_long_with_max("Storage", "name", 100),
+                Error::field_too_long_with_max("Storage", "backing_dir", 100),
+                Error::field_too_long_with_max("Runner", "name", 100),
+                Error::field_too_long_with_max("Runner", "source_path", 1024),
+                Error::field_too_long_with_max("Resolver", "name", 100),
+                Error::field_too_long_with_max("Resolver", "source_path", 1024),
+            ])),
+        },
+        test_validate_capabilities_duplicate_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.capabilities = Some(vec![
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("service".to_string()),
+                        source_path: Some("/service".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Service(fdecl::Service {
+                        name: Some("service".to_string()),
+                        source_path: Some("/service".to_string()),
+                        ..fdecl::Service::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("protocol".to_string()),
+                        source_path: Some("/protocol".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("protocol".to_string()),
+                        source_path: Some("/protocol".to_string()),
+                        ..fdecl::Protocol::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("directory".to_string()),
+                        source_path: Some("/directory".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Directory(fdecl::Directory {
+                        name: Some("directory".to_string()),
+                        source_path: Some("/directory".to_string()),
+                        rights: Some(fio::Operations::CONNECT),
+                        ..fdecl::Directory::EMPTY
+                    }),
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("storage".to_string()),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        backing_dir: Some("directory".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                    fdecl::Capability::Storage(fdecl::Storage {
+                        name: Some("storage".to_string()),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef{})),
+                        backing_dir: Some("directory".to_string()),
+                        subdir: None,
+                        storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                        ..fdecl::Storage::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("runner".to_string()),
+                        source_path: Some("/runner".to_string()),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Runner(fdecl::Runner {
+                        name: Some("runner".to_string()),
+                        source_path: Some("/runner".to_string()),
+                        ..fdecl::Runner::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("resolver".to_string()),
+                        source_path: Some("/resolver".to_string()),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                    fdecl::Capability::Resolver(fdecl::Resolver {
+                        name: Some("resolver".to_string()),
+                        source_path: Some("/resolver".to_string()),
+                        ..fdecl::Resolver::EMPTY
+                    }),
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("Service", "name", "service"),
+                Error::duplicate_field("Protocol", "name", "protocol"),
+                Error::duplicate_field("Directory", "name", "directory"),
+                Error::duplicate_field("Storage", "name", "storage"),
+                Error::duplicate_field("Runner", "name", "runner"),
+                Error::duplicate_field("Resolver", "name", "resolver"),
+            ])),
+        },
+
+        test_validate_invalid_service_aggregation_missing_filter => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: None,
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_aggregate_offer("source_instance_filter must be set for all aggregate service offers"),
+            ])),
+        },
+
+        test_validate_invalid_service_aggregation_conflicting_filter => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log1".to_string()),
+                        source_instance_filter: Some(vec!["default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log1".to_string()),
+                        source_instance_filter: Some(vec!["default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_aggregate_offer("Conflicting source_instance_filter in aggregate service offer, instance_name 'default' seen in filter lists multiple times"),
+            ])),
+        },
+
+        test_validate_service_aggregation_distinct_sources => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default_a".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default_b".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Ok(()),
+        },
+
+        test_validate_invalid_service_aggregation_duplicate_source => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default_a".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log".to_string()),
+                        source_instance_filter: Some(vec!["default_b".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("OfferService", "target_name", "fuchsia.logger.Log"),
+            ])),
+        },
+
+        test_validate_invalid_service_aggregation_conflicting_source_name => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_a".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.Log".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log2".to_string()),
+                        source_instance_filter: Some(vec!["default2".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Child(fdecl::ChildRef{name: "child_b".to_string(), collection: None})),
+                        source_name: Some("fuchsia.logger.LogAlt".to_string()),
+                        target: Some(fdecl::Ref::Child(
+                            fdecl::ChildRef {
+                                name: "child_c".to_string(),
+                                collection: None,
+                            }
+                        )),
+                        target_name: Some("fuchsia.logger.Log2".to_string()),
+                        source_instance_filter: Some(vec!["default".to_string()]),
+                        ..fdecl::OfferService::EMPTY
+                    })
+                ]);
+                decl.children = Some(vec![
+                    fdecl::Child {
+                        name: Some("child_a".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_b".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                    fdecl::Child {
+                        name: Some("child_c".to_string()),
+                        url: Some("fuchsia-pkg://fuchsia.com/logger/stable#meta/logger.cm".to_string()),
+                        startup: Some(fdecl::StartupMode::Lazy),
+                        on_terminate: None,
+                        environment: None,
+                        ..fdecl::Child::EMPTY
+                    },
+                ]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_aggregate_offer("All aggregate service offers must have the same source_name, saw fuchsia.logger.Log, fuchsia.logger.LogAlt. Use renamed_instances to rename instance names to avoid conflict."),
+            ])),
+        },
+
+        test_validate_resolvers_missing_from_offer => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.offers = Some(vec![fdecl::Offer::Resolver(fdecl::OfferResolver {
+                    source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                    source_name: Some("a".to_string()),
+                    target: Some(fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None })),
+                    target_name: Some("a".to_string()),
+                    ..fdecl::OfferResolver::EMPTY
+                })]);
+                decl.children = Some(vec![fdecl::Child {
+                    name: Some("child".to_string()),
+                    url: Some("test:///child".to_string()),
+                    startup: Some(fdecl::StartupMode::Eager),
+                    on_terminate: None,
+                    environment: None,
+                    ..fdecl::Child::EMPTY
+                }]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_capability("OfferResolver", "source", "a"),
+            ])),
+        },
+        test_validate_resolvers_missing_from_expose => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.exposes = Some(vec![fdecl::Expose::Resolver(fdecl::ExposeResolver {
+                    source: Some(fdecl::Ref::Self_(fdecl::SelfRef {})),
+                    source_name: Some("a".to_string()),
+                    target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                    target_name: Some("a".to_string()),
+                    ..fdecl::ExposeResolver::EMPTY
+                })]);
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::invalid_capability("ExposeResolver", "source", "a"),
+            ])),
+        },
+
+        test_validate_config_missing_config => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: None,
+                    checksum: None,
+                    value_source: None,
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigSchema", "fields"),
+                Error::missing_field("ConfigSchema", "checksum"),
+                Error::missing_field("ConfigSchema", "value_source"),
+            ])),
+        },
+
+        test_validate_config_missing_config_field => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: None,
+                            type_: None,
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigField", "key"),
+                Error::missing_field("ConfigField", "value_type"),
+            ])),
+        },
+
+        test_validate_config_empty_key => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Bool,
+                                parameters: Some(vec![]),
+                                constraints: vec![]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::empty_field("ConfigField", "key"),
+            ])),
+        },
+
+        test_validate_config_duplicate_key => {
+            input = {
+                let mut decl = new_component_decl();
+                let field = fdecl::ConfigField {
+                    key: Some("test".to_string()),
+                    type_: Some(fdecl::ConfigType {
+                        layout: fdecl::ConfigTypeLayout::Bool,
+                        parameters: Some(vec![]),
+                        constraints: vec![]
+                    }),
+                    ..fdecl::ConfigField::EMPTY
+                };
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![field.clone(), field]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("ConfigField", "key", "test"),
+            ])),
+        },
+
+        test_validate_config_bool => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Bool,
+                                parameters: Some(vec![]),
+                                constraints: vec![]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Ok(()),
+        },
+
+        test_validate_config_bool_extra_constraint => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Bool,
+                                parameters: Some(vec![]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_field("ConfigType", "constraints")
+            ])),
+        },
+
+        test_validate_config_bool_missing_parameters => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Bool,
+                                parameters: None,
+                                constraints: vec![]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigType", "parameters")
+            ])),
+        },
+
+        test_validate_config_string => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::String,
+                                parameters: Some(vec![]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Ok(()),
+        },
+
+        test_validate_config_string_missing_parameter => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::String,
+                                parameters: None,
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigType", "parameters")
+            ])),
+        },
+
+        test_validate_config_string_missing_constraint => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::String,
+                                parameters: Some(vec![]),
+                                constraints: vec![]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigType", "constraints")
+            ])),
+        },
+
+        test_validate_config_string_extra_constraint => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::String,
+                                parameters: Some(vec![]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10), fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_field("ConfigType", "constraints")
+            ])),
+        },
+
+        test_validate_config_vector_bool => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Vector,
+                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                    layout: fdecl::ConfigTypeLayout::Bool,
+                                    parameters: Some(vec![]),
+                                    constraints: vec![],
+                                })]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Ok(()),
+        },
+
+        test_validate_config_vector_extra_parameter => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Vector,
+                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                    layout: fdecl::ConfigTypeLayout::Bool,
+                                    parameters: Some(vec![]),
+                                    constraints: vec![],
+                                }), fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                    layout: fdecl::ConfigTypeLayout::Uint8,
+                                    parameters: Some(vec![]),
+                                    constraints: vec![],
+                                })]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_field("ConfigType", "parameters")
+            ])),
+        },
+
+        test_validate_config_vector_missing_parameter => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Vector,
+                                parameters: Some(vec![]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("ConfigType", "parameters")
+            ])),
+        },
+
+        test_validate_config_vector_string => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Vector,
+                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                    layout: fdecl::ConfigTypeLayout::String,
+                                    parameters: Some(vec![]),
+                                    constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                                })]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Ok(()),
+        },
+
+        test_validate_config_vector_vector => {
+            input = {
+                let mut decl = new_component_decl();
+                decl.config = Some(fdecl::ConfigSchema{
+                    fields: Some(vec![
+                        fdecl::ConfigField {
+                            key: Some("test".to_string()),
+                            type_: Some(fdecl::ConfigType {
+                                layout: fdecl::ConfigTypeLayout::Vector,
+                                parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                    layout: fdecl::ConfigTypeLayout::Vector,
+                                    parameters: Some(vec![fdecl::LayoutParameter::NestedType(fdecl::ConfigType {
+                                        layout: fdecl::ConfigTypeLayout::String,
+                                        parameters: Some(vec![]),
+                                        constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                                    })]),
+                                    constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                                })]),
+                                constraints: vec![fdecl::LayoutConstraint::MaxSize(10)]
+                            }),
+                            ..fdecl::ConfigField::EMPTY
+                        }
+                    ]),
+                    checksum: Some(fdecl::ConfigChecksum::Sha256([0; 32])),
+                    value_source: Some(fdecl::ConfigValueSource::PackagePath("config/test.cvf".to_string())),
+                    ..fdecl::ConfigSchema::EMPTY
+                });
+                decl
+            },
+            result = Err(ErrorList::new(vec![
+                Error::nested_vector()
+            ])),
+        },
+    }
+
+    test_validate_capabilities! {
+        test_validate_capabilities_individually_ok => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_svc".into()),
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("foo_dir".into()),
+                    source_path: Some("/foo".into()),
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+            ],
+            as_builtin = false,
+            result = Ok(()),
+        },
+        test_validate_capabilities_individually_err => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: None,
+                    source_path: None,
+                    ..fdecl::Protocol::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: None,
+                    source_path: None,
+                    rights: None,
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Event(fdecl::Event {
+                    name: None,
+                    ..fdecl::Event::EMPTY
+                }),
+            ],
+            as_builtin = false,
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Protocol", "name"),
+                Error::missing_field("Protocol", "source_path"),
+                Error::missing_field("Directory", "name"),
+                Error::missing_field("Directory", "source_path"),
+                Error::missing_field("Directory", "rights"),
+                Error::invalid_capability_type("Component", "capability", "event")
+            ])),
+        },
+        test_validate_capabilities_source_path_overlap => {
+            input = vec![
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("data".into()),
+                    source_path: Some("/data".into()),
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("data_sub".into()),
+                    source_path: Some("/data/sub".into()),
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_svc".into()),
+                    source_path: Some("/data/fuchsia.example.Foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+            ],
+            as_builtin = false,
+            result = Err(ErrorList::new(vec![
+                Error::invalid_path_overlap(
+                    "Directory", "/data", "Directory", "/data/sub"),
+                Error::invalid_path_overlap(
+                    "Directory", "/data", "Protocol", "/data/fuchsia.example.Foo"),
+                Error::invalid_path_overlap(
+                    "Directory", "/data/sub", "Protocol", "/data/fuchsia.example.Foo"),
+            ])),
+        },
+        test_validate_capabilities_source_path_aliases_ok => {
+            input = vec![
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("data".into()),
+                    source_path: Some("/data".into()),
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("data_alias".into()),
+                    source_path: Some("/data".into()),
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+            ],
+            as_builtin = false,
+            result = Ok(()),
+        },
+        test_validate_builtin_capabilities_individually_ok => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_protocol".into()),
+                    source_path: None,
+                    ..fdecl::Protocol::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: Some("foo_dir".into()),
+                    source_path: None,
+                    rights: Some(fio::Operations::CONNECT),
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Service(fdecl::Service {
+                    name: Some("foo_svc".into()),
+                    source_path: None,
+                    ..fdecl::Service::EMPTY
+                }),
+                fdecl::Capability::Runner(fdecl::Runner {
+                    name: Some("foo_runner".into()),
+                    source_path: None,
+                    ..fdecl::Runner::EMPTY
+                }),
+                fdecl::Capability::Resolver(fdecl::Resolver {
+                    name: Some("foo_resolver".into()),
+                    source_path: None,
+                    ..fdecl::Resolver::EMPTY
+                }),
+                fdecl::Capability::Event(fdecl::Event {
+                    name: Some("foo_event".into()),
+                    ..fdecl::Event::EMPTY
+                }),
+            ],
+            as_builtin = true,
+            result = Ok(()),
+        },
+        test_validate_builtin_capability_with_source_path => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_protocol".into()),
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+            ],
+            as_builtin = true,
+            // Builtins originate in the framework, so a source path is never meaningful.
+            result = Err(ErrorList::new(vec![
+                Error::extraneous_source_path("Protocol", "/svc/foo"),
+            ])),
+        },
+        test_validate_component_capability_missing_source_path => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_protocol".into()),
+                    source_path: None,
+                    ..fdecl::Protocol::EMPTY
+                }),
+            ],
+            as_builtin = false,
+            // There is no default source path; a component capability must say where it's
+            // served from.
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Protocol", "source_path"),
+            ])),
+        },
+        test_validate_builtin_event_name_collides_with_protocol => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo".into()),
+                    source_path: None,
+                    ..fdecl::Protocol::EMPTY
+                }),
+                fdecl::Capability::Event(fdecl::Event {
+                    name: Some("foo".into()),
+                    ..fdecl::Event::EMPTY
+                }),
+            ],
+            as_builtin = true,
+            // All builtin capability types share one name namespace.
+            result = Err(ErrorList::new(vec![
+                Error::duplicate_field("Event", "name", "foo"),
+            ])),
+        },
+        test_validate_builtin_storage_rejected_with_name => {
+            input = vec![
+                fdecl::Capability::Storage(fdecl::Storage {
+                    name: Some("data".into()),
+                    backing_dir: Some("minfs".into()),
+                    source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                    storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+                    ..fdecl::Storage::EMPTY
+                }),
+            ],
+            as_builtin = true,
+            // The storage declaration's name is carried in the field path so the offending
+            // declaration can be found.
+            result = Err(ErrorList::new(vec![
+                Error::invalid_capability_type("RuntimeConfig", "capability.data", "storage"),
+            ])),
+        },
+        test_validate_builtin_capabilities_individually_err => {
+            input = vec![
+                fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: None,
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+                fdecl::Capability::Directory(fdecl::Directory {
+                    name: None,
+                    source_path: Some("/foo".into()),
+                    rights: None,
+                    ..fdecl::Directory::EMPTY
+                }),
+                fdecl::Capability::Service(fdecl::Service {
+                    name: None,
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Service::EMPTY
+                }),
+                fdecl::Capability::Runner(fdecl::Runner {
+                    name: None,
+                    source_path:  Some("/foo".into()),
+                    ..fdecl::Runner::EMPTY
+                }),
+                fdecl::Capability::Resolver(fdecl::Resolver {
+                    name: None,
+                    source_path:  Some("/foo".into()),
+                    ..fdecl::Resolver::EMPTY
+                }),
+                fdecl::Capability::Event(fdecl::Event {
+                    name: None,
+                    ..fdecl::Event::EMPTY
+                }),
+                fdecl::Capability::Storage(fdecl::Storage {
+                    name: None,
+                    ..fdecl::Storage::EMPTY
+                }),
+            ],
+            as_builtin = true,
+            result = Err(ErrorList::new(vec![
+                Error::missing_field("Protocol", "name"),
+                Error::extraneous_source_path("Protocol", "/svc/foo"),
+                Error::missing_field("Directory", "name"),
+                Error::extraneous_source_path("Directory", "/foo"),
+                Error::missing_field("Directory", "rights"),
+                Error::missing_field("Service", "name"),
+                Error::extraneous_source_path("Service", "/svc/foo"),
+                Error::missing_field("Runner", "name"),
+                Error::extraneous_source_path("Runner", "/foo"),
+                Error::missing_field("Resolver", "name"),
+                Error::extraneous_source_path("Resolver", "/foo"),
+                Error::missing_field("Event", "name"),
+                Error::invalid_capability_type("RuntimeConfig", "capability", "storage"),
+            ])),
+        },
+    }
+
+    fn generate_offer_invalid_source_and_availability_decl(
+        new_offer: impl Fn(fdecl::Ref, fdecl::Availability, &str) -> fdecl::Offer,
+    ) -> fdecl::Component {
+        let mut decl = new_component_decl();
+        decl.children = Some(vec![
+            fdecl::Child {
+                name: Some("source".to_string()),
+                url: Some("fuchsia-pkg://fuchsia.com/source#meta/source.cm".to_string()),
+                startup: Some(fdecl::StartupMode::Lazy),
+                on_terminate: None,
+                environment: None,
+                ..fdecl::Child::EMPTY
+            },
+            fdecl::Child {
+                name: Some("sink".to_string()),
+                url: Some("fuchsia-pkg://fuchsia.com/sink#meta/sink.cm".to_string()),
+                startup: Some(fdecl::StartupMode::Lazy),
+                on_terminate: None,
+                environment: None,
+                ..fdecl::Child::EMPTY
+            },
+        ]);
+        decl.offers = Some(vec![
+            // These offers are fine, offers with a source of parent or void can be
+            // optional.
+            new_offer(
+                fdecl::Ref::Parent(fdecl::ParentRef {}),
+                fdecl::Availability::Required,
+                "fuchsia.examples.Echo0",
+            ),
+            new_offer(
+                fdecl::Ref::Parent(fdecl::ParentRef {}),
+                fdecl::Availability::Optional,
+                "fuchsia.examples.Echo1",
+            ),
+            new_offer(
+                fdecl::Ref::Parent(fdecl::ParentRef {}),
+                fdecl::Availability::SameAsTarget,
+                "fuchsia.examples.Echo2",
+            ),
+            new_offer(
+                fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                fdecl::Availability::Optional,
+                "fuchsia.examples.Echo3",
+            ),
+            // These offers are not fine, offers with a source other than parent or void
+            // must be required.
+            new_offer(
+                fdecl::Ref::Self_(fdecl::SelfRef {}),
+                fdecl::Availability::Optional,
+                "fuchsia.examples.Echo4",
+            ),
+            new_offer(
+                fdecl::Ref::Self_(fdecl::SelfRef {}),
+                fdecl::Availability::SameAsTarget,
+                "fuchsia.examples.Echo5",
+            ),
+            new_offer(
+                fdecl::Ref::Framework(fdecl::FrameworkRef {}),
+                fdecl::Availability::Optional,
+                "fuchsia.examples.Echo6",
+            ),
+            new_offer(
+                fdecl::Ref::Framework(fdecl::FrameworkRef {}),
+                fdecl::Availability::SameAsTarget,
+                "fuchsia.examples.Echo7",
+            ),
+            new_offer(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "source".to_string(), collection: None }),
+                fdecl::Availability::Optional,
+                "fuchsia.examples.Echo8",
+            ),
+            new_offer(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "source".to_string(), collection: None }),
+                fdecl::Availability::SameAsTarget,
+                "fuchsia.examples.Echo9",
+            ),
+            // These offers are also not fine, offers with a source of void must be optional
+            new_offer(
+                fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                fdecl::Availability::Required,
+                "fuchsia.examples.Echo10",
+            ),
+            new_offer(
+                fdecl::Ref::VoidType(fdecl::VoidRef {}),
+                fdecl::Availability::SameAsTarget,
+                "fuchsia.examples.Echo11",
+            ),
+        ]);
+        decl
+    }
+
+    #[test]
+    fn test_validate_dynamic_offers_empty() {
+        assert_eq!(validate_dynamic_offers(&vec![], &fdecl::Component::EMPTY), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_dynamic_offers_okay() {
+        assert_eq!(
+            validate_dynamic_offers(
+                &vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef)),
+                        source_name: Some("thing".to_string()),
+                        target_name: Some("thing".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thang".repeat(26)),
+                        target_name: Some("thang".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung1".repeat(26)),
+                        target_name: Some("thung1".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung2".repeat(26)),
+                        target_name: Some("thung2".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung3".repeat(26)),
+                        target_name: Some("thung3".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung4".repeat(26)),
+                        target_name: Some("thung4".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung5".repeat(26)),
+                        target_name: Some("thung5".repeat(26)),
+                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                            name: "foo".to_string(),
+                            collection: Some("foo".to_string()),
+                        })),
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                ],
+                &fdecl::Component {
+                    capabilities: Some(vec![fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("thing".to_string()),
+                        source_path: Some("/svc/foo".into()),
+                        ..fdecl::Protocol::EMPTY
+                    }),]),
+                    ..fdecl::Component::EMPTY
+                }
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_dynamic_offers_source_dynamic_child() {
+        let offer_from = |source: fdecl::Ref| {
+            vec![fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                dependency_type: Some(fdecl::DependencyType::Strong),
+                source: Some(source),
+                source_name: Some("thing".to_string()),
+                target_name: Some("thing".to_string()),
+                target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "foo".to_string(),
+                    collection: Some("foo".to_string()),
+                })),
+                ..fdecl::OfferProtocol::EMPTY
+            })]
+        };
+
+        // A dynamic child source is fine when the reference names its collection.
+        assert_eq!(
+            validate_dynamic_offers(
+                &offer_from(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "dyn".to_string(),
+                    collection: Some("coll".to_string()),
+                })),
+                &fdecl::Component::EMPTY
+            ),
+            Ok(())
+        );
+
+        // Without a collection the reference can't identify a dynamic child.
+        assert_eq!(
+            validate_dynamic_offers(
+                &offer_from(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "dyn".to_string(),
+                    collection: None,
+                })),
+                &fdecl::Component::EMPTY
+            ),
+            Err(ErrorList::new(vec![Error::invalid_field(
+                "OfferProtocol",
+                "source.child.collection"
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_validate_dynamic_storage_offer_with_target() {
+        // Dynamic offers carry a `target` like static ones do; storage offers are no
+        // exception.
+        assert_eq!(
+            validate_dynamic_offers(
+                &vec![fdecl::Offer::Storage(fdecl::OfferStorage {
+                    source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                    source_name: Some("data".to_string()),
+                    target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                        name: "foo".to_string(),
+                        collection: Some("coll".to_string()),
+                    })),
+                    target_name: Some("data".to_string()),
+                    ..fdecl::OfferStorage::EMPTY
+                })],
+                &fdecl::Component::EMPTY,
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_dynamic_event_offer_with_target() {
+        assert_eq!(
+            validate_dynamic_offers(
+                &vec![fdecl::Offer::Event(fdecl::OfferEvent {
+                    source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                    source_name: Some("started".to_string()),
+                    target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                        name: "foo".to_string(),
+                        collection: Some("coll".to_string()),
+                    })),
+                    target_name: Some("started".to_string()),
+                    ..fdecl::OfferEvent::EMPTY
+                })],
+                &fdecl::Component::EMPTY,
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_dynamic_offers_omit_target() {
+        assert_eq!(
+            validate_dynamic_offers(
+                &vec![
+                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef)),
+                        source_name: Some("thing".to_string()),
+                        target_name: Some("thing".to_string()),
+                        ..fdecl::OfferProtocol::EMPTY
+                    }),
+                    fdecl::Offer::Service(fdecl::OfferService {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thang".to_string()),
+                        target_name: Some("thang".to_string()),
+                        ..fdecl::OfferService::EMPTY
+                    }),
+                    fdecl::Offer::Directory(fdecl::OfferDirectory {
+                        dependency_type: Some(fdecl::DependencyType::Strong),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung1".to_string()),
+                        target_name: Some("thung1".to_string()),
+                        ..fdecl::OfferDirectory::EMPTY
+                    }),
+                    fdecl::Offer::Storage(fdecl::OfferStorage {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung2".to_string()),
+                        target_name: Some("thung2".to_string()),
+                        ..fdecl::OfferStorage::EMPTY
+                    }),
+                    fdecl::Offer::Runner(fdecl::OfferRunner {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung3".to_string()),
+                        target_name: Some("thung3".to_string()),
+                        ..fdecl::OfferRunner::EMPTY
+                    }),
+                    fdecl::Offer::Resolver(fdecl::OfferResolver {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung4".to_string()),
+                        target_name: Some("thung4".to_string()),
+                        ..fdecl::OfferResolver::EMPTY
+                    }),
+                    fdecl::Offer::Event(fdecl::OfferEvent {
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
+                        source_name: Some("thung5".to_string()),
+                        target_name: Some("thung5".to_string()),
+                        ..fdecl::OfferEvent::EMPTY
+                    }),
+                ],
+                &fdecl::Component {
+                    capabilities: Some(vec![fdecl::Capability::Protocol(fdecl::Protocol {
+                        name: Some("thing".to_string()),
+                        source_path: Some("/svc/foo".into()),
+                        ..fdecl::Protocol::EMPTY
+                    }),]),
+                    ..fdecl::Component::EMPTY
+                }
+            ),
+            Err(ErrorList::new(vec![
+                Error::missing_field("OfferProtocol", "target"),
+                Error::missing_field("OfferService", "target"),
+                Error::missing_field("OfferDirectory", "target"),
+                Error::missing_field("OfferStorage", "target"),
+                Error::missing_field("OfferRunner", "target"),
+                Error::missing_field("OfferResolver", "target"),
+                Error::missing_field("OfferEvent", "target"),
+            ]))
+        );
     }
 
-    fn generate_offer_invalid_source_and_availability_decl(
-        new_offer: impl Fn(fdecl::Ref, fdecl::Availability, &str) -> fdecl::Offer,
-    ) -> fdecl::Component {
+    #[test]
+    fn test_build_dependency_graph() {
+        let decl = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/pkg#meta/a.cm")
+            .child("b", "fuchsia-pkg://fuchsia.com/pkg#meta/b.cm")
+            .offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "a".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "b".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+            )
+            .build()
+            .expect("failed to build component decl");
+        let edges = build_dependency_graph(&decl).expect("failed to build dependency graph");
+        assert_eq!(edges, vec![("child a".to_string(), "child b".to_string())]);
+    }
+
+    #[test]
+    fn test_component_decl_builder() {
+        // A valid decl builds successfully.
+        let decl = ComponentDeclBuilder::new()
+            .child("logger", "fuchsia-pkg://fuchsia.com/logger#meta/logger.cm")
+            .collection("coll", fdecl::Durability::Transient)
+            .use_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "logger".to_string(),
+                    collection: None,
+                }),
+                "fuchsia.logger.Log",
+                "/svc/fuchsia.logger.Log",
+            )
+            .build()
+            .expect("failed to build component decl");
+        assert_eq!(decl.children.as_ref().unwrap().len(), 1);
+        assert_eq!(decl.collections.as_ref().unwrap().len(), 1);
+        assert_eq!(decl.uses.as_ref().unwrap().len(), 1);
+
+        // `build` reports validation errors; `build_unvalidated` does not.
+        let builder = || {
+            ComponentDeclBuilder::new().offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "a".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "b".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+            )
+        };
+        assert_eq!(
+            builder().build(),
+            Err(ErrorList::new(vec![
+                Error::invalid_child("OfferProtocol", "source", "a"),
+                Error::invalid_child("OfferProtocol", "target", "b"),
+            ]))
+        );
+        assert!(validate(&builder().build_unvalidated()).is_err());
+    }
+
+    #[test]
+    fn test_validate_all() {
+        let valid = new_component_decl();
+        let mut invalid = new_component_decl();
+        invalid.children = Some(vec![fdecl::Child {
+            name: None,
+            url: None,
+            startup: None,
+            on_terminate: None,
+            environment: None,
+            ..fdecl::Child::EMPTY
+        }]);
+        let failures =
+            validate_all(vec![("meta/valid.cm", &valid), ("meta/invalid.cm", &invalid)]);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(
+            failures.get("meta/invalid.cm"),
+            Some(&ErrorList::new(vec![
+                Error::missing_field("Child", "name"),
+                Error::missing_field("Child", "url"),
+                Error::missing_field("Child", "startup"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_validate_reboot_on_terminate_allowlist() {
         let mut decl = new_component_decl();
-        decl.children = Some(vec![
+        decl.children = Some(vec![fdecl::Child {
+            name: Some("critical".to_string()),
+            url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/critical.cm".to_string()),
+            startup: Some(fdecl::StartupMode::Lazy),
+            on_terminate: Some(fdecl::OnTerminate::Reboot),
+            environment: None,
+            ..fdecl::Child::EMPTY
+        }]);
+
+        // Without an allowlist `on_terminate` is not checked.
+        assert_eq!(validate(&decl), Ok(()));
+
+        // An allowlist that contains the child's name accepts the decl.
+        let allowed = ValidationOptions {
+            reboot_on_terminate_allowlist: Some(
+                vec!["critical".to_string()].into_iter().collect(),
+            ),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(validate_with_options(&decl, allowed), Ok(()));
+
+        // An allowlist without the child's name rejects it.
+        let disallowed = ValidationOptions {
+            reboot_on_terminate_allowlist: Some(HashSet::new()),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_with_options(&decl, disallowed),
+            Err(ErrorList::new(vec![Error::on_terminate_not_allowed(
+                "Child",
+                "on_terminate",
+                "critical"
+            )]))
+        );
+
+        // A child that doesn't set `on_terminate` isn't subject to the allowlist.
+        decl.children.as_mut().unwrap()[0].on_terminate = None;
+        let empty_allowlist = ValidationOptions {
+            reboot_on_terminate_allowlist: Some(HashSet::new()),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(validate_with_options(&decl, empty_allowlist), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_count_chars_url_length() {
+        // ~2100 two-byte characters: over the 4096 limit in bytes, under it in code points.
+        let url = format!("fuchsia-pkg://fuchsia.com/{}", "\u{00fc}".repeat(2100));
+        let decl = ComponentDeclBuilder::new().child("child", &url).build_unvalidated();
+
+        // Measured in bytes, the URL is too long.
+        let errors = validate(&decl).unwrap_err().errs;
+        assert!(errors.contains(&Error::field_too_long_with_max("Child", "url", 4096)));
+
+        // Measured in code points, it fits.
+        let count_chars =
+            ValidationOptions { count_chars: true, ..ValidationOptions::default() };
+        let errors = match validate_with_options(&decl, count_chars) {
+            Ok(()) => vec![],
+            Err(errors) => errors.errs,
+        };
+        assert!(!errors.iter().any(|error| error.code() == "field_too_long"));
+    }
+
+    #[test]
+    fn test_validator_revalidate_sections() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child", "fuchsia-pkg://fuchsia.com/child#meta/child.cm")
+            .offer_protocol(
+                fdecl::Ref::Parent(fdecl::ParentRef {}),
+                "fuchsia.foo.Bar",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "missing".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+            )
+            .build_unvalidated();
+        decl.uses = Some(vec![fdecl::Use::Protocol(fdecl::UseProtocol {
+            dependency_type: Some(fdecl::DependencyType::Strong),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            source_name: Some("fuchsia.baz.Qux".to_string()),
+            target_path: Some("bad-path".to_string()),
+            ..fdecl::UseProtocol::EMPTY
+        })]);
+        decl.exposes = Some(vec![fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+            source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                name: "also-missing".to_string(),
+                collection: None,
+            })),
+            source_name: Some("fuchsia.foo.Bar".to_string()),
+            target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            target_name: Some("fuchsia.foo.Bar".to_string()),
+            ..fdecl::ExposeProtocol::EMPTY
+        })]);
+
+        let use_error = Error::invalid_field("UseProtocol", "target_path");
+        let offer_error = Error::invalid_child("OfferProtocol", "target", "missing");
+        let expose_error = Error::invalid_child("ExposeProtocol", "source", "also-missing");
+
+        // The full run reports all three.
+        let full = validate(&decl).unwrap_err();
+        for error in [&use_error, &offer_error, &expose_error] {
+            assert!(full.contains(error), "full validation missing {:?}", error);
+        }
+
+        // Each section validator reports exactly its section's share, repeatably.
+        let mut validator = Validator::with_decl(&decl);
+        for _ in 0..2 {
+            assert_eq!(
+                validator.revalidate_uses(),
+                Err(ErrorList::new(vec![use_error.clone()]))
+            );
+            assert_eq!(
+                validator.revalidate_offers(),
+                Err(ErrorList::new(vec![offer_error.clone()]))
+            );
+            assert_eq!(
+                validator.revalidate_exposes(),
+                Err(ErrorList::new(vec![expose_error.clone()]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_and_report() {
+        let decl = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .build_unvalidated();
+        assert_eq!(validate_and_report(&decl), Ok(()));
+
+        let mut decl = new_component_decl();
+        decl.children = Some(vec![fdecl::Child {
+            name: Some("^bad".to_string()),
+            url: None,
+            startup: None,
+            ..fdecl::Child::EMPTY
+        }]);
+        assert_eq!(
+            validate_and_report(&decl),
+            Err("Found 3 validation errors.\n\
+                 \n\
+                 Structure:\n  \
+                 Field `startup` is missing for Child.\n  \
+                 Field `url` is missing for Child.\n\
+                 \n\
+                 Naming:\n  \
+                 Field `name` for Child is invalid.\n\
+                 \n\
+                 Error counts by code:\n  \
+                 invalid_field: 1\n  \
+                 missing_field: 2\n"
+                .to_string()),
+        );
+    }
+
+    #[test]
+    fn test_validate_max_decls() {
+        let decl = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("b", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .child("c", "fuchsia-pkg://fuchsia.com/c#meta/c.cm")
+            .build_unvalidated();
+
+        // Under the limit, validation proceeds normally.
+        let options = ValidationOptions { max_decls: Some(3), ..ValidationOptions::default() };
+        assert_eq!(validate_with_options(&decl, options), Ok(()));
+
+        // Over the limit, the only error is the guard; per-entry checks never run.
+        let options = ValidationOptions { max_decls: Some(2), ..ValidationOptions::default() };
+        assert_eq!(
+            validate_with_options(&decl, options),
+            Err(ErrorList::new(vec![Error::too_many_declarations(3)])),
+        );
+    }
+
+    #[test]
+    fn test_validate_max_path_depth() {
+        let subdir = vec!["a"; 500].join("/");
+        let mut decl = new_component_decl();
+        decl.uses = Some(vec![fdecl::Use::Directory(fdecl::UseDirectory {
+            dependency_type: Some(fdecl::DependencyType::Strong),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            source_name: Some("config".to_string()),
+            target_path: Some("/config".to_string()),
+            rights: Some(fio::Operations::CONNECT),
+            subdir: Some(subdir),
+            ..fdecl::UseDirectory::EMPTY
+        })]);
+
+        // Depth is unlimited by default.
+        assert_eq!(validate(&decl), Ok(()));
+
+        // Under the limit, a 500-segment subdir is rejected.
+        let options = ValidationOptions { max_path_depth: Some(32), ..ValidationOptions::default() };
+        assert_eq!(
+            validate_with_options(&decl, options),
+            Err(ErrorList::new(vec![Error::field_too_deep("UseDirectory", "subdir", 32)])),
+        );
+    }
+
+    #[test]
+    fn test_validate_strict_path_overlap() {
+        let mut decl = new_component_decl();
+        decl.capabilities = Some(vec![fdecl::Capability::Directory(fdecl::Directory {
+            name: Some("data".to_string()),
+            source_path: Some("/data".to_string()),
+            rights: Some(fio::Operations::CONNECT),
+            ..fdecl::Directory::EMPTY
+        })]);
+        decl.uses = Some(vec![fdecl::Use::Directory(fdecl::UseDirectory {
+            dependency_type: Some(fdecl::DependencyType::Strong),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            source_name: Some("config".to_string()),
+            target_path: Some("/data/sub".to_string()),
+            rights: Some(fio::Operations::CONNECT),
+            subdir: None,
+            ..fdecl::UseDirectory::EMPTY
+        })]);
+
+        // Each section is valid on its own, so the default checks pass.
+        assert_eq!(validate(&decl), Ok(()));
+
+        // The strict pass sees the program's namespace collide with its own outgoing
+        // directory.
+        let strict =
+            ValidationOptions { strict_path_overlap: true, ..ValidationOptions::default() };
+        assert_eq!(
+            validate_with_options(&decl, strict),
+            Err(ErrorList::new(vec![Error::invalid_path_overlap(
+                "Directory",
+                "/data",
+                "UseDirectory",
+                "/data/sub",
+            )])),
+        );
+    }
+
+    #[test]
+    fn test_validate_stop_on_first_error() {
+        // Three children sharing a name: a full run reports each extra occurrence.
+        let decl = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("a", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .child("a", "fuchsia-pkg://fuchsia.com/c#meta/c.cm")
+            .build_unvalidated();
+        let errors = validate(&decl).unwrap_err().errs;
+        assert!(errors.len() > 1);
+
+        // With the fast path, validation ends at the first error.
+        let stop = ValidationOptions { stop_on_first_error: true, ..ValidationOptions::default() };
+        let errors = validate_with_options(&decl, stop).unwrap_err().errs;
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_case_insensitive_names() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("Col", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .child("col", "fuchsia-pkg://fuchsia.com/bar#meta/bar.cm")
+            .build_unvalidated();
+        decl.collections = Some(vec![
+            fdecl::Collection {
+                name: Some("Shelf".to_string()),
+                durability: Some(fdecl::Durability::Transient),
+                ..fdecl::Collection::EMPTY
+            },
+            fdecl::Collection {
+                name: Some("shelf".to_string()),
+                durability: Some(fdecl::Durability::Transient),
+                ..fdecl::Collection::EMPTY
+            },
+        ]);
+
+        // By default names are only compared exactly.
+        assert_eq!(validate(&decl), Ok(()));
+
+        let case_insensitive = ValidationOptions {
+            case_insensitive_names: true,
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_with_options(&decl, case_insensitive.clone()),
+            Err(ErrorList::new(vec![
+                Error::duplicate_field("Child", "name", "col"),
+                Error::duplicate_field("Collection", "name", "shelf"),
+            ]))
+        );
+
+        // Exact duplicates are reported once, by the exact check.
+        decl.collections = None;
+        decl.children.as_mut().unwrap()[1].name = Some("Col".to_string());
+        assert_eq!(
+            validate_with_options(&decl, case_insensitive),
+            Err(ErrorList::new(vec![Error::duplicate_field_at_index("Child", "name", "Col", 0, 1)]))
+        );
+    }
+
+    #[test]
+    fn test_validate_collection() {
+        assert_eq!(
+            validate_collection(&fdecl::Collection {
+                name: Some("coll".to_string()),
+                durability: Some(fdecl::Durability::Transient),
+                ..fdecl::Collection::EMPTY
+            }),
+            Ok(())
+        );
+        assert_eq!(
+            validate_collection(&fdecl::Collection {
+                name: Some("coll".to_string()),
+                durability: None,
+                ..fdecl::Collection::EMPTY
+            }),
+            Err(ErrorList::new(vec![Error::missing_field("Collection", "durability")]))
+        );
+        assert_eq!(
+            validate_collection(&fdecl::Collection {
+                name: Some("^bad".to_string()),
+                durability: Some(fdecl::Durability::Transient),
+                ..fdecl::Collection::EMPTY
+            }),
+            Err(ErrorList::new(vec![Error::invalid_field("Collection", "name")]))
+        );
+    }
+
+    #[test]
+    fn test_validate_facets() {
+        let entry = |key: &str| fdata::DictionaryEntry {
+            key: key.to_string(),
+            value: Some(Box::new(fdata::DictionaryValue::Str("v".to_string()))),
+        };
+        let mut decl = new_component_decl();
+        decl.facets = Some(fdata::Dictionary {
+            entries: Some(vec![entry("fuchsia.test"), entry("other")]),
+            ..fdata::Dictionary::EMPTY
+        });
+        assert_eq!(validate(&decl), Ok(()));
+
+        decl.facets = Some(fdata::Dictionary {
+            entries: Some(vec![entry("fuchsia.test"), entry(""), entry("fuchsia.test")]),
+            ..fdata::Dictionary::EMPTY
+        });
+        assert_eq!(
+            validate(&decl),
+            Err(ErrorList::new(vec![
+                Error::empty_field("ComponentDecl", "facets.key"),
+                Error::duplicate_field("ComponentDecl", "facets.key", "fuchsia.test"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_validate_offers() {
+        let children = vec![
             fdecl::Child {
-                name: Some("source".to_string()),
-                url: Some("fuchsia-pkg://fuchsia.com/source#meta/source.cm".to_string()),
+                name: Some("child1".to_string()),
+                url: Some("fuchsia-pkg://fuchsia.com/foo".to_string()),
                 startup: Some(fdecl::StartupMode::Lazy),
-                on_terminate: None,
-                environment: None,
                 ..fdecl::Child::EMPTY
             },
             fdecl::Child {
-                name: Some("sink".to_string()),
-                url: Some("fuchsia-pkg://fuchsia.com/sink#meta/sink.cm".to_string()),
+                name: Some("child2".to_string()),
+                url: Some("fuchsia-pkg://fuchsia.com/foo2".to_string()),
                 startup: Some(fdecl::StartupMode::Lazy),
-                on_terminate: None,
-                environment: None,
                 ..fdecl::Child::EMPTY
             },
-        ]);
-        decl.offers = Some(vec![
-            // These offers are fine, offers with a source of parent or void can be
-            // optional.
-            new_offer(
-                fdecl::Ref::Parent(fdecl::ParentRef {}),
-                fdecl::Availability::Required,
-                "fuchsia.examples.Echo0",
-            ),
-            new_offer(
-                fdecl::Ref::Parent(fdecl::ParentRef {}),
-                fdecl::Availability::Optional,
-                "fuchsia.examples.Echo1",
-            ),
-            new_offer(
-                fdecl::Ref::Parent(fdecl::ParentRef {}),
-                fdecl::Availability::SameAsTarget,
-                "fuchsia.examples.Echo2",
-            ),
-            new_offer(
-                fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                fdecl::Availability::Optional,
-                "fuchsia.examples.Echo3",
-            ),
-            // These offers are not fine, offers with a source other than parent or void
-            // must be required.
-            new_offer(
-                fdecl::Ref::Self_(fdecl::SelfRef {}),
-                fdecl::Availability::Optional,
-                "fuchsia.examples.Echo4",
+        ];
+        let offer = |source: &str, source_name: &str, target: &str| {
+            fdecl::Offer::Service(fdecl::OfferService {
+                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: source.to_string(),
+                    collection: None,
+                })),
+                source_name: Some(source_name.to_string()),
+                target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: target.to_string(),
+                    collection: None,
+                })),
+                target_name: Some(source_name.to_string()),
+                ..fdecl::OfferService::EMPTY
+            })
+        };
+
+        // An acyclic offer between declared children is accepted.
+        assert_eq!(validate_offers(&vec![offer("child1", "a", "child2")], &children, &[]), Ok(()));
+
+        // An offer to an undeclared child is rejected.
+        assert_eq!(
+            validate_offers(&vec![offer("child1", "a", "missing")], &children, &[]),
+            Err(ErrorList::new(vec![Error::invalid_child("OfferService", "target", "missing")]))
+        );
+
+        // Mutual offers between the two children form a strong dependency cycle.
+        assert_eq!(
+            validate_offers(
+                &vec![offer("child1", "a", "child2"), offer("child2", "b", "child1")],
+                &children,
+                &[],
             ),
-            new_offer(
+            Err(ErrorList::new(vec![Error::dependency_cycle(
+                "{{child child1 --(a)--> child child2 --(b)--> child child1}}".to_string()
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let sorted = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("b", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .build_unvalidated();
+        let mut decl = ComponentDeclBuilder::new()
+            .child("b", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("b", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .build_unvalidated();
+
+        // Sorts entries and removes the exact duplicate.
+        normalize(&mut decl);
+        assert_eq!(decl, sorted);
+
+        // Normalizing is idempotent.
+        normalize(&mut decl);
+        assert_eq!(decl, sorted);
+
+        // Conflicting (non-equal) entries that share a name are kept for `validate` to report.
+        let mut decl = ComponentDeclBuilder::new()
+            .child("a", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("a", "fuchsia-pkg://fuchsia.com/other#meta/other.cm")
+            .build_unvalidated();
+        normalize(&mut decl);
+        assert_eq!(decl.children.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_validate_dependencies() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child1", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .child("child2", "fuchsia-pkg://fuchsia.com/bar#meta/bar.cm")
+            .offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None }),
+                "a",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None }),
+                "a",
+            )
+            .offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None }),
+                "b",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None }),
+                "b",
+            )
+            .build_unvalidated();
+        // An unrelated structural error, which only full validation reports.
+        decl.uses = Some(vec![fdecl::Use::Protocol(fdecl::UseProtocol {
+            dependency_type: Some(fdecl::DependencyType::Strong),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            source_name: Some("fuchsia.foo.Bar".to_string()),
+            target_path: Some("bad-path".to_string()),
+            ..fdecl::UseProtocol::EMPTY
+        })]);
+
+        let cycle_error = Error::dependency_cycle(
+            "{{child child1 --(a)--> child child2 --(b)--> child child1}}".to_string(),
+        );
+        assert_eq!(validate_dependencies(&decl), Err(ErrorList::new(vec![cycle_error.clone()])));
+        assert_eq!(
+            validate(&decl),
+            Err(ErrorList::new(vec![
+                Error::invalid_field("UseProtocol", "target_path"),
+                cycle_error,
+            ]))
+        );
+
+        // Breaking the cycle clears the topology check.
+        decl.offers.as_mut().unwrap().pop();
+        assert_eq!(validate_dependencies(&decl), Ok(()));
+    }
+
+    #[test]
+    fn test_list_routes() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child", "fuchsia-pkg://fuchsia.com/child#meta/child.cm")
+            .offer_protocol(
                 fdecl::Ref::Self_(fdecl::SelfRef {}),
-                fdecl::Availability::SameAsTarget,
-                "fuchsia.examples.Echo5",
-            ),
-            new_offer(
-                fdecl::Ref::Framework(fdecl::FrameworkRef {}),
-                fdecl::Availability::Optional,
-                "fuchsia.examples.Echo6",
-            ),
-            new_offer(
-                fdecl::Ref::Framework(fdecl::FrameworkRef {}),
-                fdecl::Availability::SameAsTarget,
-                "fuchsia.examples.Echo7",
-            ),
-            new_offer(
-                fdecl::Ref::Child(fdecl::ChildRef { name: "source".to_string(), collection: None }),
-                fdecl::Availability::Optional,
-                "fuchsia.examples.Echo8",
-            ),
-            new_offer(
-                fdecl::Ref::Child(fdecl::ChildRef { name: "source".to_string(), collection: None }),
-                fdecl::Availability::SameAsTarget,
-                "fuchsia.examples.Echo9",
-            ),
-            // These offers are also not fine, offers with a source of void must be optional
-            new_offer(
-                fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                fdecl::Availability::Required,
-                "fuchsia.examples.Echo10",
-            ),
-            new_offer(
-                fdecl::Ref::VoidType(fdecl::VoidRef {}),
-                fdecl::Availability::SameAsTarget,
-                "fuchsia.examples.Echo11",
-            ),
+                "fuchsia.foo.Bar",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child".to_string(), collection: None }),
+                "fuchsia.foo.Bar",
+            )
+            .build_unvalidated();
+        decl.exposes = Some(vec![fdecl::Expose::Directory(fdecl::ExposeDirectory {
+            source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                name: "child".to_string(),
+                collection: None,
+            })),
+            source_name: Some("assets".to_string()),
+            target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            target_name: Some("assets".to_string()),
+            ..fdecl::ExposeDirectory::EMPTY
+        })]);
+
+        assert_eq!(
+            list_routes(&decl),
+            vec![
+                Route {
+                    kind: CapabilityKind::Protocol,
+                    name: "fuchsia.foo.Bar".to_string(),
+                    source: Some(DependencyNode::Self_),
+                    targets: vec![DependencyNode::Child("child", None)],
+                },
+                Route {
+                    kind: CapabilityKind::Directory,
+                    name: "assets".to_string(),
+                    source: Some(DependencyNode::Child("child", None)),
+                    // The expose's target is the parent, which isn't a graph node.
+                    targets: vec![],
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn test_validate_cycle_starts_at_smallest_node() {
+        // The same cycle declared in both orders, so the traversal is free to discover it
+        // from either end. Both must format starting from the smallest node.
+        let decl = |first: &str, second: &str| {
+            ComponentDeclBuilder::new()
+                .child("child1", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+                .child("child2", "fuchsia-pkg://fuchsia.com/bar#meta/bar.cm")
+                .offer_protocol(
+                    fdecl::Ref::Child(fdecl::ChildRef {
+                        name: first.to_string(),
+                        collection: None,
+                    }),
+                    "thing",
+                    fdecl::Ref::Child(fdecl::ChildRef {
+                        name: second.to_string(),
+                        collection: None,
+                    }),
+                    "thing",
+                )
+                .offer_protocol(
+                    fdecl::Ref::Child(fdecl::ChildRef {
+                        name: second.to_string(),
+                        collection: None,
+                    }),
+                    "thing",
+                    fdecl::Ref::Child(fdecl::ChildRef {
+                        name: first.to_string(),
+                        collection: None,
+                    }),
+                    "thing",
+                )
+                .build_unvalidated()
+        };
+        let forward = validate_dependencies(&decl("child1", "child2")).unwrap_err();
+        let backward = validate_dependencies(&decl("child2", "child1")).unwrap_err();
+        let cycle_string = |errors: &ErrorList| match &errors.errs[..] {
+            [Error::DependencyCycle(message)] => message.clone(),
+            other => panic!("expected a single cycle error, got {:?}", other),
+        };
+        assert!(cycle_string(&forward).contains("{child child1 "));
+        assert_eq!(cycle_string(&forward), cycle_string(&backward));
+    }
+
+    #[test]
+    fn test_validate_weak_protocol_offer_to_collection_no_edge() {
+        // A weak protocol offer into the collection, and a strong service offer back out of
+        // it. If the weak offer contributed a strong edge this would be a dependency cycle,
+        // so this pins that the weak-skip path also applies to collection targets.
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child1", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .collection("coll", fdecl::Durability::Transient)
+            .build_unvalidated();
+        decl.offers = Some(vec![
+            fdecl::Offer::Protocol(fdecl::OfferProtocol {
+                source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "child1".to_string(),
+                    collection: None,
+                })),
+                source_name: Some("fuchsia.foo.Bar".to_string()),
+                target: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                    name: "coll".to_string(),
+                })),
+                target_name: Some("fuchsia.foo.Bar".to_string()),
+                dependency_type: Some(fdecl::DependencyType::Weak),
+                ..fdecl::OfferProtocol::EMPTY
+            }),
+            fdecl::Offer::Service(fdecl::OfferService {
+                source: Some(fdecl::Ref::Collection(fdecl::CollectionRef {
+                    name: "coll".to_string(),
+                })),
+                source_name: Some("fuchsia.foo.Svc".to_string()),
+                target: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                    name: "child1".to_string(),
+                    collection: None,
+                })),
+                target_name: Some("fuchsia.foo.Svc".to_string()),
+                ..fdecl::OfferService::EMPTY
+            }),
         ]);
-        decl
+        assert_eq!(validate(&decl), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_cycle_first() {
+        // A dependency cycle alongside an ordinary structural error.
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child1", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .child("child2", "fuchsia-pkg://fuchsia.com/bar#meta/bar.cm")
+            .offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None }),
+                "a",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None }),
+                "a",
+            )
+            .offer_protocol(
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child2".to_string(), collection: None }),
+                "b",
+                fdecl::Ref::Child(fdecl::ChildRef { name: "child1".to_string(), collection: None }),
+                "b",
+            )
+            .build_unvalidated();
+        decl.children.as_mut().unwrap().push(fdecl::Child {
+            name: Some("broken".to_string()),
+            url: None,
+            startup: Some(fdecl::StartupMode::Lazy),
+            ..fdecl::Child::EMPTY
+        });
+
+        // Cycle detection runs last, so by default the cycle error trails the list.
+        let errors = validate(&decl).unwrap_err().errs;
+        assert!(errors.len() > 1);
+        assert!(matches!(errors.last(), Some(Error::DependencyCycle(_))));
+
+        // With the option it leads, and the rest keep their discovery order.
+        let options = ValidationOptions { cycle_first: true, ..ValidationOptions::default() };
+        let reordered = validate_with_options(&decl, options).unwrap_err().errs;
+        assert!(matches!(reordered.first(), Some(Error::DependencyCycle(_))));
+        assert_eq!(reordered[1..], errors[..errors.len() - 1]);
+    }
+
+    #[test]
+    fn test_validate_with_spans() {
+        let decl = fdecl::Component {
+            children: Some(vec![fdecl::Child {
+                name: Some("child".to_string()),
+                url: Some("fuchsia-pkg://fuchsia.com/pkg#meta/child.cm".to_string()),
+                ..fdecl::Child::EMPTY
+            }]),
+            ..new_component_decl()
+        };
+        let mut spans = SpanMap::new();
+        spans.insert("Child", "startup", Span { start: 10, end: 20 });
+
+        let spanned = validate_with_spans(&decl, &spans).unwrap_err();
+        assert_eq!(
+            spanned.errs,
+            vec![(Error::missing_field("Child", "startup"), Some(Span { start: 10, end: 20 }))]
+        );
+
+        // Fields without a recorded span still report, just without location info.
+        let spanned = validate_with_spans(&decl, &SpanMap::new()).unwrap_err();
+        assert_eq!(spanned.errs, vec![(Error::missing_field("Child", "startup"), None)]);
     }
 
     #[test]
-    fn test_validate_dynamic_offers_empty() {
-        assert_eq!(validate_dynamic_offers(&vec![], &fdecl::Component::EMPTY), Ok(()));
+    fn test_validate_named() {
+        let decl = fdecl::Component {
+            children: Some(vec![fdecl::Child { ..fdecl::Child::EMPTY }]),
+            ..new_component_decl()
+        };
+        let errors = validate_named("meta/foo.cm", &decl).unwrap_err();
+        assert!(format!("{}", errors).starts_with("meta/foo.cm: "));
+
+        assert_eq!(validate_named("meta/foo.cm", &new_component_decl()), Ok(()));
     }
 
     #[test]
-    fn test_validate_dynamic_offers_okay() {
+    fn test_validate_with_sink() {
+        let decl = ComponentDeclBuilder::new()
+            .child("dup", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .child("dup", "fuchsia-pkg://fuchsia.com/b#meta/b.cm")
+            .build_unvalidated();
+
+        let mut streamed = Vec::new();
+        let result = validate_with_sink(&decl, &mut |error| streamed.push(error.clone()));
+
+        // The sink sees exactly the errors the result holds, in order.
+        let errors = result.unwrap_err().errs;
+        assert!(!errors.is_empty());
+        assert_eq!(streamed, errors);
+
+        // A valid decl never invokes the sink.
+        let decl = ComponentDeclBuilder::new()
+            .child("ok", "fuchsia-pkg://fuchsia.com/a#meta/a.cm")
+            .build_unvalidated();
+        let mut calls = 0;
+        assert_eq!(validate_with_sink(&decl, &mut |_| calls += 1), Ok(()));
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn test_validate_detailed() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("logger", "fuchsia-pkg://fuchsia.com/logger#meta/logger.cm")
+            .child("netstack", "fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm")
+            .build_unvalidated();
+        decl.capabilities = Some(vec![
+            fdecl::Capability::Protocol(fdecl::Protocol {
+                name: Some("fuchsia.fonts.Provider".to_string()),
+                source_path: Some("/svc/fuchsia.fonts.Provider".to_string()),
+                ..fdecl::Protocol::EMPTY
+            }),
+            fdecl::Capability::Directory(fdecl::Directory {
+                name: Some("assets".to_string()),
+                source_path: Some("/assets".to_string()),
+                rights: Some(fio::Operations::CONNECT),
+                ..fdecl::Directory::EMPTY
+            }),
+        ]);
+
+        let report = validate_detailed(&decl);
+        assert_eq!(report.errors, vec![]);
+        assert_eq!(report.warnings, vec![]);
         assert_eq!(
-            validate_dynamic_offers(
-                &vec![
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef)),
-                        source_name: Some("thing".to_string()),
-                        target_name: Some("thing".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thang".repeat(26)),
-                        target_name: Some("thang".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung1".repeat(26)),
-                        target_name: Some("thung1".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferDirectory::EMPTY
-                    }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung2".repeat(26)),
-                        target_name: Some("thung2".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferStorage::EMPTY
-                    }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung3".repeat(26)),
-                        target_name: Some("thung3".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferRunner::EMPTY
-                    }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung4".repeat(26)),
-                        target_name: Some("thung4".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferResolver::EMPTY
-                    }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung5".repeat(26)),
-                        target_name: Some("thung5".repeat(26)),
-                        target: Some(fdecl::Ref::Child(fdecl::ChildRef {
-                            name: "foo".to_string(),
-                            collection: Some("foo".to_string()),
-                        })),
-                        ..fdecl::OfferEvent::EMPTY
-                    }),
-                ],
-                &fdecl::Component {
-                    capabilities: Some(vec![fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("thing".to_string()),
-                        source_path: Some("/svc/foo".into()),
-                        ..fdecl::Protocol::EMPTY
-                    }),]),
-                    ..fdecl::Component::EMPTY
-                }
+            report.capabilities,
+            vec!["assets".to_string(), "fuchsia.fonts.Provider".to_string()]
+        );
+        assert_eq!(report.children, vec!["logger".to_string(), "netstack".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_capability() {
+        assert_eq!(
+            validate_capability(
+                &fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("foo_svc".into()),
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+                false
             ),
             Ok(())
         );
+        assert_eq!(
+            validate_capability(
+                &fdecl::Capability::Protocol(fdecl::Protocol {
+                    name: Some("".into()),
+                    source_path: Some("/svc/foo".into()),
+                    ..fdecl::Protocol::EMPTY
+                }),
+                false
+            ),
+            Err(ErrorList::new(vec![Error::empty_field("Protocol", "name")]))
+        );
     }
 
     #[test]
-    fn test_validate_dynamic_offers_omit_target() {
+    fn test_validation_options_presets() {
+        // `strict()` catches the case-insensitive child collision the default set allows.
+        let decl = ComponentDeclBuilder::new()
+            .child("Col", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .child("col", "fuchsia-pkg://fuchsia.com/bar#meta/bar.cm")
+            .build_unvalidated();
+        assert_eq!(validate_with_options(&decl, ValidationOptions::for_component()), Ok(()));
         assert_eq!(
-            validate_dynamic_offers(
-                &vec![
-                    fdecl::Offer::Protocol(fdecl::OfferProtocol {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Self_(fdecl::SelfRef)),
-                        source_name: Some("thing".to_string()),
-                        target_name: Some("thing".to_string()),
-                        ..fdecl::OfferProtocol::EMPTY
-                    }),
-                    fdecl::Offer::Service(fdecl::OfferService {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thang".to_string()),
-                        target_name: Some("thang".to_string()),
-                        ..fdecl::OfferService::EMPTY
-                    }),
-                    fdecl::Offer::Directory(fdecl::OfferDirectory {
-                        dependency_type: Some(fdecl::DependencyType::Strong),
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung1".to_string()),
-                        target_name: Some("thung1".to_string()),
-                        ..fdecl::OfferDirectory::EMPTY
-                    }),
-                    fdecl::Offer::Storage(fdecl::OfferStorage {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung2".to_string()),
-                        target_name: Some("thung2".to_string()),
-                        ..fdecl::OfferStorage::EMPTY
-                    }),
-                    fdecl::Offer::Runner(fdecl::OfferRunner {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung3".to_string()),
-                        target_name: Some("thung3".to_string()),
-                        ..fdecl::OfferRunner::EMPTY
-                    }),
-                    fdecl::Offer::Resolver(fdecl::OfferResolver {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung4".to_string()),
-                        target_name: Some("thung4".to_string()),
-                        ..fdecl::OfferResolver::EMPTY
-                    }),
-                    fdecl::Offer::Event(fdecl::OfferEvent {
-                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef)),
-                        source_name: Some("thung5".to_string()),
-                        target_name: Some("thung5".to_string()),
-                        ..fdecl::OfferEvent::EMPTY
-                    }),
-                ],
-                &fdecl::Component {
-                    capabilities: Some(vec![fdecl::Capability::Protocol(fdecl::Protocol {
-                        name: Some("thing".to_string()),
-                        source_path: Some("/svc/foo".into()),
-                        ..fdecl::Protocol::EMPTY
-                    }),]),
-                    ..fdecl::Component::EMPTY
-                }
-            ),
-            Err(ErrorList::new(vec![
-                Error::missing_field("OfferProtocol", "target"),
-                Error::missing_field("OfferService", "target"),
-                Error::missing_field("OfferDirectory", "target"),
-                Error::missing_field("OfferStorage", "target"),
-                Error::missing_field("OfferRunner", "target"),
-                Error::missing_field("OfferResolver", "target"),
-                Error::missing_field("OfferEvent", "target"),
-            ]))
+            validate_with_options(&decl, ValidationOptions::strict()),
+            Err(ErrorList::new(vec![Error::duplicate_field("Child", "name", "col")])),
+        );
+
+        // `for_builtin()` accepts builtin storage declarations.
+        let capabilities = vec![fdecl::Capability::Storage(fdecl::Storage {
+            name: Some("data".to_string()),
+            backing_dir: Some("minfs".to_string()),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+            ..fdecl::Storage::EMPTY
+        })];
+        assert!(validate_capabilities(&capabilities, true).is_err());
+        let builtin = ValidationOptions::for_builtin();
+        assert_eq!(validate_capabilities_with_options(&capabilities, true, builtin), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_allow_builtin_storage() {
+        let capabilities = vec![fdecl::Capability::Storage(fdecl::Storage {
+            name: Some("data".to_string()),
+            backing_dir: Some("minfs".to_string()),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+            ..fdecl::Storage::EMPTY
+        })];
+
+        // Rejected as a builtin by default.
+        assert_eq!(
+            validate_capabilities(&capabilities, true),
+            Err(ErrorList::new(vec![Error::invalid_capability_type(
+                "RuntimeConfig",
+                "capability.data",
+                "storage",
+            )])),
+        );
+
+        // With the flag, the declaration is validated like any other storage capability.
+        let options =
+            ValidationOptions { allow_builtin_storage: true, ..ValidationOptions::default() };
+        assert_eq!(validate_capabilities_with_options(&capabilities, true, options), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_empty_environment_warning() {
+        let mut decl = new_component_decl();
+        decl.environments = Some(vec![fdecl::Environment {
+            name: Some("env".to_string()),
+            extends: Some(fdecl::EnvironmentExtends::None),
+            stop_timeout_ms: Some(1234),
+            ..fdecl::Environment::EMPTY
+        }]);
+
+        // The decl is valid, but the useless environment is reported as a warning.
+        let (result, warnings) = validate_with_warnings(&decl);
+        assert_eq!(result, Ok(()));
+        assert_eq!(warnings, vec![Error::empty_environment("Environment", "extends")]);
+
+        // Registering a resolver silences the warning.
+        decl.environments.as_mut().unwrap()[0].resolvers = Some(vec![fdecl::ResolverRegistration {
+            resolver: Some("pkg_resolver".to_string()),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            scheme: Some("fuchsia-pkg".to_string()),
+            ..fdecl::ResolverRegistration::EMPTY
+        }]);
+        let (result, warnings) = validate_with_warnings(&decl);
+        assert_eq!(result, Ok(()));
+        assert_eq!(warnings, vec![]);
+
+        // `validate` is unaffected by warnings.
+        assert_eq!(validate(&decl), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_extraneous_as_warning() {
+        let mut decl = ComponentDeclBuilder::new()
+            .child("child1", "fuchsia-pkg://fuchsia.com/foo#meta/foo.cm")
+            .build_unvalidated();
+        decl.exposes = Some(vec![fdecl::Expose::Protocol(fdecl::ExposeProtocol {
+            source: Some(fdecl::Ref::Child(fdecl::ChildRef {
+                name: "child1".to_string(),
+                collection: Some("coll".to_string()),
+            })),
+            source_name: Some("fuchsia.foo.Bar".to_string()),
+            target: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            target_name: Some("fuchsia.foo.Bar".to_string()),
+            ..fdecl::ExposeProtocol::EMPTY
+        })]);
+        let expected = Error::extraneous_field("ExposeProtocol", "source.child.collection");
+
+        // By default the extraneous `collection` is fatal.
+        assert_eq!(validate(&decl), Err(ErrorList::new(vec![expected.clone()])));
+
+        // As a warning, the field is still flagged but validation passes.
+        let options =
+            ValidationOptions { extraneous_as_warning: true, ..ValidationOptions::default() };
+        assert_eq!(validate_with_options_and_warnings(&decl, options), (Ok(()), vec![expected]));
+    }
+
+    #[test]
+    fn test_validate_duplicate_child_reports_indices() {
+        let decl = ComponentDeclBuilder::new()
+            .child("logger", "fuchsia-pkg://fuchsia.com/logger#meta/logger.cm")
+            .child("netstack", "fuchsia-pkg://fuchsia.com/netstack#meta/netstack.cm")
+            .child("logger", "fuchsia-pkg://fuchsia.com/logger2#meta/logger.cm")
+            .build_unvalidated();
+        assert_eq!(
+            validate(&decl),
+            Err(ErrorList::new(vec![Error::duplicate_field_at_index(
+                "Child", "name", "logger", 0, 2
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_validate_api_level_deprecates_events() {
+        let mut decl = new_component_decl();
+        decl.uses = Some(vec![fdecl::Use::Event(fdecl::UseEvent {
+            dependency_type: Some(fdecl::DependencyType::Strong),
+            source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+            source_name: Some("started".to_string()),
+            target_name: Some("started".to_string()),
+            ..fdecl::UseEvent::EMPTY
+        })]);
+
+        // Without an API level, or below the deprecation level, events are accepted.
+        assert_eq!(validate(&decl), Ok(()));
+        let below = ValidationOptions {
+            api_level: Some(EVENT_DEPRECATION_API_LEVEL - 1),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(validate_with_options(&decl, below), Ok(()));
+
+        // At the deprecation level the use is rejected.
+        let at = ValidationOptions {
+            api_level: Some(EVENT_DEPRECATION_API_LEVEL),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_with_options(&decl, at),
+            Err(ErrorList::new(vec![Error::deprecated_capability_type(
+                "Component", "use", "event"
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_validate_event_stream_order_independent() {
+        // Many event/stream pairs, validated under every rotation of the `uses` list, so
+        // streams regularly precede the events they subscribe to. Validation relies on a
+        // two-pass design — events are collected before any stream is checked — and this
+        // guards that invariant against a refactor that inlines the loops.
+        let mut uses = Vec::new();
+        for i in 0..10 {
+            uses.push(fdecl::Use::Event(fdecl::UseEvent {
+                dependency_type: Some(fdecl::DependencyType::Strong),
+                source: Some(fdecl::Ref::Framework(fdecl::FrameworkRef {})),
+                source_name: Some("started".to_string()),
+                target_name: Some(format!("started_{}", i)),
+                filter: None,
+                ..fdecl::UseEvent::EMPTY
+            }));
+            uses.push(fdecl::Use::EventStreamDeprecated(fdecl::UseEventStreamDeprecated {
+                name: Some(format!("stream_{}", i)),
+                subscriptions: Some(vec![fdecl::EventSubscription {
+                    event_name: Some(format!("started_{}", i)),
+                    ..fdecl::EventSubscription::EMPTY
+                }]),
+                ..fdecl::UseEventStreamDeprecated::EMPTY
+            }));
+        }
+        for rotation in 0..uses.len() {
+            let mut rotated = uses.clone();
+            rotated.rotate_left(rotation);
+            let mut decl = new_component_decl();
+            decl.uses = Some(rotated);
+            assert_eq!(validate(&decl), Ok(()), "rotation {}", rotation);
+        }
+    }
+
+    #[test]
+    fn test_validate_reject_unknown_use_variant() {
+        let mut decl = new_component_decl();
+        decl.uses = Some(vec![fdecl::Use::unknown(0x1234, vec![])]);
+
+        // By default an unknown use variant is reported generically.
+        assert_eq!(
+            validate(&decl),
+            Err(ErrorList::new(vec![Error::invalid_field("Component", "use")]))
+        );
+
+        // With `reject_unknown` the error names the list and the unknown ordinal.
+        let options = ValidationOptions { reject_unknown: true, ..ValidationOptions::default() };
+        assert_eq!(
+            validate_with_options(&decl, options),
+            Err(ErrorList::new(vec![Error::unknown_variant("Component", "use", 0x1234)]))
+        );
+    }
+
+    #[test]
+    fn test_validate_known_storage_names() {
+        let mut decl = new_component_decl();
+        decl.uses = Some(vec![fdecl::Use::Storage(fdecl::UseStorage {
+            source_name: Some("data".to_string()),
+            target_path: Some("/data".to_string()),
+            ..fdecl::UseStorage::EMPTY
+        })]);
+
+        // Without the option the storage name is not cross-checked.
+        assert_eq!(validate(&decl), Ok(()));
+
+        // The use is accepted when the name is in the provided set.
+        let known = ValidationOptions {
+            known_storage_names: Some(vec!["data".to_string()].into_iter().collect()),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(validate_with_options(&decl, known), Ok(()));
+
+        // Nothing provides "data": the use is rejected.
+        let unknown = ValidationOptions {
+            known_storage_names: Some(vec!["cache".to_string()].into_iter().collect()),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            validate_with_options(&decl, unknown),
+            Err(ErrorList::new(vec![Error::invalid_storage(
+                "UseStorage",
+                "source_name",
+                "data"
+            )]))
+        );
+
+        // The component's own storage declarations also satisfy the check.
+        decl.capabilities = Some(vec![fdecl::Capability::Storage(fdecl::Storage {
+            name: Some("data".to_string()),
+            source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+            backing_dir: Some("minfs".to_string()),
+            storage_id: Some(fdecl::StorageId::StaticInstanceIdOrMoniker),
+            ..fdecl::Storage::EMPTY
+        })]);
+        let empty = ValidationOptions {
+            known_storage_names: Some(HashSet::new()),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(validate_with_options(&decl, empty), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_strict_runner_names() {
+        let mut decl = new_component_decl();
+        decl.environments = Some(
+            ["env_a", "env_b"]
+                .iter()
+                .map(|name| fdecl::Environment {
+                    name: Some(name.to_string()),
+                    extends: Some(fdecl::EnvironmentExtends::Realm),
+                    runners: Some(vec![fdecl::RunnerRegistration {
+                        source_name: Some("elf".to_string()),
+                        source: Some(fdecl::Ref::Parent(fdecl::ParentRef {})),
+                        target_name: Some("elf".to_string()),
+                        ..fdecl::RunnerRegistration::EMPTY
+                    }]),
+                    ..fdecl::Environment::EMPTY
+                })
+                .collect(),
+        );
+
+        // By default the same runner name may be registered by different environments.
+        assert_eq!(validate(&decl), Ok(()));
+
+        let strict =
+            ValidationOptions { strict_runner_names: true, ..ValidationOptions::default() };
+        assert_eq!(
+            validate_with_options(&decl, strict),
+            Err(ErrorList::new(vec![Error::duplicate_field(
+                "RunnerRegistration",
+                "target_name",
+                "elf"
+            )]))
         );
     }
 
@@ -9159,6 +14199,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_dynamic_child_rejects_eager() {
+        assert_eq!(
+            validate_dynamic_child(&fdecl::Child {
+                name: Some("a".to_string()),
+                url: Some("test:///child".to_string()),
+                startup: Some(fdecl::StartupMode::Eager),
+                on_terminate: None,
+                environment: None,
+                ..fdecl::Child::EMPTY
+            }),
+            Err(ErrorList::new(vec![Error::invalid_field("Child", "startup")]))
+        );
+    }
+
     #[test]
     fn test_validate_dynamic_offers_missing_stuff() {
         assert_eq!(
@@ -5,6 +5,7 @@
 use {crate::error::*, fidl_fuchsia_component_decl as fdecl, std::collections::HashMap};
 
 const MAX_PATH_LENGTH: usize = 1024;
+const MAX_PATH_SEGMENT_LENGTH: usize = 255;
 const MAX_URL_LENGTH: usize = 4096;
 pub const MAX_NAME_LENGTH: usize = 100;
 pub const MAX_DYNAMIC_NAME_LENGTH: usize = 1024;
@@ -27,6 +28,14 @@ pub(crate) enum TargetId<'a> {
     Collection(&'a str),
 }
 
+/// The target of an expose declaration. Exposes with the same target name don't conflict
+/// unless they are exposed to the same place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ExposeTarget {
+    Parent,
+    Framework,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum OfferType {
     Static,
@@ -42,9 +51,23 @@ pub(crate) fn check_presence_and_length(
     keyword: &str,
     errors: &mut Vec<Error>,
 ) {
+    check_presence_and_length_impl(max_len, prop, decl_type, keyword, false, errors)
+}
+
+fn check_presence_and_length_impl(
+    max_len: usize,
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    count_chars: bool,
+    errors: &mut Vec<Error>,
+) {
+    // Limits are measured in bytes unless the caller asked for Unicode code points; see
+    // `ValidationOptions::count_chars`.
+    let length = |prop: &str| if count_chars { prop.chars().count() } else { prop.len() };
     match prop {
-        Some(prop) if prop.len() == 0 => errors.push(Error::empty_field(decl_type, keyword)),
-        Some(prop) if prop.len() > max_len => {
+        Some(prop) if prop.is_empty() => errors.push(Error::empty_field(decl_type, keyword)),
+        Some(prop) if length(prop) > max_len => {
             errors.push(Error::field_too_long_with_max(decl_type, keyword, max_len))
         }
         Some(_) => (),
@@ -57,6 +80,28 @@ pub(crate) fn check_path(
     decl_type: &str,
     keyword: &str,
     errors: &mut Vec<Error>,
+) -> bool {
+    check_path_impl(prop, decl_type, keyword, None, errors)
+}
+
+/// Like [`check_path`], but also rejects paths with more than `max_depth` segments; see
+/// `ValidationOptions::max_path_depth`.
+pub(crate) fn check_path_with_depth(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    max_depth: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> bool {
+    check_path_impl(prop, decl_type, keyword, max_depth, errors)
+}
+
+fn check_path_impl(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    max_depth: Option<usize>,
+    errors: &mut Vec<Error>,
 ) -> bool {
     let start_err_len = errors.len();
     check_presence_and_length(MAX_PATH_LENGTH, prop, decl_type, keyword, errors);
@@ -81,6 +126,23 @@ pub(crate) fn check_path(
             errors.push(Error::invalid_field(decl_type, keyword));
             return false;
         }
+        // Individual path segments are subject to a separate, shorter limit than the
+        // path as a whole. Skipped if the total length is already out of bounds so one
+        // oversized string isn't reported twice.
+        if start_err_len == errors.len()
+            && path.split('/').any(|segment| segment.len() > MAX_PATH_SEGMENT_LENGTH)
+        {
+            errors.push(Error::field_too_long_with_max(
+                decl_type,
+                keyword,
+                MAX_PATH_SEGMENT_LENGTH,
+            ));
+            return false;
+        }
+        if let Some(max_depth) = exceeds_max_depth(path, max_depth) {
+            errors.push(Error::field_too_deep(decl_type, keyword, max_depth));
+            return false;
+        }
     }
     start_err_len == errors.len()
 }
@@ -90,13 +152,35 @@ pub(crate) fn check_relative_path(
     decl_type: &str,
     keyword: &str,
     errors: &mut Vec<Error>,
+) -> bool {
+    check_relative_path_impl(prop, decl_type, keyword, None, errors)
+}
+
+/// Like [`check_relative_path`], but also rejects paths with more than `max_depth` segments;
+/// see `ValidationOptions::max_path_depth`.
+pub(crate) fn check_relative_path_with_depth(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    max_depth: Option<usize>,
+    errors: &mut Vec<Error>,
+) -> bool {
+    check_relative_path_impl(prop, decl_type, keyword, max_depth, errors)
+}
+
+fn check_relative_path_impl(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    max_depth: Option<usize>,
+    errors: &mut Vec<Error>,
 ) -> bool {
     let start_err_len = errors.len();
     check_presence_and_length(MAX_PATH_LENGTH, prop, decl_type, keyword, errors);
     if let Some(path) = prop {
-        // Relative paths must be nonempty
+        // Relative paths must be nonempty; `check_presence_and_length` has already reported
+        // `empty_field`, so don't pile an `invalid_field` on top.
         if path.is_empty() {
-            errors.push(Error::invalid_field(decl_type, keyword));
             return false;
         }
         // Relative paths cannot start with `/`
@@ -114,10 +198,37 @@ pub(crate) fn check_relative_path(
             errors.push(Error::invalid_field(decl_type, keyword));
             return false;
         }
+        // Individual path segments are subject to a separate, shorter limit than the
+        // path as a whole. Skipped if the total length is already out of bounds so one
+        // oversized string isn't reported twice.
+        if start_err_len == errors.len()
+            && path.split('/').any(|segment| segment.len() > MAX_PATH_SEGMENT_LENGTH)
+        {
+            errors.push(Error::field_too_long_with_max(
+                decl_type,
+                keyword,
+                MAX_PATH_SEGMENT_LENGTH,
+            ));
+            return false;
+        }
+        if let Some(max_depth) = exceeds_max_depth(path, max_depth) {
+            errors.push(Error::field_too_deep(decl_type, keyword, max_depth));
+            return false;
+        }
     }
     start_err_len == errors.len()
 }
 
+/// Returns the violated limit if `path` has more `/`-separated segments than `max_depth`
+/// allows, and `None` otherwise. A `max_depth` of `None` means unlimited. Leading and trailing
+/// separators contribute empty segments, which are not counted; those shapes are rejected
+/// separately by the path checks.
+fn exceeds_max_depth(path: &str, max_depth: Option<usize>) -> Option<usize> {
+    max_depth.filter(|max_depth| {
+        path.split('/').filter(|segment| !segment.is_empty()).count() > *max_depth
+    })
+}
+
 pub(crate) fn check_dynamic_name(
     prop: Option<&String>,
     decl_type: &str,
@@ -160,6 +271,11 @@ fn check_name_impl(
                 invalid_field = true;
             }
         }
+        // `.` is only allowed as an interior character; a trailing dot is confusing in
+        // contexts where names are joined into paths.
+        if name.ends_with('.') {
+            invalid_field = true;
+        }
     }
     if invalid_field {
         errors.push(Error::invalid_field(decl_type, keyword));
@@ -210,9 +326,30 @@ pub(crate) fn check_url(
     decl_type: &str,
     keyword: &str,
     errors: &mut Vec<Error>,
+) -> bool {
+    check_url_impl(prop, decl_type, keyword, false, errors)
+}
+
+/// Like [`check_url`], but measures the URL against `MAX_URL_LENGTH` in Unicode code points
+/// rather than bytes; see `ValidationOptions::count_chars`.
+pub(crate) fn check_url_chars(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    errors: &mut Vec<Error>,
+) -> bool {
+    check_url_impl(prop, decl_type, keyword, true, errors)
+}
+
+fn check_url_impl(
+    prop: Option<&String>,
+    decl_type: &str,
+    keyword: &str,
+    count_chars: bool,
+    errors: &mut Vec<Error>,
 ) -> bool {
     let start_err_len = errors.len();
-    check_presence_and_length(MAX_URL_LENGTH, prop, decl_type, keyword, errors);
+    check_presence_and_length_impl(MAX_URL_LENGTH, prop, decl_type, keyword, count_chars, errors);
     if start_err_len == errors.len() {
         if let Some(url_str) = prop {
             if let Err(err) = cm_types::Url::validate(url_str) {
@@ -247,10 +384,9 @@ pub(crate) fn check_url_scheme(
                         Error::field_too_long(decl_type, keyword)
                     }
                 }
-                cm_types::ParseError::InvalidValue => Error::invalid_field(decl_type, keyword),
-                e => {
-                    panic!("unexpected parse error: {:?}", e);
-                }
+                // Any other parse error still means the scheme is unusable; report it
+                // rather than panicking, since input may be adversarial.
+                _ => Error::invalid_field(decl_type, keyword),
             });
             return false;
         }
@@ -266,7 +402,7 @@ mod tests {
     use {super::*, lazy_static::lazy_static, proptest::prelude::*, regex::Regex, url::Url};
 
     const PATH_REGEX_STR: &str = r"(/[^/]+)+";
-    const NAME_REGEX_STR: &str = r"[0-9a-zA-Z_][0-9a-zA-Z_\-\.]*";
+    const NAME_REGEX_STR: &str = r"[0-9a-zA-Z_]([0-9a-zA-Z_\-\.]*[0-9a-zA-Z_\-])?";
     const URL_REGEX_STR: &str = r"((([a-z][0-9a-z\+\-\.]*://[0-9a-z\+\-\._!$&,;]*/)?[0-9a-z\+\-\._/=!@$&,;]+)?#[0-9a-z\+\-\._/?=!@$&,;:]+)";
 
     lazy_static! {
@@ -318,6 +454,13 @@ mod tests {
                 prop_assert!(!errors.is_empty());
             }
         }
+        #[test]
+        fn check_url_scheme_no_panic(s in ".*") {
+            // Schemes may come from untrusted manifests; arbitrary input must be reported
+            // as an error, never a panic.
+            let mut errors = vec![];
+            let _ = check_url_scheme(Some(&s), "", "", &mut errors);
+        }
         // NOTE: The Url crate's parser is used to validate legal URLs. Testing
         // random strings against component URL validation is redundant, so
         // a `check_url_fails_invalid_input` is not necessary (and would be
@@ -416,6 +559,16 @@ mod tests {
             input = &format!("/{}", "a".repeat(1024)),
             result = Err(ErrorList::new(vec![Error::field_too_long_with_max("FooDecl", "foo", /*max=*/1024usize)])),
         },
+        test_identifier_path_segment_too_long => {
+            check_fn = check_path,
+            input = &format!("/{}", "a".repeat(256)),
+            result = Err(ErrorList::new(vec![Error::field_too_long_with_max("FooDecl", "foo", /*max=*/255usize)])),
+        },
+        test_identifier_path_segment_too_long_under_total => {
+            check_fn = check_path,
+            input = &format!("/foo/{}/bar", "a".repeat(256)),
+            result = Err(ErrorList::new(vec![Error::field_too_long_with_max("FooDecl", "foo", /*max=*/255usize)])),
+        },
 
         // name
         test_identifier_dynamic_name_valid => {
@@ -425,9 +578,14 @@ mod tests {
         },
         test_identifier_name_valid => {
             check_fn = check_name,
-            input = "abcdefghijklmnopqrstuvwxyz0123456789_-.",
+            input = "abcdefghijklmnopqrstuvwxyz0123456789_-.x",
             result = Ok(()),
         },
+        test_identifier_name_invalid_trailing_dot => {
+            check_fn = check_name,
+            input = "my.capability.",
+            result = Err(ErrorList::new(vec![Error::invalid_field("FooDecl", "foo")])),
+        },
         test_identifier_name_invalid => {
             check_fn = check_name,
             input = "^bad",